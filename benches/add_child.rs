@@ -0,0 +1,95 @@
+//! Time to build a dense DAG edge by edge, `cargo bench --bench add_child`.
+//! `add_child` checks the cycles against the topological order kept by the
+//! graph, the search from the child it did before is timed for comparison.
+
+use std::{
+  collections::{
+    HashSet,
+    VecDeque,
+  },
+  time::{
+    Duration,
+    Instant,
+  },
+};
+
+use dag::AcyclicGraph;
+use rand::{
+  Rng,
+  SeedableRng,
+  rngs::StdRng,
+  seq::SliceRandom,
+};
+use uuid::Uuid;
+
+const EDGES_PER_NODE: usize = 4;
+
+// random edges between nodes numbered in a topological order, shuffled
+fn edges(nodes: usize, rng: &mut StdRng) -> Vec<(usize, usize)> {
+  let mut edges = HashSet::new();
+  while edges.len() < nodes * EDGES_PER_NODE {
+    let a = rng.random_range(0..nodes);
+    let b = rng.random_range(0..nodes);
+    if a != b {
+      edges.insert((a.min(b), a.max(b)));
+    }
+  }
+  let mut edges: Vec<(usize, usize)> = edges.into_iter().collect();
+  edges.sort_unstable();
+  edges.shuffle(rng);
+  edges
+}
+
+// the cycle check of add_child before the topological order
+fn reaches(graph: &AcyclicGraph, from: Uuid, to: Uuid) -> bool {
+  let mut queue = VecDeque::from([from]);
+  let mut queued = HashSet::from([from]);
+  while let Some(current) = queue.pop_front() {
+    if current == to {
+      return true;
+    }
+    for &child in graph.nodes()[&current].childs() {
+      if queued.insert(child) {
+        queue.push_back(child);
+      }
+    }
+  }
+  false
+}
+
+// the nodes are added in `order` then the edges, only the edges are timed
+fn build(order: &[usize], edges: &[(usize, usize)], search: bool) -> Duration {
+  let mut graph = AcyclicGraph::new("bench");
+  let mut uuids = vec![Uuid::nil(); order.len()];
+  for &i in order {
+    uuids[i] = graph.add_node(i as u64).0;
+  }
+  let start = Instant::now();
+  for &(parent, child) in edges {
+    let (parent, child) = (uuids[parent], uuids[child]);
+    if search {
+      assert!(!reaches(&graph, child, parent));
+    }
+    graph.add_child(parent, child).unwrap();
+  }
+  start.elapsed()
+}
+
+fn main() {
+  let mut rng = StdRng::seed_from_u64(42);
+  println!("nodes added in topological order, like the generator, or shuffled");
+  for nodes in [1_000, 10_000, 100_000] {
+    let edges = edges(nodes, &mut rng);
+    let in_order: Vec<usize> = (0..nodes).collect();
+    let mut shuffled = in_order.clone();
+    shuffled.shuffle(&mut rng);
+    println!(
+      "{nodes:>7} nodes {:>7} edges: in order {:>10.2?}, shuffled {:>10.2?}, with a search from \
+       the child {:>10.2?}",
+      edges.len(),
+      build(&in_order, &edges, false),
+      build(&shuffled, &edges, false),
+      build(&in_order, &edges, true),
+    );
+  }
+}
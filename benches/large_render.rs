@@ -0,0 +1,142 @@
+//! Time and peak memory to build a large DAG then stream its DOT and Mermaid
+//! outputs, `cargo bench --bench large_render`, then of the command line
+//! generating and writing a DOT graph of as many nodes. The number of nodes
+//! is read from `DAG_BENCH_NODES`, 5M by default, the peak RSS comes from
+//! `/proc/<pid>/status` so it's only reported on Linux.
+
+use std::{
+  io,
+  process::{
+    Command,
+    Stdio,
+  },
+  sync::{
+    Arc,
+    Mutex,
+  },
+  thread,
+  time::{
+    Duration,
+    Instant,
+  },
+};
+
+use dag::AcyclicGraph;
+use rand::{
+  Rng,
+  SeedableRng,
+  rngs::StdRng,
+};
+use uuid::Uuid;
+
+const WIDTH: usize = 1_000;
+const PARENTS_PER_NODE: usize = 2;
+
+// levels of WIDTH nodes, each node has a few parents in the level above
+fn build(nodes: usize, rng: &mut StdRng) -> AcyclicGraph {
+  let mut graph = AcyclicGraph::new("bench");
+  let mut above: Vec<Uuid> = Vec::new();
+  let mut level = Vec::with_capacity(WIDTH);
+  for i in 0..nodes {
+    let (uuid, _) = graph.add_node(i as u64);
+    for _ in 0..PARENTS_PER_NODE.min(above.len()) {
+      let parent = above[rng.random_range(0..above.len())];
+      let _ = graph.add_child(parent, uuid);
+    }
+    level.push(uuid);
+    if level.len() == WIDTH {
+      above = std::mem::replace(&mut level, Vec::with_capacity(WIDTH));
+    }
+  }
+  graph
+}
+
+// counts the bytes, io::Sink would skip the formatting
+#[derive(Default)]
+struct Counter(usize);
+
+impl io::Write for Counter {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.0 += buf.len();
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+// the high water mark of the resident memory of a process
+fn peak_rss(process: &str) -> Option<String> {
+  let status = std::fs::read_to_string(format!("/proc/{process}/status")).ok()?;
+  let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+  let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+  Some(format!("{} MiB", kib / 1024))
+}
+
+fn report(step: &str, start: Instant, bytes: usize, peak: Option<String>) {
+  println!(
+    "{step:<8} {:>10.2?}, {:>5} MiB written, peak RSS {}",
+    start.elapsed(),
+    bytes >> 20,
+    peak.unwrap_or_else(|| "unknown".to_string())
+  );
+}
+
+// `dag --format dot` to a pipe, the mark of the child is read until it exits
+// as it's gone with the process
+fn cli(nodes: usize) {
+  // three levels below the root of about the cube root of the nodes childs
+  let childs = (nodes as f64).cbrt().round();
+  let start = Instant::now();
+  let mut child = Command::new(env!("CARGO_BIN_EXE_dag"))
+    .args(["--depth", "4", "--seed", "1", "--format", "dot"])
+    .args(["--width-mean", &nodes.to_string()])
+    .args(["--child-mean", &childs.to_string()])
+    .stdout(Stdio::piped())
+    .stderr(Stdio::null())
+    .spawn()
+    .unwrap();
+  let peak = Arc::new(Mutex::new(None));
+  let poll = {
+    let (process, peak) = (child.id().to_string(), Arc::clone(&peak));
+    thread::spawn(move || {
+      while let Some(current) = peak_rss(&process) {
+        *peak.lock().unwrap() = Some(current);
+        thread::sleep(Duration::from_millis(50));
+      }
+    })
+  };
+  let mut dot = Counter::default();
+  io::copy(&mut child.stdout.take().unwrap(), &mut dot).unwrap();
+  assert!(child.wait().unwrap().success());
+  poll.join().unwrap();
+  let peak = peak.lock().unwrap().take();
+  report("cli dot", start, dot.0, peak);
+}
+
+fn main() {
+  let nodes = std::env::var("DAG_BENCH_NODES")
+    .ok()
+    .and_then(|nodes| nodes.parse().ok())
+    .unwrap_or(5_000_000);
+  println!("{nodes} nodes, {PARENTS_PER_NODE} parents per node");
+  let mut rng = StdRng::seed_from_u64(42);
+
+  let start = Instant::now();
+  let graph = build(nodes, &mut rng);
+  report("build", start, 0, peak_rss("self"));
+
+  let start = Instant::now();
+  let mut dot = Counter::default();
+  graph.write_dot(&mut dot).unwrap();
+  report("dot", start, dot.0, peak_rss("self"));
+
+  let start = Instant::now();
+  let mut mermaid = Counter::default();
+  graph.write_mermaid(&mut mermaid).unwrap();
+  report("mermaid", start, mermaid.0, peak_rss("self"));
+  drop(graph);
+
+  cli(nodes);
+}
@@ -0,0 +1,15 @@
+//! Arbitrary text given to the DOT import, a graph it accepts must hold the
+//! invariants an `AcyclicGraph` maintains itself.
+
+#![no_main]
+
+use dag::AcyclicGraph;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &str| {
+  if let Ok(graph) = AcyclicGraph::from_dot(data) {
+    graph
+      .check_invariants()
+      .expect("the DOT import accepted an invalid graph");
+  }
+});
@@ -0,0 +1,15 @@
+//! Arbitrary bytes given to the JSON import, a graph it accepts must hold the
+//! invariants an `AcyclicGraph` maintains itself.
+
+#![no_main]
+
+use dag::AcyclicGraph;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+  if let Ok(graph) = AcyclicGraph::from_json_reader(data) {
+    graph
+      .check_invariants()
+      .expect("the JSON import accepted an invalid graph");
+  }
+});
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use num_bigint::BigUint;
+use short_uuid::ShortUuid;
+use uuid::Uuid;
+
+use crate::{
+  graph::AcyclicGraph,
+  i18n::{
+    Lang,
+    Text,
+  },
+  report::Table,
+};
+
+/// Structural metrics of a graph, more expensive to compute than the
+/// validation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Analysis {
+  /// Largest set of nodes where no node can reach another one
+  pub maximum_antichain: Vec<Uuid>,
+  /// Number of distinct paths from a root to a leaf
+  pub path_count: BigUint,
+  /// Parents of each node over the other nodes
+  pub in_degree_centrality: HashMap<Uuid, f64>,
+  /// Childs of each node over the other nodes
+  pub out_degree_centrality: HashMap<Uuid, f64>,
+  /// Fraction of the shortest paths going through each node
+  pub betweenness_centrality: HashMap<Uuid, f64>,
+  /// PageRank with a 0.85 damping factor
+  pub pagerank: HashMap<Uuid, f64>,
+}
+
+pub fn analyze(graph: &AcyclicGraph) -> Analysis {
+  Analysis {
+    maximum_antichain: graph.maximum_antichain(),
+    path_count: graph.path_count(),
+    in_degree_centrality: graph.in_degree_centrality(),
+    out_degree_centrality: graph.out_degree_centrality(),
+    betweenness_centrality: graph.betweenness_centrality(),
+    pagerank: graph.pagerank(0.85),
+  }
+}
+
+impl Analysis {
+  pub fn table(&self, lang: Lang) -> Table {
+    let mut table = Table::metrics(lang);
+    table.metric(lang.text(Text::Width), self.maximum_antichain.len());
+    table.metric(lang.text(Text::RootToLeafPaths), &self.path_count);
+    for (metric, centrality) in [
+      (Text::InDegreeCentrality, &self.in_degree_centrality),
+      (Text::OutDegreeCentrality, &self.out_degree_centrality),
+      (Text::BetweennessCentrality, &self.betweenness_centrality),
+      (Text::PageRank, &self.pagerank),
+    ] {
+      table.metric(lang.text(metric), summary(centrality, lang));
+    }
+    table
+  }
+}
+
+// mean and the node with the highest value, ties are broken by UUID
+fn summary(values: &HashMap<Uuid, f64>, lang: Lang) -> String {
+  let mean = values.values().sum::<f64>() / values.len().max(1) as f64;
+  let max = values
+    .iter()
+    .max_by(|a, b| a.1.total_cmp(b.1).then(b.0.cmp(a.0)));
+  match max {
+    Some((uuid, max)) => format!(
+      "{} {mean:.4}, {} {max:.4} ({})",
+      lang.text(Text::Mean).to_lowercase(),
+      lang.text(Text::Max).to_lowercase(),
+      ShortUuid::from_uuid(uuid)
+    ),
+    None => "-".to_string(),
+  }
+}
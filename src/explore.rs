@@ -0,0 +1,271 @@
+//! Navigation in a graph as a tree of expandable nodes, drawn in the terminal
+//! by `dag explore` with the `explore` feature
+
+#[cfg(feature = "explore")]
+mod tui;
+
+use std::collections::{
+  HashMap,
+  HashSet,
+};
+
+use itertools::Itertools;
+use short_uuid::ShortUuid;
+use uuid::Uuid;
+
+#[cfg(feature = "explore")]
+pub use self::tui::run;
+use crate::graph::AcyclicGraph;
+
+/// A visible line of the tree, a node with several parents has a line under
+/// each expanded one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Row {
+  pub uuid: Uuid,
+  /// Depth in the tree, 0 for a root
+  pub indent: usize,
+}
+
+/// The roots are listed first, expanding a node shows its childs under every
+/// line of the node
+pub struct Explorer<'a> {
+  graph: &'a AcyclicGraph,
+  parents: HashMap<Uuid, HashSet<Uuid>>,
+  expanded: HashSet<Uuid>,
+  rows: Vec<Row>,
+  selected: usize,
+}
+
+impl<'a> Explorer<'a> {
+  pub fn new(graph: &'a AcyclicGraph) -> Self {
+    let mut explorer = Explorer {
+      graph,
+      parents: graph.parents(),
+      expanded: HashSet::new(),
+      rows: Vec::new(),
+      selected: 0,
+    };
+    explorer.rebuild();
+    explorer
+  }
+
+  pub fn graph(&self) -> &'a AcyclicGraph {
+    self.graph
+  }
+
+  pub fn rows(&self) -> &[Row] {
+    &self.rows
+  }
+
+  /// Index of the selected row
+  pub fn position(&self) -> usize {
+    self.selected
+  }
+
+  /// None for an empty graph
+  pub fn selected(&self) -> Option<Row> {
+    self.rows.get(self.selected).copied()
+  }
+
+  pub fn is_expanded(&self, uuid: Uuid) -> bool {
+    self.expanded.contains(&uuid)
+  }
+
+  /// The name of the node, its data without name or its short UUID without
+  /// data
+  pub fn label(&self, uuid: Uuid) -> String {
+    let node = &self.graph.nodes()[&uuid];
+    let label = node
+      .name()
+      .map_or_else(|| node.data().to_string(), str::to_string);
+    match label.is_empty() {
+      true => ShortUuid::from_uuid(&uuid).to_string(),
+      false => label,
+    }
+  }
+
+  /// Parents of a node by label
+  pub fn parents(&self, uuid: Uuid) -> Vec<Uuid> {
+    self.sorted(self.parents.get(&uuid).into_iter().flatten().copied())
+  }
+
+  pub fn up(&mut self) {
+    self.selected = self.selected.saturating_sub(1);
+  }
+
+  pub fn down(&mut self) {
+    if self.selected + 1 < self.rows.len() {
+      self.selected += 1;
+    }
+  }
+
+  pub fn toggle(&mut self) {
+    if let Some(row) = self.selected() {
+      if !self.expanded.remove(&row.uuid) {
+        self.expanded.insert(row.uuid);
+      }
+      self.rebuild_at(row);
+    }
+  }
+
+  pub fn expand(&mut self) {
+    if let Some(row) = self.selected()
+      && self.expanded.insert(row.uuid)
+    {
+      self.rebuild_at(row);
+    }
+  }
+
+  /// Collapse the selected node, or select the line of its parent when it's
+  /// already collapsed
+  pub fn collapse(&mut self) {
+    let Some(row) = self.selected() else {
+      return;
+    };
+    if self.expanded.remove(&row.uuid) {
+      self.rebuild_at(row);
+    } else if let Some(parent) = self.rows[..self.selected]
+      .iter()
+      .rposition(|other| other.indent + 1 == row.indent)
+    {
+      self.selected = parent;
+    }
+  }
+
+  /// Select the next node after the selected one whose label contains
+  /// `query`, ignoring the case, by label order. False if there is none.
+  pub fn search(&mut self, query: &str) -> bool {
+    let query = query.to_lowercase();
+    let nodes = self.sorted(self.graph.nodes().keys().copied());
+    let start = self
+      .selected()
+      .and_then(|row| nodes.iter().position(|&uuid| uuid == row.uuid))
+      .map_or(0, |i| i + 1);
+    let found = nodes[start..]
+      .iter()
+      .chain(&nodes[..start])
+      .find(|&&uuid| self.label(uuid).to_lowercase().contains(&query))
+      .copied();
+    if let Some(uuid) = found {
+      self.reveal(uuid);
+    }
+    found.is_some()
+  }
+
+  /// Expand a path from a root to the node then select it
+  pub fn reveal(&mut self, uuid: Uuid) {
+    let mut current = uuid;
+    while let Some(parent) = self.parents(current).first() {
+      self.expanded.insert(*parent);
+      current = *parent;
+    }
+    self.rebuild();
+    if let Some(i) = self.rows.iter().position(|row| row.uuid == uuid) {
+      self.selected = i;
+    }
+  }
+
+  fn sorted(&self, uuids: impl Iterator<Item = Uuid>) -> Vec<Uuid> {
+    uuids
+      .map(|uuid| (self.label(uuid), uuid))
+      .sorted()
+      .map(|(_, uuid)| uuid)
+      .collect()
+  }
+
+  // keep the selection on the same line of the node
+  fn rebuild_at(&mut self, row: Row) {
+    let before = self.rows[..self.selected]
+      .iter()
+      .filter(|other| **other == row)
+      .count();
+    self.rebuild();
+    if let Some((i, _)) = self
+      .rows
+      .iter()
+      .enumerate()
+      .filter(|(_, other)| **other == row)
+      .nth(before)
+    {
+      self.selected = i;
+    }
+  }
+
+  // depth first with a stack to support deep graphs
+  fn rebuild(&mut self) {
+    let roots = self.sorted(
+      self
+        .graph
+        .nodes()
+        .keys()
+        .filter(|uuid| !self.parents.contains_key(uuid))
+        .copied(),
+    );
+    let mut stack: Vec<Row> = roots
+      .into_iter()
+      .rev()
+      .map(|uuid| Row { uuid, indent: 0 })
+      .collect();
+    self.rows.clear();
+    while let Some(row) = stack.pop() {
+      self.rows.push(row);
+      if self.expanded.contains(&row.uuid) {
+        let childs = self.sorted(self.graph.nodes()[&row.uuid].childs().iter().copied());
+        stack.extend(childs.into_iter().rev().map(|uuid| Row {
+          uuid,
+          indent: row.indent + 1,
+        }));
+      }
+    }
+    self.selected = self.selected.min(self.rows.len().saturating_sub(1));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_explorer() {
+    // root -> a -> c, root -> b -> c
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let (a, _) = graph.add_node("A");
+    let (b, _) = graph.add_node("B");
+    let (c, _) = graph.add_node("C");
+    for (parent, child) in [(root, a), (root, b), (a, c), (b, c)] {
+      graph.add_child(parent, child).unwrap();
+    }
+
+    let mut explorer = Explorer::new(&graph);
+    assert_eq!(
+      explorer.rows(),
+      [Row {
+        uuid: root,
+        indent: 0
+      }]
+    );
+    explorer.expand();
+    explorer.down();
+    explorer.down();
+    assert_eq!(explorer.selected().map(|row| row.uuid), Some(b));
+    explorer.toggle();
+    let uuids = |explorer: &Explorer| explorer.rows().iter().map(|row| row.uuid).collect_vec();
+    assert_eq!(uuids(&explorer), [root, a, b, c]);
+
+    // collapsed then to its parent
+    explorer.collapse();
+    assert_eq!(uuids(&explorer), [root, a, b]);
+    explorer.collapse();
+    assert_eq!(explorer.position(), 0);
+
+    assert!(explorer.search("c"));
+    assert_eq!(explorer.selected().map(|row| row.uuid), Some(c));
+    assert_eq!(uuids(&explorer), [root, a, c, b]);
+    assert_eq!(explorer.parents(c), [a, b]);
+    explorer.reveal(b);
+    assert_eq!(explorer.selected(), Some(Row { uuid: b, indent: 1 }));
+    assert!(!explorer.search("missing"));
+    assert_eq!(explorer.selected().map(|row| row.uuid), Some(b));
+  }
+}
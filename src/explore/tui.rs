@@ -0,0 +1,201 @@
+use std::io;
+
+use ratatui::{
+  DefaultTerminal,
+  Frame,
+  crossterm::event::{
+    self,
+    Event,
+    KeyCode,
+    KeyEventKind,
+  },
+  layout::{
+    Constraint,
+    Layout,
+  },
+  style::{
+    Style,
+    Stylize,
+  },
+  widgets::{
+    Block,
+    List,
+    ListItem,
+    ListState,
+    Paragraph,
+    Wrap,
+  },
+};
+
+use super::Explorer;
+use crate::graph::{
+  AcyclicGraph,
+  NodeData,
+};
+
+const HELP: &str =
+  "↑↓ move  → expand  ← collapse  enter toggle  / search  n next  1-9 parent  q quit";
+
+/// Navigate the graph in the terminal until q or escape is pressed
+pub fn run(graph: &AcyclicGraph) -> io::Result<()> {
+  let mut terminal = ratatui::init();
+  let result = Tui {
+    explorer: Explorer::new(graph),
+    query: String::new(),
+    typing: false,
+    status: HELP.to_string(),
+  }
+  .run(&mut terminal);
+  ratatui::restore();
+  result
+}
+
+struct Tui<'a> {
+  explorer: Explorer<'a>,
+  query: String,
+  // the query is edited in the status line
+  typing: bool,
+  status: String,
+}
+
+impl Tui<'_> {
+  fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+    loop {
+      terminal.draw(|frame| self.draw(frame))?;
+      let Event::Key(key) = event::read()? else {
+        continue;
+      };
+      if key.kind != KeyEventKind::Press {
+        continue;
+      }
+      if self.typing {
+        match key.code {
+          KeyCode::Enter => {
+            self.typing = false;
+            self.search();
+          }
+          KeyCode::Esc => {
+            self.typing = false;
+            self.status = HELP.to_string();
+          }
+          KeyCode::Backspace => {
+            self.query.pop();
+          }
+          KeyCode::Char(c) => self.query.push(c),
+          _ => {}
+        }
+        continue;
+      }
+      match key.code {
+        KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+        KeyCode::Up | KeyCode::Char('k') => self.explorer.up(),
+        KeyCode::Down | KeyCode::Char('j') => self.explorer.down(),
+        KeyCode::Right | KeyCode::Char('l') => self.explorer.expand(),
+        KeyCode::Left | KeyCode::Char('h') => self.explorer.collapse(),
+        KeyCode::Enter => self.explorer.toggle(),
+        KeyCode::Char('/') => {
+          self.typing = true;
+          self.query.clear();
+        }
+        KeyCode::Char('n') => self.search(),
+        KeyCode::Char(c @ '1'..='9') => self.jump(c as usize - '1' as usize),
+        _ => {}
+      }
+    }
+  }
+
+  fn search(&mut self) {
+    self.status = match self.explorer.search(&self.query) {
+      true => format!("/{}  n next", self.query),
+      false => format!("/{}  not found", self.query),
+    };
+  }
+
+  fn jump(&mut self, parent: usize) {
+    let Some(row) = self.explorer.selected() else {
+      return;
+    };
+    if let Some(&uuid) = self.explorer.parents(row.uuid).get(parent) {
+      self.explorer.reveal(uuid);
+    }
+  }
+
+  fn draw(&self, frame: &mut Frame) {
+    let [main, status] =
+      Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame.area());
+    let [tree, details] =
+      Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)]).areas(main);
+
+    let explorer = &self.explorer;
+    let nodes = explorer.graph().nodes();
+    let items = explorer.rows().iter().map(|row| {
+      let marker = match (
+        nodes[&row.uuid].childs().is_empty(),
+        explorer.is_expanded(row.uuid),
+      ) {
+        (true, _) => ' ',
+        (false, true) => '▾',
+        (false, false) => '▸',
+      };
+      ListItem::new(format!(
+        "{}{marker} {}",
+        "  ".repeat(row.indent),
+        explorer.label(row.uuid)
+      ))
+    });
+    let list = List::new(items)
+      .block(Block::bordered().title(explorer.graph().name()))
+      .highlight_style(Style::new().reversed());
+    let mut state =
+      ListState::default().with_selected(explorer.selected().map(|_| explorer.position()));
+    frame.render_stateful_widget(list, tree, &mut state);
+
+    let text = explorer
+      .selected()
+      .map(|row| self.details(row.uuid))
+      .unwrap_or_default();
+    let paragraph = Paragraph::new(text)
+      .block(Block::bordered().title("Node"))
+      .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, details);
+
+    let line = match self.typing {
+      true => format!("/{}", self.query),
+      false => self.status.clone(),
+    };
+    frame.render_widget(Paragraph::new(line), status);
+  }
+
+  fn details(&self, uuid: uuid::Uuid) -> String {
+    let explorer = &self.explorer;
+    let node = &explorer.graph().nodes()[&uuid];
+    let mut text = format!("{}\n{uuid}\n", explorer.label(uuid));
+    if let Some(level) = node.level() {
+      text += &format!("level {level}\n");
+    }
+    if let Some(timestamp) = node.timestamp() {
+      text += &format!("timestamp {timestamp}\n");
+    }
+    text += &format!("{} childs\n", node.childs().len());
+    let parents = explorer.parents(uuid);
+    if !parents.is_empty() {
+      text += "\nparents:\n";
+      for (i, parent) in parents.iter().enumerate() {
+        let key = match i {
+          0..9 => format!("{}", i + 1),
+          _ => " ".to_string(),
+        };
+        text += &format!("{key} {}\n", explorer.label(*parent));
+      }
+    }
+    let data = match node.data() {
+      NodeData::Json(json) => serde_json::to_string_pretty(json).unwrap_or_default(),
+      NodeData::None => String::new(),
+      data => data.to_string(),
+    };
+    if !data.is_empty() {
+      text += &format!("\ndata:\n{data}\n");
+    }
+    text
+  }
+}
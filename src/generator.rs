@@ -1,23 +1,60 @@
+use std::{
+  borrow::Cow,
+  io::{
+    self,
+    Read,
+    Write,
+  },
+  ops::Range,
+  time::{
+    Duration,
+    Instant,
+  },
+};
+
+use itertools::Itertools;
 use petname::Generator;
 use rand::{
+  Rng,
+  RngCore,
   SeedableRng,
   rngs::StdRng,
-  seq::SliceRandom,
+  seq::{
+    SliceRandom,
+    index,
+  },
 };
 use rand_distr::{
   Distribution,
+  Geometric,
   Normal,
+  Poisson,
+  Uniform,
+};
+use serde_json::{
+  Map,
+  Value,
 };
 use snafu::{
+  OptionExt,
   ResultExt,
   Snafu,
+  ensure,
 };
+use uuid::Uuid;
 
-use crate::graph::{
-  self,
-  AcyclicGraph,
+use crate::{
+  graph::{
+    self,
+    AcyclicGraph,
+    Node,
+    NodeData,
+    UuidVersion,
+  },
+  schema::Schema,
 };
 
+#[derive(Debug, Clone)]
 pub struct Config {
   pub name: Option<String>,
   pub depth: usize,
@@ -25,45 +62,1160 @@ pub struct Config {
   pub width_std_dev: f64,
   pub child_mean: f64,
   pub child_std_dev: f64,
+  /// Distribution of the widths, drawn with the width mean and standard
+  /// deviation
+  pub width_dist: Dist,
+  /// Distribution of the childs of each node, drawn with the child mean and
+  /// standard deviation
+  pub child_dist: Dist,
   pub seed: u64,
+  /// Replace node names by JSON objects generated from the schema, the name
+  /// is kept in the `name` field
+  pub schema: Option<Schema>,
+  /// Give each edge a JSON object generated from the schema, see
+  /// [`AcyclicGraph::edge_data`]
+  pub edge_schema: Option<Schema>,
+  /// Version of the node UUIDs, see [`graph::UuidVersion`]
+  pub uuid_version: UuidVersion,
+  pub strategy: Strategy,
+  pub shape: Shape,
+  /// Extra edges of [`Shape::Dag`] per edge of the tree, e.g. 0.2 adds one
+  /// for five nodes
+  pub extra_edge_density: f64,
+  /// Give each edge a weight drawn from a normal distribution of this mean,
+  /// negative draws become 0, see [`graph::EdgeData`]
+  pub edge_weight_mean: Option<f64>,
+  pub edge_weight_std_dev: f64,
+}
+
+/// The defaults of the command line with the seed 0
+impl Default for Config {
+  fn default() -> Self {
+    Self {
+      name: None,
+      depth: 5,
+      width_mean: 10.0,
+      width_std_dev: 0.5,
+      child_mean: 3.0,
+      child_std_dev: 1.0,
+      width_dist: Dist::default(),
+      child_dist: Dist::default(),
+      seed: 0,
+      schema: None,
+      edge_schema: None,
+      uuid_version: UuidVersion::default(),
+      strategy: Strategy::default(),
+      shape: Shape::default(),
+      extra_edge_density: 0.2,
+      edge_weight_mean: None,
+      edge_weight_std_dev: 1.0,
+    }
+  }
+}
+
+/// Order in which the nodes are added below the root
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Strategy {
+  /// Level by level, the childs of the nodes of a level make the next one
+  #[default]
+  BreadthFirst,
+  /// Subtree by subtree, a child gets all its descendants before its next
+  /// sibling. The width of a level is drawn when the level is first reached
+  /// so the first subtrees take most of it.
+  DepthFirst,
+}
+
+/// Distribution of the widths or of the childs, each one is parametrized by
+/// the mean and standard deviation of the config so it keeps the same mean.
+/// The draws are rounded, a negative one gives no child and a width is at
+/// least 1.
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  Default,
+  serde::Serialize,
+  serde::Deserialize
+)]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Dist {
+  /// The mean and standard deviation, small means give many draws clamped
+  /// to 0 or 1
+  #[default]
+  Normal,
+  /// Between the mean minus and plus √3 standard deviations, the same
+  /// standard deviation as a normal one
+  Uniform,
+  /// Of the mean, the standard deviation is ignored
+  Poisson,
+  /// Number of failures before a success of probability 1 / (1 + mean), a
+  /// long tail, the standard deviation is ignored
+  Geometric,
+  /// Always the mean
+  Constant,
+}
+
+impl Dist {
+  fn sampler(self, mean: f64, std_dev: f64) -> Result<Sampler, Error> {
+    let invalid = InvalidDistributionSnafu {
+      dist: self,
+      mean,
+      std_dev,
+    };
+    Ok(match self {
+      Dist::Normal => {
+        Sampler::Normal(Normal::new(mean, std_dev).context(RandNormalDistributionSnafu)?)
+      }
+      Dist::Uniform => {
+        let half = std_dev * 3f64.sqrt();
+        Sampler::Uniform(
+          Uniform::new_inclusive(mean - half, mean + half)
+            .ok()
+            .context(invalid)?,
+        )
+      }
+      Dist::Poisson => Sampler::Poisson(Poisson::new(mean).ok().context(invalid)?),
+      Dist::Geometric => {
+        Sampler::Geometric(Geometric::new(1.0 / (1.0 + mean)).ok().context(invalid)?)
+      }
+      Dist::Constant => Sampler::Constant(mean),
+    })
+  }
+}
+
+enum Sampler {
+  Normal(Normal<f64>),
+  Uniform(Uniform<f64>),
+  Poisson(Poisson<f64>),
+  Geometric(Geometric),
+  Constant(f64),
+}
+
+impl Distribution<f64> for Sampler {
+  fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+    match self {
+      Sampler::Normal(normal) => normal.sample(rng),
+      Sampler::Uniform(uniform) => uniform.sample(rng),
+      Sampler::Poisson(poisson) => poisson.sample(rng),
+      Sampler::Geometric(geometric) => geometric.sample(rng) as f64,
+      Sampler::Constant(value) => *value,
+    }
+  }
+}
+
+// of the widths and of the childs
+fn samplers(cfg: &Config) -> Result<(Sampler, Sampler), Error> {
+  Ok((
+    cfg.width_dist.sampler(cfg.width_mean, cfg.width_std_dev)?,
+    cfg.child_dist.sampler(cfg.child_mean, cfg.child_std_dev)?,
+  ))
+}
+
+/// Edges of the generated graph
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  Default,
+  serde::Serialize,
+  serde::Deserialize
+)]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Shape {
+  /// Every node but the root has a single parent on the level above
+  #[default]
+  Tree,
+  /// The tree then extra edges from random nodes to random nodes of deeper
+  /// levels, so a node can have several parents
+  Dag,
+}
+
+/// Generation parameters of a configuration file, the missing ones keep the
+/// value of the base config, see [`Parameters::apply`]
+#[derive(Debug, Clone, Default, PartialEq, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Parameters {
+  pub name: Option<String>,
+  pub depth: Option<usize>,
+  pub width_mean: Option<f64>,
+  pub width_std_dev: Option<f64>,
+  pub child_mean: Option<f64>,
+  pub child_std_dev: Option<f64>,
+  pub width_dist: Option<Dist>,
+  pub child_dist: Option<Dist>,
+  pub seed: Option<u64>,
+}
+
+impl Parameters {
+  pub fn apply(self, cfg: &Config) -> Config {
+    Config {
+      name: self.name.or_else(|| cfg.name.clone()),
+      depth: self.depth.unwrap_or(cfg.depth),
+      width_mean: self.width_mean.unwrap_or(cfg.width_mean),
+      width_std_dev: self.width_std_dev.unwrap_or(cfg.width_std_dev),
+      child_mean: self.child_mean.unwrap_or(cfg.child_mean),
+      child_std_dev: self.child_std_dev.unwrap_or(cfg.child_std_dev),
+      width_dist: self.width_dist.unwrap_or(cfg.width_dist),
+      child_dist: self.child_dist.unwrap_or(cfg.child_dist),
+      seed: self.seed.unwrap_or(cfg.seed),
+      ..cfg.clone()
+    }
+  }
 }
 
 #[derive(Snafu, Debug)]
 pub enum Error {
-  RandNormalDistribution { source: rand_distr::NormalError },
-  AcyclicGraph { source: graph::Error },
+  RandNormalDistribution {
+    source: rand_distr::NormalError,
+  },
+  #[snafu(display("No {dist:?} distribution of mean {mean} and standard deviation {std_dev}"))]
+  InvalidDistribution {
+    dist: Dist,
+    mean: f64,
+    std_dev: f64,
+  },
+  AcyclicGraph {
+    source: graph::Error,
+  },
+  #[snafu(display(
+    "no graph with a single root has exactly {nodes} nodes, {depth} levels and {edges} edges"
+  ))]
+  Infeasible {
+    nodes: usize,
+    depth: usize,
+    edges: usize,
+  },
+  #[snafu(display("Can't write the checkpoint"))]
+  Checkpoint {
+    source: io::Error,
+  },
+  #[snafu(display("Not a checkpoint"))]
+  CheckpointFormat {
+    source: serde_json::Error,
+  },
+  #[snafu(display("The checkpoint was made with other generation parameters"))]
+  CheckpointMismatch,
+  #[snafu(display("Only the breadth-first generation can be checkpointed"))]
+  CheckpointStrategy,
+  #[snafu(display(
+    "The {preset:?} preset of {depth} levels and width {width} is above {MAX_PRESET_SIZE} nodes \
+     or edges"
+  ))]
+  PresetSize {
+    preset: Preset,
+    depth: usize,
+    width: usize,
+  },
 }
 
-/// This will generate a sinple graph that look like a family tree
-pub fn generate(cfg: &Config) -> Result<AcyclicGraph, Error> {
+/// Expected shape of a generation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+  /// Number of nodes of each level, root level included
+  pub widths: Vec<usize>,
+  /// Edges added to a tree of these widths, the extra edges of
+  /// [`Shape::Dag`], of an exact generation or of a preset
+  pub extra_edges: usize,
+}
+
+// Rough sizes used by the estimations, a short UUID is 22 characters and a
+// petname is about 7
+const ID_LEN: usize = 22;
+const NAME_LEN: usize = 7;
+
+impl Plan {
+  pub fn nodes(&self) -> usize {
+    self.widths.iter().sum()
+  }
+
+  /// Every node except the root have a parent, plus the extra edges
+  pub fn edges(&self) -> usize {
+    self.nodes().saturating_sub(1) + self.extra_edges
+  }
+
+  /// Estimated heap used by the graph in bytes
+  pub fn memory(&self) -> usize {
+    // the vectors indexed by node ID grow by doubling: the slots of the
+    // nodes and three u32 of the topological order, then a B-tree entry of
+    // about 30 bytes per UUID and the allocation of the name
+    let slot = size_of::<Option<(uuid::Uuid, graph::Node)>>() + 3 * size_of::<u32>();
+    let nodes = self.nodes().next_power_of_two() * slot + self.nodes() * (30 + 16);
+    // a parent link of 8 bytes per edge in the order, the childs of a parent
+    // in a hash set of at least 4 buckets, a power of two with 1/8 free, of
+    // an UUID and a control byte each
+    let sets = match self.edges() {
+      0 => 0,
+      edges => {
+        let parents = self.parents().max(1);
+        let buckets = (edges.div_ceil(parents) * 8)
+          .div_ceil(7)
+          .next_power_of_two()
+          .max(4);
+        parents * (buckets * (size_of::<uuid::Uuid>() + 1) + 16)
+      }
+    };
+    nodes + self.edges().next_power_of_two() * 8 + sets
+  }
+
+  /// Estimated size of the DOT output in bytes
+  pub fn dot_size(&self) -> usize {
+    // `  "id" [label = "name"];` and `  "id" -> {"id" "id"};`
+    let node = 2 + ID_LEN + 2 + 12 + NAME_LEN + 4;
+    let parent = 2 + ID_LEN + 2 + 6 + 3;
+    let child = ID_LEN + 3;
+    self.nodes() * node + self.parents() * parent + self.edges() * child
+  }
+
+  /// Estimated size of the Mermaid output in bytes
+  pub fn mermaid_size(&self) -> usize {
+    // `  id[name] --> id & id`
+    let node = 2 + ID_LEN + 2 + NAME_LEN + 1;
+    let parent = 2;
+    let child = 3 + ID_LEN;
+    self.nodes() * node + self.parents() * parent + self.edges() * child
+  }
+
+  /// Estimated size of the HTML output in bytes
+  pub fn html_size(&self) -> usize {
+    // page with the script, a `<g>` with a rect and a text per node and a
+    // `<line>` per edge
+    let page = 2500;
+    let node = 140 + ID_LEN + NAME_LEN;
+    let edge = 90;
+    page + self.nodes() * node + self.edges() * edge
+  }
+
+  /// Estimated size of the JGF output in bytes
+  pub fn jgf_size(&self) -> usize {
+    // pretty printed node with its label, UUID, data and level metadata and
+    // an edge object with two short UUIDs
+    let node = 200 + ID_LEN + 2 * NAME_LEN;
+    let edge = 50 + 2 * ID_LEN;
+    self.nodes() * node + self.edges() * edge
+  }
+
+  /// Estimated size of the DIMACS output in bytes
+  pub fn dimacs_size(&self) -> usize {
+    // `c 12 id name` legend and `e 1 12` edges
+    let node = 4 + ID_LEN + 1 + NAME_LEN + 4;
+    let edge = 12;
+    self.nodes() * node + self.edges() * edge
+  }
+
+  /// Estimated size of the METIS output in bytes, without the mapping
+  pub fn metis_size(&self) -> usize {
+    // each edge is listed twice as a number of about 3 digits
+    let node = 1;
+    let edge = 2 * 4;
+    self.nodes() * node + self.edges() * edge
+  }
+
+  /// Estimated size of the JSON output in bytes
+  pub fn json_size(&self) -> usize {
+    // pretty printed node with its UUID, data and level then a line per
+    // child UUID
+    let node = 110 + 36 + NAME_LEN;
+    let child = 8 + 36 + 3;
+    self.nodes() * node + self.edges() * child
+  }
+
+  /// Estimated size of the GraphML output in bytes
+  pub fn graphml_size(&self) -> usize {
+    // a `<node>` with its label, UUID, data and level `<data>` and an
+    // `<edge>` with two short UUIDs
+    let node = 200 + ID_LEN + 2 * NAME_LEN;
+    let edge = 30 + 2 * ID_LEN;
+    self.nodes() * node + self.edges() * edge
+  }
+
+  /// Estimated size of the GEXF output in bytes
+  pub fn gexf_size(&self) -> usize {
+    // a `<node>` with its label and its UUID, data and level `<attvalue>` and
+    // an `<edge>` with its number and two short UUIDs
+    let node = 250 + ID_LEN + 2 * NAME_LEN;
+    let edge = 40 + 2 * ID_LEN;
+    self.nodes() * node + self.edges() * edge
+  }
+
+  /// Estimated size of the topological order output in bytes
+  pub fn topo_size(&self) -> usize {
+    // `id name`
+    self.nodes() * (ID_LEN + 1 + NAME_LEN + 1)
+  }
+
+  /// Estimated size of the ASCII tree output in bytes
+  pub fn ascii_size(&self) -> usize {
+    // a line per root and per edge, `│   ` is 6 bytes per level above the
+    // line and `├── ` 10 bytes
+    let depths: usize = self
+      .widths
+      .iter()
+      .enumerate()
+      .map(|(depth, width)| depth * width)
+      .sum();
+    let lines = self.widths.first().copied().unwrap_or(0) + self.edges();
+    let indent = depths * 6 / self.nodes().max(1);
+    lines * (indent + 10 + NAME_LEN + 1)
+  }
+
+  /// Estimated size of the XLSX output in bytes, the sheets are compressed
+  #[cfg(feature = "xlsx")]
+  pub fn xlsx_size(&self) -> usize {
+    // a few KiB of workbook parts then about a quarter of the cells
+    let parts = 6 * 1024;
+    let node = (ID_LEN + 36 + 2 * NAME_LEN + 40) / 4;
+    let edge = (2 * ID_LEN + 2 * NAME_LEN + 40) / 4;
+    parts + self.nodes() * node + self.edges() * edge
+  }
+
+  // a level can only be reached from a parent of the previous one
+  fn parents(&self) -> usize {
+    self
+      .widths
+      .windows(2)
+      .map(|widths| widths[0].min(widths[1]))
+      .sum()
+  }
+}
+
+/// Sample the widths of each level without building the graph, the random
+/// draws differ from [`generate`] so this is only an estimation of the shape
+/// of a graph with the same config
+pub fn plan(cfg: &Config) -> Result<Plan, Error> {
   let mut rng = StdRng::seed_from_u64(cfg.seed);
 
+  let (width_dist, child_dist) = samplers(cfg)?;
+
+  let mut widths = vec![1];
+  for _ in 1..cfg.depth {
+    let n = width_dist.sample(&mut rng).round().max(1.0) as usize;
+    let current = widths.last().copied().unwrap_or(0);
+
+    let mut i = 0;
+    for _ in 0..current {
+      let k = child_dist.sample(&mut rng).round().max(0.0) as usize;
+      i = (i + k).min(n);
+      if i >= n {
+        break;
+      }
+    }
+
+    if i == 0 {
+      break;
+    }
+    widths.push(i);
+  }
+
+  let extra_edges = match cfg.shape {
+    Shape::Tree => 0,
+    Shape::Dag => {
+      let tree = widths.iter().sum::<usize>() - 1;
+      extra_edges(cfg, tree).min(max_edges(&widths) - tree)
+    }
+  };
+  Ok(Plan {
+    widths,
+    extra_edges,
+  })
+}
+
+/// The shape of [`generate_exact`], the widths are the ones of the graph
+/// generated with the same config
+pub fn plan_exact(cfg: &Config, exact: &Exact) -> Result<Plan, Error> {
+  let widths = exact_widths(exact, &mut StdRng::seed_from_u64(cfg.seed))?;
+  Ok(Plan {
+    widths,
+    extra_edges: exact.edges - (exact.nodes - 1),
+  })
+}
+
+/// The shape of [`generate_preset`], the same as the generated graph
+pub fn plan_preset(cfg: &Config, preset: Preset) -> Result<Plan, Error> {
+  let (widths, edges) = preset_widths(cfg, preset)?;
+  Ok(Plan {
+    extra_edges: edges - widths.iter().sum::<usize>().saturating_sub(1),
+    widths,
+  })
+}
+
+/// This will generate a sinple graph that look like a family tree from the
+/// seed of the config
+pub fn generate(cfg: &Config) -> Result<AcyclicGraph, Error> {
+  generate_with_rng(cfg, &mut StdRng::seed_from_u64(cfg.seed))
+}
+
+/// Like [`generate`] with any RNG, the seed of the config is ignored
+pub fn generate_with_rng(cfg: &Config, rng: &mut impl Rng) -> Result<AcyclicGraph, Error> {
+  let petnames = petname::Petnames::default();
+  let (mut graph, root) = start(cfg, &petnames, rng)?;
+  match cfg.strategy {
+    Strategy::BreadthFirst => add_levels(
+      &mut graph,
+      cfg,
+      vec![root],
+      1..cfg.depth,
+      &petnames,
+      rng,
+      |_, _, _, _| Ok(()),
+    )?,
+    Strategy::DepthFirst => {
+      add_subtrees(&mut graph, cfg, vec![root], 1..cfg.depth, &petnames, rng)?
+    }
+  }
+  add_shape(&mut graph, cfg, rng)?;
+  Ok(graph)
+}
+
+/// State of a generation between two levels, enough to finish it with
+/// [`generate_checkpointed`]. A made checkpoint borrows the graph being
+/// generated, a read one owns it.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Checkpoint<'a> {
+  #[serde(flatten)]
+  parameters: Generation,
+  // 32 bits words drawn from the RNG seeded with `seed`
+  words: u64,
+  // next level to generate and the nodes of the previous one in their order
+  level: usize,
+  current: Cow<'a, [Uuid]>,
+  // absent from the older checkpoints, the graph gives it back
+  #[serde(default)]
+  uuid_clock: u64,
+  graph: Cow<'a, AcyclicGraph>,
+}
+
+// every parameter of the config changing the generated graph but the
+// strategy, always breadth first. The ones absent from the older checkpoints
+// get the defaults of the command line.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+struct Generation {
+  name: Option<String>,
+  seed: u64,
+  depth: usize,
+  width_mean: f64,
+  width_std_dev: f64,
+  child_mean: f64,
+  child_std_dev: f64,
+  width_dist: Dist,
+  child_dist: Dist,
+  schema: Option<Schema>,
+  edge_schema: Option<Schema>,
+  uuid_version: UuidVersion,
+  shape: Shape,
+  extra_edge_density: f64,
+  edge_weight_mean: Option<f64>,
+  edge_weight_std_dev: f64,
+}
+
+impl From<&Config> for Generation {
+  fn from(cfg: &Config) -> Self {
+    Generation {
+      name: cfg.name.clone(),
+      seed: cfg.seed,
+      depth: cfg.depth,
+      width_mean: cfg.width_mean,
+      width_std_dev: cfg.width_std_dev,
+      child_mean: cfg.child_mean,
+      child_std_dev: cfg.child_std_dev,
+      width_dist: cfg.width_dist,
+      child_dist: cfg.child_dist,
+      schema: cfg.schema.clone(),
+      edge_schema: cfg.edge_schema.clone(),
+      uuid_version: cfg.uuid_version,
+      shape: cfg.shape,
+      extra_edge_density: cfg.extra_edge_density,
+      edge_weight_mean: cfg.edge_weight_mean,
+      edge_weight_std_dev: cfg.edge_weight_std_dev,
+    }
+  }
+}
+
+impl Default for Generation {
+  fn default() -> Self {
+    Generation::from(&Config::default())
+  }
+}
+
+impl<'a> Checkpoint<'a> {
+  fn new(
+    cfg: &Config, rng: &Counted, level: usize, current: &'a [Uuid], graph: &'a AcyclicGraph,
+  ) -> Self {
+    Checkpoint {
+      parameters: cfg.into(),
+      words: rng.words,
+      level,
+      current: Cow::Borrowed(current),
+      uuid_clock: graph.uuid_clock(),
+      graph: Cow::Borrowed(graph),
+    }
+  }
+
+  fn matches(&self, cfg: &Config) -> bool {
+    self.parameters == Generation::from(cfg)
+  }
+
+  pub fn seed(&self) -> u64 {
+    self.parameters.seed
+  }
+
+  /// Number of levels already generated
+  pub fn level(&self) -> usize {
+    self.level
+  }
+
+  /// The partial graph
+  pub fn graph(&self) -> &AcyclicGraph {
+    &self.graph
+  }
+
+  pub fn from_reader(reader: impl Read) -> Result<Checkpoint<'static>, Error> {
+    serde_json::from_reader(reader).context(CheckpointFormatSnafu)
+  }
+
+  pub fn write(&self, writer: impl Write) -> io::Result<()> {
+    Ok(serde_json::to_writer(writer, self)?)
+  }
+}
+
+// StdRng counting the 32 bits words drawn, every draw takes whole words in
+// order so drawing as many words from the seed gives back the same state
+struct Counted {
+  rng: StdRng,
+  words: u64,
+}
+
+impl Counted {
+  fn new(seed: u64, words: u64) -> Self {
+    let mut rng = StdRng::seed_from_u64(seed);
+    for _ in 0..words {
+      rng.next_u32();
+    }
+    Counted { rng, words }
+  }
+}
+
+impl RngCore for Counted {
+  fn next_u32(&mut self) -> u32 {
+    self.words += 1;
+    self.rng.next_u32()
+  }
+
+  fn next_u64(&mut self) -> u64 {
+    self.words += 2;
+    self.rng.next_u64()
+  }
+
+  fn fill_bytes(&mut self, dst: &mut [u8]) {
+    self.words += dst.len().div_ceil(4) as u64;
+    self.rng.fill_bytes(dst)
+  }
+}
+
+/// Like [`generate`] but `checkpoint` is called between two levels, once
+/// `interval` elapsed since the start or the previous call, with the state to
+/// give back as `resume` to finish an interrupted generation. The resumed
+/// generation gives the same graph as an uninterrupted one and must have the
+/// same config. Only for [`Strategy::BreadthFirst`].
+///
+/// A level is never interrupted by a checkpoint, so a single huge level is
+/// generated again from its start, and the extra edges of [`Shape::Dag`]
+/// are added after the last checkpoint.
+pub fn generate_checkpointed(
+  cfg: &Config, resume: Option<Checkpoint<'_>>, interval: Duration,
+  mut checkpoint: impl FnMut(&Checkpoint) -> io::Result<()>,
+) -> Result<AcyclicGraph, Error> {
+  ensure!(
+    cfg.strategy == Strategy::BreadthFirst,
+    CheckpointStrategySnafu
+  );
+  let petnames = petname::Petnames::default();
+  let (mut rng, mut graph, current, levels) = match resume {
+    Some(resume) => {
+      ensure!(resume.matches(cfg), CheckpointMismatchSnafu);
+      let mut graph = resume.graph.into_owned();
+      graph.set_uuid_version(cfg.uuid_version);
+      graph.set_uuid_clock(graph.uuid_clock().max(resume.uuid_clock));
+      let rng = Counted::new(cfg.seed, resume.words);
+      (
+        rng,
+        graph,
+        resume.current.into_owned(),
+        resume.level..cfg.depth,
+      )
+    }
+    None => {
+      let mut rng = Counted::new(cfg.seed, 0);
+      let (graph, root) = start(cfg, &petnames, &mut rng)?;
+      (rng, graph, vec![root], 1..cfg.depth)
+    }
+  };
+
+  let mut last = Instant::now();
+  add_levels(
+    &mut graph,
+    cfg,
+    current,
+    levels,
+    &petnames,
+    &mut rng,
+    |graph, current, level, rng| {
+      if last.elapsed() >= interval {
+        checkpoint(&Checkpoint::new(cfg, rng, level, current, graph)).context(CheckpointSnafu)?;
+        last = Instant::now();
+      }
+      Ok(())
+    },
+  )?;
+  add_shape(&mut graph, cfg, &mut rng)?;
+  Ok(graph)
+}
+
+// the graph with its root
+fn start(
+  cfg: &Config, petnames: &petname::Petnames, rng: &mut impl Rng,
+) -> Result<(AcyclicGraph, Uuid), Error> {
+  let name = cfg.name.as_ref().cloned().unwrap_or_else(|| {
+    petnames
+      .generate(rng, 2, "_")
+      .unwrap_or_else(|| "output".to_string())
+  });
+  let mut graph = AcyclicGraph::new(name);
+  graph.set_uuid_version(cfg.uuid_version);
+
+  let root = node_data(cfg, Some("Root".to_string()), rng);
+  let (root, _) = graph.add_node_with_rng(root, rng);
+  graph
+    .get_node_mut(root)
+    .context(AcyclicGraphSnafu {})?
+    .set_level(0);
+  Ok((graph, root))
+}
+
+/// Append `levels` levels below the deepest recorded level of a generated
+/// graph with the distributions of the config. The nodes without a recorded
+/// level, like the nodes of an imported graph, get their depth as level first.
+pub fn grow(graph: &mut AcyclicGraph, cfg: &Config, levels: usize, seed: u64) -> Result<(), Error> {
+  grow_with_rng(graph, cfg, levels, &mut StdRng::seed_from_u64(seed))
+}
+
+/// Like [`grow`] with any RNG
+pub fn grow_with_rng(
+  graph: &mut AcyclicGraph, cfg: &Config, levels: usize, rng: &mut impl Rng,
+) -> Result<(), Error> {
   let petnames = petname::Petnames::default();
 
-  let width_dist =
-    Normal::new(cfg.width_mean, cfg.width_std_dev).context(RandNormalDistributionSnafu)?;
-  let child_dist =
-    Normal::new(cfg.child_mean, cfg.child_std_dev).context(RandNormalDistributionSnafu)?;
+  // the depth of a node without level is the longest path from a root, a
+  // generated graph has the same levels
+  let depths = graph.depths();
+  for (uuid, depth) in depths {
+    let node = graph.get_node_mut(uuid).context(AcyclicGraphSnafu {})?;
+    if node.level().is_none() {
+      node.set_level(depth);
+    }
+  }
+
+  let Some(deepest) = graph.nodes().values().filter_map(Node::level).max() else {
+    return Ok(());
+  };
+  let current = graph
+    .nodes()
+    .iter()
+    .filter(|(_, node)| node.level() == Some(deepest))
+    .map(|(&uuid, _)| uuid)
+    .sorted()
+    .collect();
+
+  let levels = deepest + 1..deepest + 1 + levels;
+  match cfg.strategy {
+    Strategy::BreadthFirst => {
+      add_levels(graph, cfg, current, levels, &petnames, rng, |_, _, _, _| {
+        Ok(())
+      })?
+    }
+    Strategy::DepthFirst => add_subtrees(graph, cfg, current, levels, &petnames, rng)?,
+  }
+  add_shape(graph, cfg, rng)
+}
+
+/// Exact shape of a graph built by [`generate_exact`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Exact {
+  pub nodes: usize,
+  /// Number of levels, root level included
+  pub depth: usize,
+  pub edges: usize,
+}
+
+/// Like [`generate`] but with exactly the nodes, levels and edges of `exact`
+/// instead of following the distributions of the config, which is only used
+/// for the name, the node data and the UUIDs
+pub fn generate_exact(cfg: &Config, exact: &Exact) -> Result<AcyclicGraph, Error> {
+  generate_exact_with_rng(cfg, exact, &mut StdRng::seed_from_u64(cfg.seed))
+}
+
+/// Like [`generate_exact`] with any RNG, the seed of the config is ignored
+pub fn generate_exact_with_rng(
+  cfg: &Config, exact: &Exact, rng: &mut impl Rng,
+) -> Result<AcyclicGraph, Error> {
+  let widths = exact_widths(exact, rng)?;
+  let &Exact {
+    nodes,
+    depth,
+    edges,
+  } = exact;
 
+  let petnames = petname::Petnames::default();
   let name = cfg.name.as_ref().cloned().unwrap_or_else(|| {
     petnames
-      .generate(&mut rng, 2, "_")
+      .generate(rng, 2, "_")
       .unwrap_or_else(|| "output".to_string())
   });
   let mut graph = AcyclicGraph::new(name);
+  graph.set_uuid_version(cfg.uuid_version);
+
+  // the spanning edges, a random parent on the level above
+  let mut levels: Vec<Vec<Uuid>> = Vec::with_capacity(depth);
+  for (level, &width) in widths.iter().enumerate() {
+    let mut current = Vec::with_capacity(width);
+    for _ in 0..width {
+      let name = match level {
+        0 => Some("Root".to_string()),
+        _ => petnames.generate(rng, 1, "_"),
+      };
+      let data = node_data(cfg, name, rng);
+      let (uuid, _) = graph.add_node_with_rng(data, rng);
+      graph
+        .get_node_mut(uuid)
+        .context(AcyclicGraphSnafu {})?
+        .set_level(level);
+      if let Some(above) = levels.last() {
+        let parent = above[rng.random_range(0..above.len())];
+        add_edge(&mut graph, cfg, parent, uuid, rng)?;
+      }
+      current.push(uuid);
+    }
+    levels.push(current);
+  }
+
+  let order: Vec<(Uuid, usize)> = levels
+    .iter()
+    .enumerate()
+    .flat_map(|(level, uuids)| uuids.iter().map(move |&uuid| (uuid, level)))
+    .collect();
+  add_cross_edges(&mut graph, cfg, &order, edges - (nodes - 1), rng)?;
+
+  Ok(graph)
+}
+
+// the width of each level of an exact generation, the first draws of `rng`
+fn exact_widths(exact: &Exact, rng: &mut impl Rng) -> Result<Vec<usize>, Error> {
+  let &Exact {
+    nodes,
+    depth,
+    edges,
+  } = exact;
+  let infeasible = InfeasibleSnafu {
+    nodes,
+    depth,
+    edges,
+  };
+  // a single root and every other node has a parent on the level above
+  ensure!(
+    depth >= 1 && depth <= nodes && (depth > 1 || nodes == 1) && edges >= nodes - 1,
+    infeasible
+  );
+
+  // random widths, then nodes are moved from the widest level to the
+  // narrowest until there is room for the edges, equal widths allow the most
+  let mut widths = vec![1; depth];
+  for _ in depth..nodes {
+    widths[rng.random_range(1..depth)] += 1;
+  }
+  while max_edges(&widths) < edges {
+    let (narrowest, _) = widths
+      .iter()
+      .enumerate()
+      .skip(1)
+      .min_by_key(|(_, w)| **w)
+      .unwrap();
+    let (widest, _) = widths
+      .iter()
+      .enumerate()
+      .skip(1)
+      .max_by_key(|(_, w)| **w)
+      .unwrap();
+    ensure!(widths[widest] - widths[narrowest] > 1, infeasible);
+    widths[widest] -= 1;
+    widths[narrowest] += 1;
+  }
+  Ok(widths)
+}
+
+/// A well-known shape built by [`generate_preset`], with the depth of the
+/// config and its width mean as width
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Preset {
+  /// Levels of a single node alternating with levels of `width` nodes, each
+  /// node linked to every node of the next level
+  Diamond,
+  /// A chain of `depth` nodes
+  Pipeline,
+  /// `width` roots linked to a single sink
+  FanIn,
+  /// A root linked to `width` leaves
+  FanOut,
+  /// `depth` levels of `width` nodes, each node linked to every node of the
+  /// next level
+  Layered,
+  /// A balanced binary tree of `depth` levels
+  BinaryTree,
+}
+
+/// Most nodes and most edges of a preset, the nodes have u32 IDs
+pub const MAX_PRESET_SIZE: usize = u32::MAX as usize;
+
+impl Preset {
+  /// Number of nodes of each level, None above [`MAX_PRESET_SIZE`] nodes
+  pub fn widths(self, depth: usize, width: usize) -> Option<Vec<usize>> {
+    if depth > MAX_PRESET_SIZE || width > MAX_PRESET_SIZE {
+      return None;
+    }
+    let widths: Vec<usize> = match self {
+      Preset::Diamond => (0..depth)
+        .map(|level| match level % 2 {
+          0 => 1,
+          _ => width,
+        })
+        .collect(),
+      Preset::Pipeline => vec![1; depth],
+      Preset::FanIn => vec![width, 1],
+      Preset::FanOut => vec![1, width],
+      Preset::Layered => vec![width; depth],
+      Preset::BinaryTree => (0..depth)
+        .map(|level| {
+          2usize
+            .checked_pow(u32::try_from(level).ok()?)
+            .filter(|&width| width <= MAX_PRESET_SIZE)
+        })
+        .collect::<Option<_>>()?,
+    };
+    widths
+      .iter()
+      .try_fold(0usize, |nodes, &width| nodes.checked_add(width))
+      .filter(|&nodes| nodes <= MAX_PRESET_SIZE)
+      .map(|_| widths)
+  }
+
+  /// Number of edges of the levels of [`Preset::widths`], None above
+  /// [`MAX_PRESET_SIZE`]
+  pub fn edges(self, widths: &[usize]) -> Option<usize> {
+    widths
+      .windows(2)
+      .try_fold(0usize, |edges, pair| {
+        let between = match self {
+          // a single parent per node
+          Preset::BinaryTree => pair[1],
+          _ => pair[0].checked_mul(pair[1])?,
+        };
+        edges.checked_add(between)
+      })
+      .filter(|&edges| edges <= MAX_PRESET_SIZE)
+  }
+}
+
+// the widths and the number of edges of a preset sized by the config
+fn preset_widths(cfg: &Config, preset: Preset) -> Result<(Vec<usize>, usize), Error> {
+  let width = cfg.width_mean.round().max(1.0) as usize;
+  preset
+    .widths(cfg.depth, width)
+    .and_then(|widths| {
+      let edges = preset.edges(&widths)?;
+      Some((widths, edges))
+    })
+    .context(PresetSizeSnafu {
+      preset,
+      depth: cfg.depth,
+      width,
+    })
+}
+
+/// Build the shape of `preset` instead of following the distributions of the
+/// config, which is only used for the size, the name, the node and edge data
+/// and the UUIDs, so the same seed gives the same graph
+pub fn generate_preset(cfg: &Config, preset: Preset) -> Result<AcyclicGraph, Error> {
+  generate_preset_with_rng(cfg, preset, &mut StdRng::seed_from_u64(cfg.seed))
+}
+
+/// Like [`generate_preset`] with any RNG, the seed of the config is ignored
+pub fn generate_preset_with_rng(
+  cfg: &Config, preset: Preset, rng: &mut impl Rng,
+) -> Result<AcyclicGraph, Error> {
+  let (widths, _) = preset_widths(cfg, preset)?;
+
+  let petnames = petname::Petnames::default();
+  let name = cfg.name.as_ref().cloned().unwrap_or_else(|| {
+    petnames
+      .generate(rng, 2, "_")
+      .unwrap_or_else(|| "output".to_string())
+  });
+  let mut graph = AcyclicGraph::new(name);
+  graph.set_uuid_version(cfg.uuid_version);
+
+  let mut above: Vec<Uuid> = Vec::new();
+  for (level, &width) in widths.iter().enumerate() {
+    let mut current = Vec::with_capacity(width);
+    for i in 0..width {
+      let name = match (level, width) {
+        (0, 1) => Some("Root".to_string()),
+        _ => petnames.generate(rng, 1, "_"),
+      };
+      let data = node_data(cfg, name, rng);
+      let (uuid, _) = graph.add_node_with_rng(data, rng);
+      graph
+        .get_node_mut(uuid)
+        .context(AcyclicGraphSnafu {})?
+        .set_level(level);
+      // the other presets link every pair of consecutive levels
+      let parents = match preset {
+        Preset::BinaryTree => &above[i / 2..above.len().min(i / 2 + 1)],
+        _ => &above[..],
+      };
+      for &parent in parents {
+        add_edge(&mut graph, cfg, parent, uuid, rng)?;
+      }
+      current.push(uuid);
+    }
+    above = current;
+  }
+
+  Ok(graph)
+}
+
+/// Extra edges of [`Shape::Dag`] for a tree with `tree` edges
+fn extra_edges(cfg: &Config, tree: usize) -> usize {
+  (cfg.extra_edge_density * tree as f64).round().max(0.0) as usize
+}
+
+// the extra edges of the dag shape missing from a generated graph, the nodes
+// have a recorded level
+fn add_shape(graph: &mut AcyclicGraph, cfg: &Config, rng: &mut impl Rng) -> Result<(), Error> {
+  if cfg.shape == Shape::Tree {
+    return Ok(());
+  }
+  // by level then by UUID, the order of the nodes
+  let order: Vec<(Uuid, usize)> = graph
+    .nodes()
+    .iter()
+    .filter_map(|(&uuid, node)| Some((uuid, node.level()?)))
+    .sorted_by_key(|&(_, level)| level)
+    .collect();
+  let tree = order.len().saturating_sub(1);
+  let edges: usize = graph.nodes().values().map(|node| node.childs().len()).sum();
+  let missing = extra_edges(cfg, tree).saturating_sub(edges.saturating_sub(tree));
+  add_cross_edges(graph, cfg, &order, missing, rng)
+}
+
+// `missing` edges from a node of `order`, pairs of a node and its level, to a
+// node of a deeper level, or as many as there is room for. They always go down
+// so no cycle can appear.
+fn add_cross_edges(
+  graph: &mut AcyclicGraph, cfg: &Config, order: &[(Uuid, usize)], missing: usize,
+  rng: &mut impl Rng,
+) -> Result<(), Error> {
+  let mut widths = Vec::new();
+  for &(_, level) in order {
+    if widths.len() <= level {
+      widths.resize(level + 1, 0);
+    }
+    widths[level] += 1;
+  }
+  let edges: usize = order
+    .iter()
+    .map(|(uuid, _)| graph.nodes()[uuid].childs().len())
+    .sum();
+  let free = max_edges(&widths).saturating_sub(edges);
+  let mut missing = missing.min(free);
+  if missing * 2 <= free {
+    // sparse, draw pairs until enough are new
+    while missing > 0 {
+      let (a, level_a) = order[rng.random_range(0..order.len())];
+      let (b, level_b) = order[rng.random_range(0..order.len())];
+      let (parent, child) = match level_a.cmp(&level_b) {
+        std::cmp::Ordering::Less => (a, b),
+        std::cmp::Ordering::Greater => (b, a),
+        std::cmp::Ordering::Equal => continue,
+      };
+      if graph.nodes()[&parent].childs().contains(&child) {
+        continue;
+      }
+      add_edge(graph, cfg, parent, child, rng)?;
+      missing -= 1;
+    }
+  } else {
+    // dense, sample among every free pair
+    let pairs: Vec<(Uuid, Uuid)> = order
+      .iter()
+      .flat_map(|&(parent, level)| {
+        order
+          .iter()
+          .filter(move |&&(_, other)| other > level)
+          .map(move |&(child, _)| (parent, child))
+      })
+      .filter(|(parent, child)| !graph.nodes()[parent].childs().contains(child))
+      .collect();
+    for i in index::sample(rng, pairs.len(), missing) {
+      let (parent, child) = pairs[i];
+      add_edge(graph, cfg, parent, child, rng)?;
+    }
+  }
+  Ok(())
+}
+
+// every pair of nodes on different levels
+fn max_edges(widths: &[usize]) -> usize {
+  let mut above = 0;
+  widths
+    .iter()
+    .map(|&width| {
+      let edges = above * width;
+      above += width;
+      edges
+    })
+    .sum()
+}
+
+// `done` is called before each level but the first with the graph, the nodes
+// of the previous level and the level
+fn add_levels<R: Rng>(
+  graph: &mut AcyclicGraph, cfg: &Config, mut current: Vec<Uuid>, levels: Range<usize>,
+  petnames: &petname::Petnames, rng: &mut R,
+  mut done: impl FnMut(&AcyclicGraph, &[Uuid], usize, &R) -> Result<(), Error>,
+) -> Result<(), Error> {
+  let (width_dist, child_dist) = samplers(cfg)?;
 
-  let root = graph.add_node_with_rng("Root", &mut rng);
-  let mut current = vec![root.0];
   let mut next = Vec::new();
 
-  for _ in 1..cfg.depth {
-    let n = width_dist.sample(&mut rng).round().max(1.0) as usize;
+  let first = levels.start;
+  for level in levels {
+    if level != first {
+      done(graph, &current, level, rng)?;
+    }
+    let n = width_dist.sample(rng).round().max(1.0) as usize;
 
     next.clear();
     let mut i = 0;
-    current.shuffle(&mut rng);
+    current.shuffle(rng);
     'outer: for &node in &current {
-      let k = child_dist.sample(&mut rng).round().max(0.0) as usize;
+      let k = child_dist.sample(rng).round().max(0.0) as usize;
 
       for _ in 0..k {
         // limit total width
@@ -72,9 +1224,14 @@ pub fn generate(cfg: &Config) -> Result<AcyclicGraph, Error> {
         } else {
           i += 1;
         }
-        let name = petnames.generate(&mut rng, 1, "_");
-        let (uuid, _) = graph.add_node_with_rng(name, &mut rng);
-        graph.add_child(node, uuid).context(AcyclicGraphSnafu {})?;
+        let name = petnames.generate(rng, 1, "_");
+        let data = node_data(cfg, name, rng);
+        let (uuid, _) = graph.add_node_with_rng(data, rng);
+        graph
+          .get_node_mut(uuid)
+          .context(AcyclicGraphSnafu {})?
+          .set_level(level);
+        add_edge(graph, cfg, node, uuid, rng)?;
         next.push(uuid);
       }
     }
@@ -82,5 +1239,571 @@ pub fn generate(cfg: &Config) -> Result<AcyclicGraph, Error> {
     std::mem::swap(&mut current, &mut next);
   }
 
-  Ok(graph)
+  Ok(())
+}
+
+// like add_levels but a child gets its whole subtree before its next sibling,
+// with a stack rather than recursion to support deep graphs
+fn add_subtrees(
+  graph: &mut AcyclicGraph, cfg: &Config, mut current: Vec<Uuid>, levels: Range<usize>,
+  petnames: &petname::Petnames, rng: &mut impl Rng,
+) -> Result<(), Error> {
+  let (width_dist, child_dist) = samplers(cfg)?;
+
+  // width limit and number of nodes of each level
+  let mut widths: Vec<(usize, usize)> = Vec::new();
+  current.shuffle(rng);
+  // nodes with the level of their childs, the next one to expand on top
+  let mut stack: Vec<(Uuid, usize)> = current
+    .into_iter()
+    .rev()
+    .map(|uuid| (uuid, levels.start))
+    .collect();
+  while let Some((node, level)) = stack.pop() {
+    if level >= levels.end {
+      continue;
+    }
+    // the levels are reached in order
+    let i = level - levels.start;
+    if widths.len() == i {
+      let n = width_dist.sample(rng).round().max(1.0) as usize;
+      widths.push((n, 0));
+    }
+
+    let k = child_dist.sample(rng).round().max(0.0) as usize;
+    let first = stack.len();
+    for _ in 0..k {
+      let (n, count) = &mut widths[i];
+      // limit total width
+      if count >= n {
+        break;
+      }
+      *count += 1;
+      let name = petnames.generate(rng, 1, "_");
+      let data = node_data(cfg, name, rng);
+      let (uuid, _) = graph.add_node_with_rng(data, rng);
+      graph
+        .get_node_mut(uuid)
+        .context(AcyclicGraphSnafu {})?
+        .set_level(level);
+      add_edge(graph, cfg, node, uuid, rng)?;
+      stack.push((uuid, level + 1));
+    }
+    // the first child on top
+    stack[first..].reverse();
+  }
+
+  Ok(())
+}
+
+fn add_edge(
+  graph: &mut AcyclicGraph, cfg: &Config, parent: Uuid, child: Uuid, rng: &mut impl Rng,
+) -> Result<(), Error> {
+  graph
+    .add_child(parent, child)
+    .context(AcyclicGraphSnafu {})?;
+  let mut data = Map::new();
+  if let Some(schema) = &cfg.edge_schema {
+    data = schema.sample(rng);
+  }
+  if let Some(mean) = cfg.edge_weight_mean {
+    let weight = Normal::new(mean, cfg.edge_weight_std_dev)
+      .context(RandNormalDistributionSnafu)?
+      .sample(rng)
+      .max(0.0);
+    data.insert("weight".to_string(), Value::from(weight));
+  }
+  if !data.is_empty() {
+    graph
+      .set_edge_data(parent, child, data)
+      .context(AcyclicGraphSnafu {})?;
+  }
+  Ok(())
+}
+
+fn node_data(cfg: &Config, name: Option<String>, rng: &mut impl Rng) -> NodeData {
+  match &cfg.schema {
+    Some(schema) => {
+      let mut object = Map::from_iter([("name".to_string(), Value::from(name))]);
+      object.extend(schema.sample(rng));
+      Value::Object(object).into()
+    }
+    None => name.into(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashSet;
+
+  use rand::rngs::SmallRng;
+
+  use super::*;
+  use crate::schema::Attribute;
+
+  #[test]
+  fn test_grow() {
+    let cfg = Config {
+      depth: 3,
+      width_mean: 4.0,
+      width_std_dev: 0.0,
+      child_mean: 2.0,
+      child_std_dev: 0.0,
+      seed: 42,
+      ..Default::default()
+    };
+    let mut graph = generate(&cfg).unwrap();
+    let nodes = graph.nodes().clone();
+
+    grow(&mut graph, &cfg, 2, 7).unwrap();
+    // existing nodes are untouched except the new children of the last level
+    for (uuid, node) in &nodes {
+      let grown = graph.get_node(*uuid).unwrap();
+      assert_eq!(grown.data(), node.data());
+      assert!(grown.childs().is_superset(node.childs()));
+    }
+    let levels = graph.nodes().values().filter_map(Node::level).max();
+    assert_eq!(levels, Some(4));
+    assert_eq!(graph.nodes().len(), nodes.len() + 8);
+    assert_eq!(graph.check_invariants(), Ok(()));
+
+    // an imported graph has no level, root -> a -> b, root -> b
+    let mut imported = AcyclicGraph::new("Imported");
+    let [root, a, b] = ["Root", "A", "B"].map(|name| imported.add_node(name).0);
+    for (parent, child) in [(root, a), (a, b), (root, b)] {
+      imported.add_child(parent, child).unwrap();
+    }
+    grow(&mut imported, &cfg, 1, 7).unwrap();
+    assert_eq!(imported.get_node(b).unwrap().level(), Some(2));
+    assert_eq!(imported.get_node(b).unwrap().childs().len(), 2);
+    assert_eq!(imported.nodes().len(), 5);
+  }
+
+  #[test]
+  fn test_seed() {
+    let cfg = Config {
+      depth: 4,
+      width_mean: 5.0,
+      width_std_dev: 1.0,
+      child_mean: 2.0,
+      seed: 42,
+      ..Default::default()
+    };
+    let graph = generate(&cfg).unwrap();
+    assert_eq!(generate(&cfg).unwrap(), graph);
+    assert_ne!(
+      generate(&Config {
+        seed: 7,
+        ..cfg.clone()
+      })
+      .unwrap(),
+      graph
+    );
+
+    let with_rng = generate_with_rng(&cfg, &mut StdRng::seed_from_u64(42)).unwrap();
+    assert_eq!(with_rng, graph);
+  }
+
+  #[test]
+  fn test_generate_with_rng() {
+    let cfg = Config {
+      depth: 4,
+      width_mean: 5.0,
+      width_std_dev: 1.0,
+      child_mean: 2.0,
+      ..Default::default()
+    };
+    let graph = generate_with_rng(&cfg, &mut SmallRng::seed_from_u64(7)).unwrap();
+    assert_eq!(graph.check_invariants(), Ok(()));
+    assert_eq!(
+      generate_with_rng(&cfg, &mut SmallRng::seed_from_u64(7)).unwrap(),
+      graph
+    );
+
+    let mut rng = SmallRng::seed_from_u64(7);
+    let mut grown = generate_with_rng(&cfg, &mut rng).unwrap();
+    grow_with_rng(&mut grown, &cfg, 1, &mut rng).unwrap();
+    assert!(grown.nodes().len() > graph.nodes().len());
+  }
+
+  #[test]
+  fn test_dists() {
+    let cfg = Config {
+      depth: 4,
+      width_mean: 6.0,
+      width_std_dev: 2.0,
+      child_mean: 2.0,
+      width_dist: Dist::Constant,
+      child_dist: Dist::Constant,
+      seed: 42,
+      ..Default::default()
+    };
+    // 2 childs for each node until the width of 6
+    assert_eq!(plan(&cfg).unwrap().widths, [1, 2, 4, 6]);
+    assert_eq!(generate(&cfg).unwrap().nodes().len(), 13);
+
+    for dist in [Dist::Uniform, Dist::Poisson, Dist::Geometric] {
+      let cfg = Config {
+        width_dist: dist,
+        child_dist: dist,
+        ..cfg.clone()
+      };
+      let graph = generate(&cfg).unwrap();
+      assert_eq!(graph.check_invariants(), Ok(()));
+      assert_eq!(
+        crate::validator::validate(&graph, &cfg).map_err(|e| e.to_string()),
+        Ok(())
+      );
+    }
+
+    let cfg = Config {
+      child_mean: 0.0,
+      child_dist: Dist::Poisson,
+      ..cfg
+    };
+    assert!(matches!(
+      generate(&cfg),
+      Err(Error::InvalidDistribution {
+        dist: Dist::Poisson,
+        ..
+      })
+    ));
+  }
+
+  #[test]
+  fn test_generate_preset() {
+    let cfg = Config {
+      depth: 3,
+      width_mean: 3.0,
+      width_std_dev: 1.0,
+      child_mean: 2.0,
+      seed: 42,
+      ..Default::default()
+    };
+    let edges = |graph: &AcyclicGraph| -> usize {
+      graph.nodes().values().map(|node| node.childs().len()).sum()
+    };
+    for (preset, nodes, expected) in [
+      (Preset::Diamond, 5, 6),
+      (Preset::Pipeline, 3, 2),
+      (Preset::FanIn, 4, 3),
+      (Preset::FanOut, 4, 3),
+      (Preset::Layered, 9, 18),
+      (Preset::BinaryTree, 7, 6),
+    ] {
+      let graph = generate_preset(&cfg, preset).unwrap();
+      assert_eq!(graph.check_invariants(), Ok(()));
+      assert_eq!(
+        (graph.nodes().len(), edges(&graph)),
+        (nodes, expected),
+        "{preset:?}"
+      );
+      assert_eq!(generate_preset(&cfg, preset).unwrap(), graph);
+      let plan = plan_preset(&cfg, preset).unwrap();
+      assert_eq!(
+        (plan.nodes(), plan.edges()),
+        (nodes, expected),
+        "{preset:?}"
+      );
+    }
+
+    // a single parent on the level above
+    let tree = generate_preset(&cfg, Preset::BinaryTree).unwrap();
+    assert!(tree.parents().values().all(|parents| parents.len() == 1));
+    assert_eq!(Preset::Diamond.widths(5, 2), Some(vec![1, 2, 1, 2, 1]));
+    assert_eq!(Preset::Diamond.edges(&[1, 2, 1, 2, 1]), Some(8));
+    assert_eq!(Preset::BinaryTree.edges(&[1, 2, 4]), Some(6));
+
+    // refused before allocating the levels
+    for (preset, depth, width_mean) in [
+      (Preset::FanIn, 2, 1e30),
+      (Preset::BinaryTree, 70, 1.0),
+      (Preset::BinaryTree, 33, 1.0),
+      (Preset::Pipeline, usize::MAX, 1.0),
+      (Preset::Layered, 3, 100_000.0),
+    ] {
+      let cfg = Config {
+        depth,
+        width_mean,
+        ..cfg.clone()
+      };
+      assert!(
+        matches!(generate_preset(&cfg, preset), Err(Error::PresetSize { .. })),
+        "{preset:?}"
+      );
+    }
+  }
+
+  #[test]
+  fn test_generate_exact() {
+    let cfg = Config {
+      depth: 0,
+      width_mean: 0.0,
+      width_std_dev: 0.0,
+      child_mean: 0.0,
+      child_std_dev: 0.0,
+      seed: 42,
+      ..Default::default()
+    };
+    // sparse, dense and complete
+    for (nodes, depth, edges) in [(50, 6, 80), (20, 4, 120), (10, 10, 45), (1, 1, 0)] {
+      let exact = Exact {
+        nodes,
+        depth,
+        edges,
+      };
+      let graph = generate_exact(&cfg, &exact).unwrap();
+      assert_eq!(graph.check_invariants(), Ok(()));
+      assert_eq!(graph.nodes().len(), nodes);
+      let count: usize = graph.nodes().values().map(|node| node.childs().len()).sum();
+      assert_eq!(count, edges);
+      assert_eq!(graph.depths().into_values().max(), Some(depth - 1));
+      let childs: HashSet<&Uuid> = graph.nodes().values().flat_map(Node::childs).collect();
+      assert_eq!(graph.nodes().len() - childs.len(), 1);
+      assert_eq!(generate_exact(&cfg, &exact).unwrap(), graph);
+      // the widths of the generated levels
+      let plan = plan_exact(&cfg, &exact).unwrap();
+      assert_eq!((plan.nodes(), plan.edges()), (nodes, edges));
+      let levels = graph
+        .nodes()
+        .values()
+        .counts_by(|node| node.level().unwrap());
+      assert!(
+        plan
+          .widths
+          .iter()
+          .enumerate()
+          .all(|(level, width)| levels[&level] == *width)
+      );
+    }
+
+    for (nodes, depth, edges) in [(0, 0, 0), (5, 6, 10), (5, 1, 4), (5, 3, 3), (5, 3, 9)] {
+      let exact = Exact {
+        nodes,
+        depth,
+        edges,
+      };
+      assert!(matches!(
+        generate_exact(&cfg, &exact),
+        Err(Error::Infeasible { .. })
+      ));
+    }
+  }
+
+  #[test]
+  fn test_depth_first() {
+    let cfg = Config {
+      depth: 4,
+      width_mean: 100.0,
+      width_std_dev: 0.0,
+      child_mean: 2.0,
+      child_std_dev: 0.0,
+      seed: 42,
+      uuid_version: UuidVersion::V7,
+      strategy: Strategy::DepthFirst,
+      ..Default::default()
+    };
+    let graph = generate(&cfg).unwrap();
+    assert_eq!(graph.check_invariants(), Ok(()));
+    assert_eq!(graph.nodes().len(), 15);
+    // version 7 UUIDs sort in creation order, the first child of the root
+    // gets its subtree before its sibling
+    let levels: Vec<Option<usize>> = graph
+      .nodes()
+      .iter()
+      .sorted_by_key(|(uuid, _)| **uuid)
+      .map(|(_, node)| node.level())
+      .collect();
+    assert_eq!(levels[..9], [0, 1, 1, 2, 2, 3, 3, 3, 3].map(Some));
+    assert_eq!(generate(&cfg).unwrap(), graph);
+
+    // the first subtrees take the width
+    let narrow = Config {
+      width_mean: 3.0,
+      ..cfg.clone()
+    };
+    let graph = generate(&narrow).unwrap();
+    assert_eq!(graph.nodes().len(), 1 + 2 + 3 + 3);
+
+    let mut grown = generate(&cfg).unwrap();
+    grow(&mut grown, &cfg, 1, 7).unwrap();
+    assert_eq!(grown.nodes().len(), 15 + 16);
+    assert!(matches!(
+      generate_checkpointed(&cfg, None, Duration::ZERO, |_| Ok(())),
+      Err(Error::CheckpointStrategy)
+    ));
+  }
+
+  #[test]
+  fn test_dag_shape() {
+    let cfg = Config {
+      depth: 4,
+      width_mean: 8.0,
+      width_std_dev: 0.0,
+      child_std_dev: 0.0,
+      seed: 42,
+      shape: Shape::Dag,
+      extra_edge_density: 0.5,
+      ..Default::default()
+    };
+    let graph = generate(&cfg).unwrap();
+    assert_eq!(graph.check_invariants(), Ok(()));
+    let edges = |graph: &AcyclicGraph| -> usize {
+      graph.nodes().values().map(|node| node.childs().len()).sum()
+    };
+    // 1 + 3 + 8 + 8 nodes
+    assert_eq!(graph.nodes().len(), 20);
+    assert_eq!(edges(&graph), 19 + 10);
+    assert!(graph.parents().values().any(|parents| parents.len() > 1));
+    assert_eq!(
+      crate::validator::validate(&graph, &cfg).map_err(|e| e.to_string()),
+      Ok(())
+    );
+    assert_eq!(plan(&cfg).unwrap().edges(), 29);
+
+    // the density stays the same after a growth
+    let mut grown = graph.clone();
+    grow(&mut grown, &cfg, 1, 7).unwrap();
+    let tree = grown.nodes().len() - 1;
+    assert_eq!(edges(&grown), tree + (tree as f64 * 0.5).round() as usize);
+
+    let checkpointed = generate_checkpointed(&cfg, None, Duration::MAX, |_| Ok(())).unwrap();
+    assert_eq!(edges(&checkpointed), 29);
+  }
+
+  #[test]
+  fn test_edge_schema() {
+    let attribute: Attribute = "latency=lognormal(1,0.5)".parse().unwrap();
+    let cfg = Config {
+      depth: 4,
+      width_mean: 5.0,
+      width_std_dev: 1.0,
+      child_mean: 2.0,
+      seed: 42,
+      edge_schema: Some(Schema::from_iter([attribute])),
+      edge_weight_mean: Some(1.0),
+      edge_weight_std_dev: 2.0,
+      ..Default::default()
+    };
+    let graph = generate(&cfg).unwrap();
+    let mut weights = Vec::new();
+    for (&parent, node) in graph.nodes() {
+      for &child in node.childs() {
+        let data = graph.edge_data(parent, child).unwrap();
+        assert!(data["latency"].as_f64().unwrap() > 0.0);
+        weights.extend(graph.edge_weight(parent, child));
+      }
+    }
+    assert_eq!(weights.len(), graph.nodes().len() - 1);
+    // the negative draws are clamped
+    assert!(weights.iter().all(|&weight| weight >= 0.0));
+    assert!(weights.contains(&0.0));
+  }
+
+  #[test]
+  fn test_checkpoint() {
+    let cfg = Config {
+      depth: 6,
+      width_mean: 5.0,
+      width_std_dev: 1.0,
+      child_mean: 2.0,
+      seed: 42,
+      uuid_version: UuidVersion::V7,
+      ..Default::default()
+    };
+    let graph = generate(&cfg).unwrap();
+
+    let mut checkpoints: Vec<Vec<u8>> = Vec::new();
+    let checkpointed = generate_checkpointed(&cfg, None, Duration::ZERO, |checkpoint| {
+      checkpoints.push(Vec::new());
+      checkpoint.write(checkpoints.last_mut().unwrap())
+    })
+    .unwrap();
+    assert_eq!(checkpointed, graph);
+    // before each level but the root and the first one
+    assert_eq!(checkpoints.len(), 4);
+
+    let resume = Checkpoint::from_reader(checkpoints[1].as_slice()).unwrap();
+    assert_eq!((resume.seed(), resume.level()), (42, 3));
+    let resumed = generate_checkpointed(
+      &cfg,
+      Some(resume.clone()),
+      Duration::MAX,
+      |_| unreachable!(),
+    );
+    assert_eq!(resumed.unwrap(), graph);
+
+    let others = [
+      Config {
+        seed: 7,
+        ..cfg.clone()
+      },
+      Config {
+        shape: Shape::Dag,
+        ..cfg.clone()
+      },
+      Config {
+        edge_weight_mean: Some(3.0),
+        ..cfg.clone()
+      },
+      Config {
+        uuid_version: UuidVersion::V4,
+        ..cfg.clone()
+      },
+      Config {
+        schema: Some(
+          serde_json::from_value(serde_json::json!({ "age": { "int": { "min": 1, "max": 9 } } }))
+            .unwrap(),
+        ),
+        ..cfg.clone()
+      },
+    ];
+    for other in others {
+      assert!(matches!(
+        generate_checkpointed(
+          &other,
+          Some(resume.clone()),
+          Duration::MAX,
+          |_| unreachable!()
+        ),
+        Err(Error::CheckpointMismatch)
+      ));
+    }
+
+    // the checkpoints made before the other parameters were saved
+    let mut old: Value = serde_json::from_slice(&checkpoints[1]).unwrap();
+    let old = old.as_object_mut().unwrap();
+    for key in [
+      "name",
+      "schema",
+      "shape",
+      "extra_edge_density",
+      "uuid_version",
+    ] {
+      old.remove(key);
+    }
+    let old = Checkpoint::from_reader(serde_json::to_vec(old).unwrap().as_slice()).unwrap();
+    assert!(!old.matches(&cfg));
+    assert!(old.matches(&Config {
+      uuid_version: UuidVersion::V4,
+      ..cfg.clone()
+    }));
+  }
+
+  #[test]
+  fn test_parameters() {
+    let cfg = Config {
+      depth: 4,
+      width_mean: 5.0,
+      width_std_dev: 1.0,
+      child_mean: 2.0,
+      ..Default::default()
+    };
+    let parameters: Parameters =
+      serde_json::from_value(serde_json::json!({ "depth": 6, "seed": 9 })).unwrap();
+    let applied = parameters.apply(&cfg);
+    assert_eq!((applied.depth, applied.seed), (6, 9));
+    assert_eq!(applied.width_mean, cfg.width_mean);
+    assert!(serde_json::from_value::<Parameters>(serde_json::json!({ "deep": 6 })).is_err());
+  }
 }
@@ -1,4 +1,47 @@
+mod ancestry;
+#[cfg(feature = "testing")]
+mod arbitrary;
+mod centrality;
+mod data;
+mod diff;
+mod dimacs;
+mod dominators;
+#[cfg(feature = "dot")]
+mod dot_import;
+mod edge_data;
+mod equality;
+mod gexf;
+mod graphml;
+mod history;
+mod html;
+mod ids;
+mod jgf;
+mod metis;
+mod names;
+mod node_map;
+mod observer;
+mod ordering;
+mod paths;
+#[cfg(feature = "persistent")]
+mod persistent;
+mod poset;
+mod sanitize;
+mod serialize;
+mod similarity;
+mod split;
+mod style;
+mod subgraph;
+mod sync;
+mod topo;
+mod topo_order;
+mod transitive;
+mod traversal;
+mod uuids;
+#[cfg(feature = "xlsx")]
+mod xlsx;
+
 use std::{
+  cmp::Reverse,
   collections::{
     HashMap,
     HashSet,
@@ -9,14 +52,98 @@ use std::{
     Display,
     Formatter,
   },
+  io,
 };
 
-use itertools::Itertools;
-use rand::Rng;
+use itertools::{
+  Either,
+  Itertools,
+};
 use short_uuid::ShortUuid;
 use snafu::Snafu;
 use uuid::Uuid;
 
+#[cfg(feature = "testing")]
+pub use self::arbitrary::ArbitraryParameters;
+#[cfg(feature = "persistent")]
+pub use self::persistent::PersistentGraph;
+pub use self::{
+  data::DataFile,
+  diff::{
+    DiffDot,
+    GraphDiff,
+  },
+  dimacs::Dimacs,
+  edge_data::EdgeData,
+  gexf::Gexf,
+  graphml::Graphml,
+  history::SnapshotId,
+  html::Html,
+  ids::IdMapping,
+  jgf::Jgf,
+  metis::{
+    Metis,
+    MetisMapping,
+  },
+  node_map::NodeMap,
+  observer::{
+    Observer,
+    Removed,
+  },
+  sanitize::{
+    Sanitize,
+    is_unsafe,
+  },
+  similarity::Similarity,
+  split::Part,
+  style::{
+    EdgeStyle,
+    NodeShape,
+    NodeShapes,
+    RankDir,
+  },
+  sync::SyncAcyclicGraph,
+  topo::Topo,
+  traversal::{
+    Bfs,
+    DfsPostOrder,
+    TopologicalGenerations,
+    Visit,
+  },
+  uuids::{
+    UuidVersion,
+    parse_uuid,
+  },
+};
+use self::{
+  edge_data::edge_text,
+  history::{
+    Change,
+    History,
+  },
+  html::Escape,
+  names::Names,
+  observer::Observers,
+  sanitize::{
+    DotEscape,
+    LineEscape,
+    MermaidText,
+  },
+  style::{
+    Stroke,
+    Strokes,
+  },
+  topo_order::TopoOrder,
+  uuids::Uuids,
+};
+use crate::{
+  schema::Timestamp,
+  template::{
+    Placeholder,
+    Template,
+  },
+};
+
 #[derive(Debug, Snafu, PartialEq)]
 pub enum Error {
   #[snafu(display("Cycle detected {src} => {dst}"))]
@@ -25,17 +152,51 @@ pub enum Error {
   UuidNotFound { uuid: Uuid },
   #[snafu(display("Child already exist from {parent} to {child}"))]
   ChildAlreadyExist { parent: Uuid, child: Uuid },
+  #[snafu(display("Child not found from {parent} to {child}"))]
+  ChildNotFound { parent: Uuid, child: Uuid },
+  #[snafu(display("Snapshot not found: {id}"))]
+  SnapshotNotFound { id: usize },
+  #[snafu(display("UUID collision: {uuid} is already used by a node"))]
+  UuidCollision { uuid: Uuid },
+  #[snafu(display("Name {name} already exist for {uuid}"))]
+  NameAlreadyExists { name: String, uuid: Uuid },
+  #[snafu(display("Unsafe character in the name {name:?}{}", uuid.map(|uuid| format!(" of {uuid}")).unwrap_or_default()))]
+  UnsafeName { uuid: Option<Uuid>, name: String },
+  #[snafu(display("Invalid DOT: {message}"))]
+  InvalidDot { message: String },
+  #[snafu(display("Only a DOT digraph can be imported"))]
+  UndirectedDot,
 }
 
-#[derive(Debug, Clone)]
+/// Deserialization checks the graph, see [`AcyclicGraph::from_parts`]
+#[derive(Debug, Clone, serde::Deserialize)]
+#[serde(try_from = "serialize::GraphData")]
 pub struct AcyclicGraph {
   name: String,
-  nodes: HashMap<Uuid, Node>,
+  nodes: NodeMap,
+  history: History,
+  observers: Observers,
+  names: Names,
+  uuids: Uuids,
+  edge_data: HashMap<(Uuid, Uuid), serde_json::Map<String, serde_json::Value>>,
+  order: TopoOrder,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Serialized with its childs sorted, a child listed twice is rejected
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Node {
+  #[serde(default, skip_serializing_if = "serialize::is_none")]
   data: NodeData,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  level: Option<usize>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  timestamp: Option<Timestamp>,
+  #[serde(
+    default,
+    skip_serializing_if = "HashSet::is_empty",
+    serialize_with = "serialize::sorted",
+    deserialize_with = "serialize::unique"
+  )]
   childs: HashSet<Uuid>,
 }
 
@@ -44,35 +205,132 @@ impl Node {
     Self {
       data: data.into(),
       childs: HashSet::new(),
+      level: None,
+      timestamp: None,
     }
   }
 
+  /// Level recorded by the generator, root is 0
+  pub fn level(&self) -> Option<usize> {
+    self.level
+  }
+
+  pub fn set_level(&mut self, level: usize) {
+    self.level = Some(level);
+  }
+
+  /// Creation time of the node, an edge is created with its child so it
+  /// share the timestamp of the child
+  pub fn timestamp(&self) -> Option<Timestamp> {
+    self.timestamp
+  }
+
+  pub fn set_timestamp(&mut self, timestamp: Timestamp) {
+    self.timestamp = Some(timestamp);
+  }
+
   pub fn childs(&self) -> &HashSet<Uuid> {
     &self.childs
   }
+
+  pub fn data(&self) -> &NodeData {
+    &self.data
+  }
+
+  /// The name of the node is its text data or the `name` field of its JSON
+  /// data
+  pub fn name(&self) -> Option<&str> {
+    match &self.data {
+      NodeData::Text(t) => Some(t),
+      NodeData::Json(json) => json.get("name").and_then(|name| name.as_str()),
+      _ => None,
+    }
+  }
+
+  /// Set the `name` field of JSON object data, any other data is replaced by
+  /// the name
+  pub fn set_name(&mut self, name: impl Into<String>) {
+    match &mut self.data {
+      NodeData::Json(serde_json::Value::Object(object)) => {
+        object.insert("name".to_string(), name.into().into());
+      }
+      data => *data = NodeData::Text(name.into()),
+    }
+  }
+
+  /// The attributes of the node are the fields of its JSON object data other
+  /// than `name`, sorted by key
+  pub fn attrs(&self) -> impl Iterator<Item = (&str, &serde_json::Value)> {
+    let object = match &self.data {
+      NodeData::Json(serde_json::Value::Object(object)) => Some(object),
+      _ => None,
+    };
+    object
+      .into_iter()
+      .flatten()
+      .filter(|(key, _)| *key != "name")
+      .map(|(key, value)| (key.as_str(), value))
+  }
+
+  pub fn attr(&self, key: &str) -> Option<&serde_json::Value> {
+    self
+      .attrs()
+      .find(|(other, _)| *other == key)
+      .map(|(_, value)| value)
+  }
+
+  /// Set an attribute, data that isn't a JSON object becomes one with a text
+  /// as its `name` field and any other data as its `data` field
+  pub fn set_attr(&mut self, key: impl Into<String>, value: impl Into<serde_json::Value>) {
+    let mut object = match std::mem::take(&mut self.data) {
+      NodeData::Json(serde_json::Value::Object(object)) => object,
+      NodeData::Text(name) => serde_json::Map::from_iter([("name".to_string(), name.into())]),
+      NodeData::None => serde_json::Map::new(),
+      data => {
+        serde_json::Map::from_iter([("data".to_string(), data.to_json().unwrap_or_default())])
+      }
+    };
+    object.insert(key.into(), value.into());
+    self.data = NodeData::Json(object.into());
+  }
+
+  pub fn remove_attr(&mut self, key: &str) -> Option<serde_json::Value> {
+    match &mut self.data {
+      NodeData::Json(serde_json::Value::Object(object)) if key != "name" => object.remove(key),
+      _ => None,
+    }
+  }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(
+  Debug,
+  Clone,
+  PartialEq,
+  Eq,
+  Default,
+  serde::Serialize,
+  serde::Deserialize
+)]
+#[serde(rename_all = "snake_case")]
 pub enum NodeData {
   Number(u64),
   Text(String),
+  Json(serde_json::Value),
+  #[default]
   None,
 }
 
-impl Default for NodeData {
-  fn default() -> Self {
-    Self::None
+impl Display for NodeData {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      NodeData::Number(n) => write!(f, "{n}"),
+      NodeData::Text(t) => write!(f, "{t}"),
+      NodeData::Json(json) => write!(f, "{json}"),
+      NodeData::None => Ok(()),
+    }
   }
 }
 
-// impl Display for NodeData {
-//     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-//         match  {
-
-//         }
-//     }
-// }
-
 impl From<u64> for NodeData {
   fn from(n: u64) -> Self {
     NodeData::Number(n)
@@ -97,6 +355,12 @@ impl From<&str> for NodeData {
   }
 }
 
+impl From<serde_json::Value> for NodeData {
+  fn from(json: serde_json::Value) -> Self {
+    NodeData::Json(json)
+  }
+}
+
 impl From<()> for NodeData {
   fn from(_: ()) -> Self {
     NodeData::None
@@ -105,62 +369,70 @@ impl From<()> for NodeData {
 
 impl AcyclicGraph {
   pub fn new(name: impl Into<String>) -> Self {
+    let nodes = NodeMap::default();
     Self {
       name: name.into(),
-      nodes: HashMap::new(),
+      order: TopoOrder::new(&nodes, Vec::new()),
+      nodes,
+      history: History::default(),
+      observers: Observers::default(),
+      names: Names::default(),
+      uuids: Uuids::default(),
+      edge_data: HashMap::new(),
     }
   }
 
-  pub fn nodes(&self) -> &HashMap<Uuid, Node> {
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  /// The nodes by UUID order
+  pub fn nodes(&self) -> &NodeMap {
     &self.nodes
   }
 
+  // the UUIDs drawn by the graph don't collide in practice
   fn add_node_uuid(&mut self, uuid: Uuid, data: impl Into<NodeData>) -> (Uuid, &Node) {
-    let node = Node::new(data);
-    match self.nodes.entry(uuid) {
-      std::collections::hash_map::Entry::Vacant(vacant) => (uuid, vacant.insert(node)),
-      std::collections::hash_map::Entry::Occupied(_) => {
-        panic!("UUID collision detected");
-      }
+    match self.add_node_with_uuid(uuid, data) {
+      Ok(added) => added,
+      Err(e) => panic!("{e}"),
     }
   }
 
+  /// Add a node with an UUID given by the caller, like the ID of an imported
+  /// node. Nothing is changed when the UUID is already used or the name is
+  /// already used in unique names mode.
+  pub fn add_node_with_uuid(
+    &mut self, uuid: Uuid, data: impl Into<NodeData>,
+  ) -> Result<(Uuid, &Node), Error> {
+    if self.nodes.contains_key(&uuid) {
+      return Err(Error::UuidCollision { uuid });
+    }
+    let node = Node::new(data);
+    self.names.check(node.name())?;
+    self.names.insert(uuid, node.name());
+    self.history.record(|| Change::AddNode { uuid });
+    self.nodes.insert(uuid, node);
+    self.order.push(&self.nodes, uuid);
+    let node = &self.nodes[&uuid];
+    self.observers.node_added(uuid, node);
+    Ok((uuid, node))
+  }
+
+  /// Panics if the name is already used by another node in unique names
+  /// mode, see [`AcyclicGraph::try_add_node_with_rng`]
   pub fn add_node_with_rng(
     &mut self, data: impl Into<NodeData>, rng: &mut impl rand::RngCore,
   ) -> (Uuid, &Node) {
-    let uuid = uuid::Builder::from_random_bytes(rng.random()).into_uuid();
+    let uuid = self.uuids.next_with_rng(rng);
     self.add_node_uuid(uuid, data)
   }
 
-  #[allow(dead_code)]
+  /// Panics if the name is already used by another node in unique names
+  /// mode, see [`AcyclicGraph::try_add_node`]
   pub fn add_node(&mut self, data: impl Into<NodeData>) -> (Uuid, &Node) {
-    self.add_node_uuid(Uuid::new_v4(), data)
-  }
-
-  fn check_cycle(&self, parent: Uuid, child: Uuid) -> Result<(), Error> {
-    let mut queue = VecDeque::from([child]);
-    let mut queued = HashSet::from([child]);
-
-    while let Some(current) = queue.pop_front() {
-      if current == parent {
-        return Err(Error::Cycle {
-          src: parent,
-          dst: child,
-        });
-      }
-
-      if let Some(node) = self.nodes.get(&current) {
-        for &child in &node.childs {
-          if queued.insert(child) {
-            queue.push_back(child);
-          }
-        }
-      } else {
-        return Err(Error::UuidNotFound { uuid: current });
-      }
-    }
-
-    Ok(())
+    let uuid = self.uuids.next();
+    self.add_node_uuid(uuid, data)
   }
 
   pub fn get_node(&self, uuid: Uuid) -> Result<&Node, Error> {
@@ -171,29 +443,158 @@ impl AcyclicGraph {
     }
   }
 
+  /// While a snapshot exists the node is copied before being lent so the
+  /// change can be undone. In unique names mode the name must be changed with
+  /// [`AcyclicGraph::set_node_name`] to keep the index up to date.
   pub fn get_node_mut(&mut self, uuid: Uuid) -> Result<&mut Node, Error> {
     if let Some(node) = self.nodes.get_mut(&uuid) {
+      self.history.record(|| Change::Node {
+        uuid,
+        node: node.clone(),
+      });
       Ok(node)
     } else {
       Err(Error::UuidNotFound { uuid })
     }
   }
 
+  /// Refused if it closes a cycle, checked against a topological order kept
+  /// by the graph so an edge that goes down it costs nothing
   pub fn add_child(&mut self, parent: Uuid, child: Uuid) -> Result<(), Error> {
-    self.check_cycle(parent, child)?;
-    if self.get_node_mut(parent)?.childs.insert(child) {
+    self.get_node(child)?;
+    if self.get_node(parent)?.childs.contains(&child) {
+      return Err(Error::ChildAlreadyExist { parent, child });
+    }
+    if !self.order.is_valid() {
+      self.rebuild_order();
+    }
+    self.order.add_edge(&self.nodes, parent, child)?;
+    let node = self
+      .nodes
+      .get_mut(&parent)
+      .ok_or(Error::UuidNotFound { uuid: parent })?;
+    if node.childs.insert(child) {
+      self.history.record(|| Change::AddChild { parent, child });
+      self.observers.edge_added(parent, child);
       Ok(())
     } else {
       Err(Error::ChildAlreadyExist { parent, child })
     }
   }
 
-  pub fn dot(&self) -> Dot {
-    Dot { graph: self }
+  /// Remove the edge from `parent` to `child` and its data, both nodes are
+  /// kept
+  pub fn remove_child(&mut self, parent: Uuid, child: Uuid) -> Result<(), Error> {
+    let node = self
+      .nodes
+      .get_mut(&parent)
+      .ok_or(Error::UuidNotFound { uuid: parent })?;
+    if node.childs.remove(&child) {
+      let data = self.edge_data.remove(&(parent, child));
+      self.order.remove_edge(&self.nodes, parent, child);
+      self.history.record(|| Change::RemoveChild {
+        parent,
+        child,
+        data,
+      });
+      self.observers.removed(Removed::Edge { parent, child });
+      Ok(())
+    } else {
+      Err(Error::ChildNotFound { parent, child })
+    }
   }
 
-  pub fn mermaid(&self) -> Mermaid {
-    Mermaid { graph: self }
+  /// Remove a node and every edge to it, its children are kept. The removed
+  /// node is returned with its childs, the edges from it are gone from the
+  /// graph.
+  pub fn remove_node(&mut self, uuid: Uuid) -> Result<Node, Error> {
+    self.get_node(uuid)?;
+    // the parents are linked by the order, rebuilt like by an insertion
+    // rather than searched in every node
+    if !self.order.is_valid() {
+      self.rebuild_order();
+    }
+    let parents: Vec<Uuid> = self.order.parents_of(&self.nodes, uuid).collect();
+    let node = self.nodes.get(&uuid).ok_or(Error::UuidNotFound { uuid })?;
+    for child in &node.childs {
+      self.order.remove_edge(&self.nodes, uuid, *child);
+    }
+    self.order.remove(&self.nodes, uuid);
+    let node = self
+      .nodes
+      .remove(&uuid)
+      .ok_or(Error::UuidNotFound { uuid })?;
+    self.names.remove(node.name());
+    let mut edge_data = Vec::new();
+    for &parent in &parents {
+      if let Some(other) = self.nodes.get_mut(&parent) {
+        other.childs.remove(&uuid);
+      }
+      edge_data.extend(self.edge_data.remove_entry(&(parent, uuid)));
+      self.observers.removed(Removed::Edge {
+        parent,
+        child: uuid,
+      });
+    }
+    for child in &node.childs {
+      edge_data.extend(self.edge_data.remove_entry(&(uuid, *child)));
+    }
+    self.observers.removed(Removed::Node { uuid, node: &node });
+    self.history.record(|| Change::RemoveNode {
+      uuid,
+      node: node.clone(),
+      parents,
+      edge_data,
+    });
+    Ok(node)
+  }
+
+  pub fn dot(&self) -> Dot<'_> {
+    self.dot_with(RenderOptions::default())
+  }
+
+  pub fn dot_with(&self, options: RenderOptions) -> Dot<'_> {
+    Dot {
+      graph: self,
+      options,
+    }
+  }
+
+  /// Write [`AcyclicGraph::dot`] to `w` as it's rendered, the whole output
+  /// is never held in memory
+  pub fn write_dot(&self, w: &mut impl io::Write) -> io::Result<()> {
+    self.dot().write(w)
+  }
+
+  pub fn mermaid(&self) -> Mermaid<'_> {
+    self.mermaid_with(RenderOptions::default())
+  }
+
+  pub fn mermaid_with(&self, options: RenderOptions) -> Mermaid<'_> {
+    Mermaid {
+      graph: self,
+      options,
+    }
+  }
+
+  /// Write [`AcyclicGraph::mermaid`] to `w` as it's rendered, the whole
+  /// output is never held in memory
+  pub fn write_mermaid(&self, w: &mut impl io::Write) -> io::Result<()> {
+    self.mermaid().write(w)
+  }
+
+  /// A tree drawn with box-drawing characters to read a small graph in the
+  /// terminal, a node with several parents is drawn under the first one and
+  /// marked `(*)` under the others
+  pub fn ascii(&self) -> Ascii<'_> {
+    self.ascii_with(RenderOptions::default())
+  }
+
+  pub fn ascii_with(&self, options: RenderOptions) -> Ascii<'_> {
+    Ascii {
+      graph: self,
+      options,
+    }
   }
 
   pub fn parents(&self) -> HashMap<Uuid, HashSet<Uuid>> {
@@ -207,77 +608,814 @@ impl AcyclicGraph {
 
     parents
   }
+
+  /// Yield generations of nodes, each one contains the nodes whose parents
+  /// are all in previous generations, like `networkx.topological_generations`.
+  /// Nodes of a generation are sorted by UUID.
+  pub fn topological_generations(&self) -> TopologicalGenerations<'_> {
+    TopologicalGenerations::new(self)
+  }
+
+  /// Nodes reachable from `root` grouped by level, the level of a node is the
+  /// length of the longest path from `root` so a node with several parents is
+  /// always below all of them. Levels are sorted by UUID.
+  pub fn levels(&self, root: Uuid) -> Result<Vec<Vec<Uuid>>, Error> {
+    let depths: Vec<(Uuid, usize)> = match self.order.is_valid() {
+      true => {
+        self.get_node(root)?;
+        self.order.longest_paths(&self.nodes, root)
+      }
+      false => self.longest_paths([root])?.into_iter().collect(),
+    };
+    let mut levels: Vec<Vec<Uuid>> = Vec::new();
+    for (uuid, depth) in depths {
+      if levels.len() <= depth {
+        levels.resize_with(depth + 1, Vec::new);
+      }
+      levels[depth].push(uuid);
+    }
+    for level in &mut levels {
+      level.sort();
+    }
+
+    Ok(levels)
+  }
+
+  /// Check that every child exists and that the graph has no cycle. The
+  /// graph maintains this itself, it's for graphs built from untrusted input.
+  pub fn check_invariants(&self) -> Result<(), Error> {
+    for node in self.nodes.values() {
+      for &child in &node.childs {
+        self.get_node(child)?;
+      }
+    }
+
+    let mut in_degrees = self.in_degrees();
+    let mut queue: VecDeque<Uuid> = in_degrees
+      .iter()
+      .filter(|(_, in_degree)| **in_degree == 0)
+      .map(|(&uuid, _)| uuid)
+      .collect();
+    while let Some(uuid) = queue.pop_front() {
+      in_degrees.remove(&uuid);
+      for child in &self.nodes[&uuid].childs {
+        let in_degree = in_degrees
+          .get_mut(child)
+          .expect("child can't be visited before its parents");
+        *in_degree -= 1;
+        if *in_degree == 0 {
+          queue.push_back(*child);
+        }
+      }
+    }
+
+    // every node left is in a cycle or below one, report the smallest edge
+    // between two of them
+    match in_degrees
+      .keys()
+      .flat_map(|&src| {
+        self.nodes[&src]
+          .childs
+          .iter()
+          .filter(|dst| in_degrees.contains_key(dst))
+          .map(move |&dst| (src, dst))
+      })
+      .min()
+    {
+      Some((src, dst)) => Err(Error::Cycle { src, dst }),
+      None => Ok(()),
+    }
+  }
+
+  // children of the roots sorted by UUID and the index of the subtree of each
+  // node below them, a node reachable from several subtrees belong to the
+  // first one
+  fn clusters(&self) -> (Vec<Uuid>, HashMap<Uuid, usize>) {
+    let parents = self.parents();
+    let heads: Vec<Uuid> = self
+      .nodes
+      .iter()
+      .filter(|(uuid, _)| !parents.contains_key(uuid))
+      .flat_map(|(_, root)| root.childs.iter().copied())
+      .sorted()
+      .dedup()
+      .collect();
+
+    let mut clusters = HashMap::new();
+    for (i, &head) in heads.iter().enumerate() {
+      let mut queue = VecDeque::from([head]);
+      while let Some(uuid) = queue.pop_front() {
+        if clusters.contains_key(&uuid) {
+          continue;
+        }
+        clusters.insert(uuid, i);
+        queue.extend(self.nodes[&uuid].childs.iter().sorted());
+      }
+    }
+
+    (heads, clusters)
+  }
+
+  fn in_degrees(&self) -> HashMap<Uuid, usize> {
+    let mut in_degrees: HashMap<Uuid, usize> = self.nodes.keys().map(|&uuid| (uuid, 0)).collect();
+    for node in self.nodes.values() {
+      for child in &node.childs {
+        *in_degrees.entry(*child).or_default() += 1;
+      }
+    }
+    in_degrees
+  }
+
+  /// Depth of each node, the level recorded by the generator if every node
+  /// have one or the longest path from a root
+  pub fn depths(&self) -> HashMap<Uuid, usize> {
+    let levels: Option<HashMap<Uuid, usize>> = self
+      .nodes
+      .iter()
+      .map(|(&uuid, node)| node.level.map(|level| (uuid, level)))
+      .collect();
+    if let Some(levels) = levels {
+      return levels;
+    }
+
+    let parents = self.parents();
+    let roots = self.nodes.keys().filter(|uuid| !parents.contains_key(uuid));
+    // roots come from the graph so they exist
+    self.longest_paths(roots.copied()).unwrap_or_default()
+  }
+
+  // Kahn's algorithm on the nodes reachable from sources, no recursion to
+  // support deep graphs
+  fn longest_paths(
+    &self, sources: impl IntoIterator<Item = Uuid>,
+  ) -> Result<HashMap<Uuid, usize>, Error> {
+    let sources: Vec<Uuid> = sources.into_iter().collect();
+
+    let mut in_degrees: HashMap<Uuid, usize> = sources.iter().map(|&uuid| (uuid, 0)).collect();
+    let mut queue = VecDeque::from(sources.clone());
+    while let Some(current) = queue.pop_front() {
+      for &child in &self.get_node(current)?.childs {
+        let degree = in_degrees.entry(child).or_default();
+        if *degree == 0 {
+          queue.push_back(child);
+        }
+        *degree += 1;
+      }
+    }
+
+    let mut depths: HashMap<Uuid, usize> = sources.iter().map(|&uuid| (uuid, 0)).collect();
+    let mut queue = VecDeque::from(sources);
+    while let Some(current) = queue.pop_front() {
+      let depth = depths[&current];
+      for &child in &self.get_node(current)?.childs {
+        let child_depth = depths.entry(child).or_default();
+        *child_depth = (*child_depth).max(depth + 1);
+
+        let degree = in_degrees.entry(child).or_default();
+        *degree -= 1;
+        if *degree == 0 {
+          queue.push_back(child);
+        }
+      }
+    }
+
+    Ok(depths)
+  }
+}
+
+/// Options shared by the renderers
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+  /// Replace the default label, the node data, by a template
+  pub label: Option<Template>,
+  /// A score per node, like [`AcyclicGraph::pagerank`], exported in DOT as a
+  /// `score` attribute with a font size growing with the score
+  pub scores: Option<HashMap<Uuid, f64>>,
+  /// Wrap each subtree of a child of a root in a DOT cluster labeled by the
+  /// child
+  pub clusters: bool,
+  /// Draw each DOT node as an HTML-like table with its label, short UUID and
+  /// data fields
+  pub tables: bool,
+  /// URL of each node, the `url` field of JSON data is used without template.
+  /// Exported as a Mermaid `click` and a DOT `URL` attribute.
+  pub link: Option<Template>,
+  /// Nodes drawn dashed in DOT and Mermaid, the references of a [`Part`]
+  pub references: HashSet<Uuid>,
+  /// Order of the nodes and childs in DOT and Mermaid
+  pub sort_by: SortBy,
+  /// Color or width of the DOT and Mermaid edges
+  pub edge_style: EdgeStyle,
+  /// Handling of unsafe characters in the graph name and the labels
+  pub sanitize: Sanitize,
+  /// Sequential IDs replacing the short UUIDs of the nodes, from
+  /// [`AcyclicGraph::sequential_ids`]
+  pub ids: Option<HashMap<Uuid, usize>>,
+  /// Order the DOT nodes by [`AcyclicGraph::rank_order`] and chain each rank
+  /// with invisible edges so Graphviz keeps that order, ignored with clusters
+  pub rank_hints: bool,
+  /// Leave the node data out of the JGF metadata, the DOT tables and the XLSX
+  /// sheet, see [`AcyclicGraph::data_file`]
+  pub omit_data: bool,
+  /// Direction of the DOT and Mermaid levels
+  pub rankdir: RankDir,
+  /// Shape of the DOT and Mermaid nodes by variant of their data
+  pub shapes: NodeShapes,
+  /// Fill the DOT and Mermaid nodes with a color per depth, the palette
+  /// cycles after 9 levels
+  pub color_by_depth: bool,
+  /// Add the short UUID of the node under its DOT and Mermaid label
+  pub show_uuid: bool,
+}
+
+/// Order of the exported nodes, by name or topological order give readable
+/// diffs between close graphs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum SortBy {
+  #[default]
+  Uuid,
+  /// By label, nodes without one first, then by UUID
+  Name,
+  /// By topological generation, then by UUID
+  Topological,
+}
+
+impl RenderOptions {
+  fn depths(&self, graph: &AcyclicGraph) -> Option<HashMap<Uuid, usize>> {
+    let templates = [&self.label, &self.link]
+      .into_iter()
+      .flatten()
+      .any(|template| template.uses(Placeholder::Depth));
+    (templates || self.color_by_depth).then(|| graph.depths())
+  }
+
+  fn link(&self, uuid: Uuid, node: &Node, depths: Option<&HashMap<Uuid, usize>>) -> Option<String> {
+    match (&self.link, &node.data) {
+      (Some(template), _) => Some(template.render(
+        uuid,
+        node,
+        depths.and_then(|depths| depths.get(&uuid).copied()),
+      )),
+      (None, NodeData::Json(json)) => json.get("url")?.as_str().map(str::to_string),
+      (None, _) => None,
+    }
+  }
+
+  fn label(
+    &self, uuid: Uuid, node: &Node, depths: Option<&HashMap<Uuid, usize>>,
+  ) -> Option<String> {
+    let label = match &self.label {
+      Some(template) => Some(template.render(
+        uuid,
+        node,
+        depths.and_then(|depths| depths.get(&uuid).copied()),
+      )),
+      None if node.data == NodeData::None => None,
+      None => Some(
+        node
+          .name()
+          .map_or_else(|| node.data.to_string(), str::to_string),
+      ),
+    };
+    label.map(|label| self.sanitize.apply(&label).into_owned())
+  }
+
+  // the label of a DOT or Mermaid node, with its short UUID on a second
+  // line when asked
+  fn shown_label(
+    &self, uuid: Uuid, node: &Node, depths: Option<&HashMap<Uuid, usize>>,
+  ) -> Option<String> {
+    let label = self.label(uuid, node, depths);
+    if !self.show_uuid {
+      return label;
+    }
+    let short = ShortUuid::from_uuid(&uuid);
+    Some(match label {
+      Some(label) => format!("{label}\n{short}"),
+      None => short.to_string(),
+    })
+  }
+
+  // position of each node in the output
+  fn ranks(&self, graph: &AcyclicGraph, depths: Option<&HashMap<Uuid, usize>>) -> Ranks {
+    let uuids: Vec<Uuid> = match self.sort_by {
+      SortBy::Uuid => return Ranks::Uuid,
+      SortBy::Name => graph
+        .nodes
+        .iter()
+        .map(|(&uuid, node)| (self.label(uuid, node, depths), uuid))
+        .sorted()
+        .map(|(_, uuid)| uuid)
+        .collect(),
+      SortBy::Topological => graph.topological_generations().flatten().collect(),
+    };
+    Ranks::positions(uuids)
+  }
+
+  // font size from 10 for the lowest score to 30 for the highest
+  fn score(&self, uuid: Uuid) -> Option<(f64, f64)> {
+    let scores = self.scores.as_ref()?;
+    let score = *scores.get(&uuid)?;
+    let (min, max) = scores
+      .values()
+      .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &score| {
+        (min.min(score), max.max(score))
+      });
+    let ratio = if max > min {
+      (score - min) / (max - min)
+    } else {
+      0.0
+    };
+    Some((score, 10.0 + 20.0 * ratio))
+  }
+}
+
+// the order of the nodes in the output, the nodes are kept by UUID order so
+// that one needs no index and they are written as they are read
+enum Ranks {
+  Uuid,
+  Positions(HashMap<Uuid, usize>),
+}
+
+impl Ranks {
+  fn positions(uuids: impl IntoIterator<Item = Uuid>) -> Self {
+    Ranks::Positions(
+      uuids
+        .into_iter()
+        .enumerate()
+        .map(|(i, uuid)| (uuid, i))
+        .collect(),
+    )
+  }
+
+  // sort key of a node
+  fn key(&self, uuid: &Uuid) -> (usize, Uuid) {
+    match self {
+      Ranks::Uuid => (0, *uuid),
+      Ranks::Positions(positions) => (positions[uuid], *uuid),
+    }
+  }
+
+  // the nodes of the graph in order, only sorted out of the UUID order
+  fn nodes<'a>(
+    &self, graph: &'a AcyclicGraph,
+  ) -> impl Iterator<Item = (&'a Uuid, &'a Node)> + Clone + use<'a> {
+    match self {
+      Ranks::Uuid => Either::Left(graph.nodes.iter()),
+      Ranks::Positions(positions) => Either::Right(
+        graph
+          .nodes
+          .iter()
+          .sorted_by_key(|(uuid, _)| positions[*uuid]),
+      ),
+    }
+  }
 }
 
 pub struct Dot<'a> {
   graph: &'a AcyclicGraph,
+  options: RenderOptions,
+}
+
+impl Dot<'_> {
+  /// Write the output to `w` as it's rendered, like
+  /// [`AcyclicGraph::write_dot`] with options
+  pub fn write(&self, w: &mut impl io::Write) -> io::Result<()> {
+    // each piece goes to `w` as soon as it's formatted, nothing is collected
+    write!(w, "{self}")
+  }
+
+  fn write_node(
+    &self, f: &mut Formatter<'_>, indent: &str, uuid: Uuid, node: &Node,
+    depths: Option<&HashMap<Uuid, usize>>,
+  ) -> fmt::Result {
+    write!(f, "{indent}\"{}\"", self.options.id(&uuid))?;
+    let mut attributes = Vec::new();
+    let label = match self.options.tables {
+      true => self.options.label(uuid, node, depths),
+      false => self.options.shown_label(uuid, node, depths),
+    };
+    let shape = self.options.shapes.get(&node.data);
+    if !self.options.tables && shape != NodeShape::Box {
+      attributes.push(format!("shape = {}", shape.dot()));
+    }
+    if self.options.tables {
+      attributes.push("shape = plain".to_string());
+      let data = match self.options.omit_data {
+        true => &NodeData::None,
+        false => &node.data,
+      };
+      attributes.push(format!("label = <{}>", table(uuid, data, label.as_deref())));
+    } else if let Some(label) = label {
+      attributes.push(format!("label = \"{}\"", DotEscape(&label)));
+    }
+    if let Some(link) = self.options.link(uuid, node, depths) {
+      attributes.push(format!("URL = \"{}\"", DotEscape(&link)));
+    }
+    // the tables already show them
+    if !self.options.tables && !self.options.omit_data && node.attrs().next().is_some() {
+      let tooltip = node
+        .attrs()
+        .map(|(key, value)| format!("{key}: {}", edge_text(value)))
+        .join("\n");
+      attributes.push(format!("tooltip = \"{}\"", DotEscape(&tooltip)));
+    }
+    let fill = self
+      .options
+      .fill(depths.and_then(|depths| depths.get(&uuid).copied()));
+    let styles: Vec<&str> = [
+      self.options.references.contains(&uuid).then_some("dashed"),
+      fill.map(|_| "filled"),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    match styles.as_slice() {
+      [] => {}
+      [style] => attributes.push(format!("style = {style}")),
+      styles => attributes.push(format!("style = \"{}\"", styles.join(","))),
+    }
+    if let Some(fill) = fill {
+      attributes.push(format!("fillcolor = \"{fill}\""));
+    }
+    if let Some(timestamp) = node.timestamp {
+      attributes.push(format!("timestamp = \"{timestamp}\""));
+    }
+    if let Some((score, font_size)) = self.options.score(uuid) {
+      attributes.push(format!("score = \"{score}\""));
+      attributes.push(format!("fontsize = {font_size:.1}"));
+    }
+    if !attributes.is_empty() {
+      write!(f, " [{}]", attributes.join(", "))?;
+    }
+    writeln!(f, ";")
+  }
+
+  fn write_childs(
+    &self, f: &mut Formatter<'_>, uuid: Uuid, node: &Node, ranks: &Ranks, strokes: Option<&Strokes>,
+  ) -> fmt::Result {
+    let mut childrens = node.childs.iter().sorted_by_key(|child| ranks.key(child));
+    // styled edges and edges with data can't be grouped
+    let data = |child: &Uuid| self.graph.edge_data(uuid, *child);
+    if strokes.is_some() || node.childs.iter().any(|child| data(child).is_some()) {
+      for child in childrens {
+        let mut attributes = Vec::new();
+        if let Some(strokes) = strokes {
+          attributes.push(strokes.edges[&(uuid, *child)].dot().to_string());
+        }
+        for (key, value) in data(child).into_iter().flatten() {
+          let value = match value {
+            serde_json::Value::String(value) => value.clone(),
+            value => value.to_string(),
+          };
+          attributes.push(format!(
+            "\"{}\" = \"{}\"",
+            DotEscape(key),
+            DotEscape(&value)
+          ));
+        }
+        write!(
+          f,
+          "  \"{}\" -> \"{}\"",
+          self.options.id(&uuid),
+          self.options.id(child)
+        )?;
+        if !attributes.is_empty() {
+          write!(f, " [{}]", attributes.join(", "))?;
+        }
+        writeln!(f, ";")?;
+      }
+      return Ok(());
+    }
+    if let Some(first) = childrens.next() {
+      write!(
+        f,
+        "  \"{}\" -> {{\"{}\"",
+        self.options.id(&uuid),
+        self.options.id(first)
+      )?;
+      for child in childrens {
+        write!(f, " \"{}\"", self.options.id(child))?;
+      }
+      writeln!(f, "}};")?;
+    }
+    Ok(())
+  }
 }
 
 impl Display for Dot<'_> {
   fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-    writeln!(f, "digraph \"{}\" {{", self.graph.name)?;
+    writeln!(
+      f,
+      "digraph \"{}\" {{",
+      DotEscape(&self.options.name(self.graph))
+    )?;
     writeln!(f, "  node [shape = box]")?;
-    writeln!(f, "  graph [rankdir = TB]")?;
+    writeln!(f, "  graph [rankdir = {}]", self.options.rankdir.code())?;
     writeln!(f)?;
-    for parent in self.graph.nodes.iter().sorted_by_key(|node| node.0) {
-      // Node
-      write!(f, "  \"{}\"", ShortUuid::from_uuid(parent.0))?;
-      match &parent.1.data {
-        NodeData::Number(n) => write!(f, " [label = \"{n}\"]")?,
-        NodeData::Text(t) => write!(f, " [label = \"{t}\"]")?,
-        NodeData::None => {}
+    let depths = self.options.depths(self.graph);
+    let rank_order =
+      (self.options.rank_hints && !self.options.clusters).then(|| self.graph.rank_order());
+    let ranks = match &rank_order {
+      Some(rank_order) => Ranks::positions(rank_order.iter().flatten().copied()),
+      None => self.options.ranks(self.graph, depths.as_ref()),
+    };
+    let strokes = self.options.strokes(self.graph);
+    let nodes = ranks.nodes(self.graph);
+
+    if !self.options.clusters {
+      for (&uuid, node) in nodes {
+        self.write_node(f, "  ", uuid, node, depths.as_ref())?;
+        self.write_childs(f, uuid, node, &ranks, strokes.as_ref())?;
+      }
+      for rank in rank_order.iter().flatten().filter(|rank| rank.len() > 1) {
+        let chain = rank
+          .iter()
+          .map(|uuid| format!("\"{}\"", self.options.id(uuid)))
+          .join(" -> ");
+        writeln!(f, "  {{rank = same; {chain} [style = invis]}}")?;
+      }
+      if let Some(strokes) = &strokes {
+        strokes.write_dot_legend(f)?;
       }
-      writeln!(f, ";")?;
+      return writeln!(f, "}}");
+    }
 
-      // Childs
-      let mut childrens = parent.1.childs.iter().sorted();
-      if let Some(first) = childrens.next() {
-        write!(
-          f,
-          "  \"{}\" -> {{\"{}\"",
-          ShortUuid::from_uuid(parent.0),
-          ShortUuid::from_uuid(first)
-        )?;
-        for child in childrens {
-          write!(f, " \"{}\"", ShortUuid::from_uuid(child))?;
+    let (heads, clusters) = self.graph.clusters();
+    for (&uuid, node) in nodes.clone() {
+      if !clusters.contains_key(&uuid) {
+        self.write_node(f, "  ", uuid, node, depths.as_ref())?;
+      }
+    }
+    for (i, head) in heads.iter().enumerate() {
+      writeln!(f, "  subgraph \"cluster_{i}\" {{")?;
+      let label = self
+        .options
+        .label(*head, &self.graph.nodes[head], depths.as_ref())
+        .unwrap_or_else(|| self.options.id(head).to_string());
+      writeln!(f, "    label = \"{}\"", DotEscape(&label))?;
+      for (&uuid, node) in nodes.clone() {
+        if clusters.get(&uuid) == Some(&i) {
+          self.write_node(f, "    ", uuid, node, depths.as_ref())?;
         }
-        writeln!(f, "}};")?;
       }
+      writeln!(f, "  }}")?;
+    }
+    for (&uuid, node) in nodes {
+      self.write_childs(f, uuid, node, &ranks, strokes.as_ref())?;
+    }
+    if let Some(strokes) = &strokes {
+      strokes.write_dot_legend(f)?;
     }
 
     writeln!(f, "}}")
   }
 }
 
+// HTML-like label, one row per field of JSON object data, every cell is
+// escaped so the data can't close the table
+fn table(uuid: Uuid, data: &NodeData, label: Option<&str>) -> String {
+  let mut rows = Vec::new();
+  if let Some(label) = label {
+    rows.push(format!(
+      "<tr><td colspan=\"2\"><b>{}</b></td></tr>",
+      Escape(label)
+    ));
+  }
+  rows.push(format!(
+    "<tr><td colspan=\"2\">{}</td></tr>",
+    ShortUuid::from_uuid(&uuid)
+  ));
+  let fields: Vec<(&str, String)> = match data {
+    NodeData::Json(serde_json::Value::Object(object)) => object
+      .iter()
+      .map(|(key, value)| {
+        let value = match value {
+          serde_json::Value::String(value) => value.clone(),
+          value => value.to_string(),
+        };
+        (key.as_str(), value)
+      })
+      .collect(),
+    NodeData::None => Vec::new(),
+    data => vec![("data", data.to_string())],
+  };
+  for (key, value) in fields {
+    rows.push(format!(
+      "<tr><td align=\"left\">{}</td><td align=\"left\">{}</td></tr>",
+      Escape(key),
+      Escape(&value)
+    ));
+  }
+  format!(
+    "<table border=\"0\" cellborder=\"1\" cellspacing=\"0\">{}</table>",
+    rows.concat()
+  )
+}
+
 pub struct Mermaid<'a> {
   graph: &'a AcyclicGraph,
+  options: RenderOptions,
+}
+
+impl Mermaid<'_> {
+  /// Write the output to `w` as it's rendered, like
+  /// [`AcyclicGraph::write_mermaid`] with options
+  pub fn write(&self, w: &mut impl io::Write) -> io::Result<()> {
+    write!(w, "{self}")
+  }
 }
 
 impl Display for Mermaid<'_> {
   fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
     writeln!(f, "---")?;
-    writeln!(f, "title: {}", self.graph.name)?;
+    // a YAML string, JSON escaping is valid YAML
+    let name = self.options.name(self.graph);
+    if name.contains(is_unsafe) || name.contains(':') {
+      writeln!(f, "title: {}", serde_json::Value::from(name.as_ref()))?;
+    } else {
+      writeln!(f, "title: {name}")?;
+    }
 
     writeln!(f, "---")?;
-    writeln!(f, "flowchart TB")?;
-    for parent in self.graph.nodes.iter().sorted_by_key(|node| node.0) {
+    writeln!(f, "flowchart {}", self.options.rankdir.code())?;
+    let depths = self.options.depths(self.graph);
+    let ranks = self.options.ranks(self.graph, depths.as_ref());
+    let nodes = ranks.nodes(self.graph);
+    let strokes = self.options.strokes(self.graph);
+    // links of each stroke by their index in the output, in order of first use
+    let mut styles: Vec<(Stroke, Vec<usize>)> = Vec::new();
+    let mut links = 0;
+    for parent in nodes.clone() {
       // Node
-      write!(f, "  {}", ShortUuid::from_uuid(parent.0))?;
-      match &parent.1.data {
-        NodeData::Number(n) => write!(f, "[{n}]")?,
-        NodeData::Text(t) => write!(f, "[{t}]")?,
-        NodeData::None => {}
+      write!(f, "  {}", self.options.id(parent.0))?;
+      let shape = self.options.shapes.get(&parent.1.data);
+      let (open, close) = shape.mermaid();
+      match self
+        .options
+        .shown_label(*parent.0, parent.1, depths.as_ref())
+      {
+        Some(label) => write!(f, "{open}{}{close}", MermaidText(&label))?,
+        // the ID is the text of a node without label
+        None if shape != NodeShape::Box => write!(f, "{open}{}{close}", self.options.id(parent.0))?,
+        None => {}
       }
 
       // Childrens
-      let mut childrens = parent.1.childs.iter().sorted();
-      if let Some(child) = childrens.next() {
-        write!(f, " --> {}", ShortUuid::from_uuid(child))?;
+      let childrens: Vec<&Uuid> = parent
+        .1
+        .childs
+        .iter()
+        .sorted_by_key(|child| ranks.key(child))
+        .collect();
+      // the label and weight of an edge are written on its link, such links
+      // can't be grouped
+      let text = |child: &Uuid| {
+        let data = self.graph.edge_data(*parent.0, *child)?;
+        let text = ["label", "weight"]
+          .into_iter()
+          .filter_map(|key| data.get(key))
+          .map(edge_text)
+          .join(" ");
+        (!text.is_empty()).then_some(text)
+      };
+      if childrens.iter().any(|child| text(child).is_some()) {
+        for (i, child) in childrens.iter().enumerate() {
+          if i > 0 {
+            write!(f, "\n  {}", self.options.id(parent.0))?;
+          }
+          match text(child) {
+            Some(text) => write!(f, " -->|{}| {}", MermaidText(&text), self.options.id(child))?,
+            None => write!(f, " --> {}", self.options.id(child))?,
+          }
+        }
+      } else if let Some((child, others)) = childrens.split_first() {
+        write!(f, " --> {}", self.options.id(child))?;
+        for child in others {
+          write!(f, " & {}", self.options.id(child))?;
+        }
+      }
+      writeln!(f)?;
+      if let Some(strokes) = &strokes {
         for child in childrens {
-          write!(f, " & {}", ShortUuid::from_uuid(child))?;
+          let stroke = strokes.edges[&(*parent.0, *child)];
+          match styles.iter_mut().find(|(other, _)| *other == stroke) {
+            Some((_, indexes)) => indexes.push(links),
+            None => styles.push((stroke, vec![links])),
+          }
+          links += 1;
+        }
+      }
+    }
+    for (stroke, indexes) in styles {
+      writeln!(
+        f,
+        "  linkStyle {} {}",
+        indexes.iter().join(","),
+        stroke.mermaid()
+      )?;
+    }
+    if let Some(strokes) = &strokes {
+      strokes.write_mermaid_legend(f)?;
+    }
+    if !self.options.references.is_empty() {
+      writeln!(f, "  classDef reference stroke-dasharray: 5 5")?;
+      writeln!(
+        f,
+        "  class {} reference",
+        self
+          .options
+          .references
+          .iter()
+          .sorted_by_key(|uuid| ranks.key(uuid))
+          .map(|uuid| self.options.id(uuid))
+          .join(",")
+      )?;
+    }
+    // a class per fill color, with its nodes in order
+    let mut fills: Vec<(&str, Vec<&Uuid>)> = Vec::new();
+    for (uuid, _) in nodes.clone() {
+      let depth = depths.as_ref().and_then(|depths| depths.get(uuid).copied());
+      if let Some(fill) = self.options.fill(depth) {
+        match fills.iter_mut().find(|(other, _)| *other == fill) {
+          Some((_, uuids)) => uuids.push(uuid),
+          None => fills.push((fill, vec![uuid])),
         }
       }
+    }
+    for (i, (fill, uuids)) in fills.iter().enumerate() {
+      writeln!(f, "  classDef fill{i} fill:{fill}")?;
+      writeln!(
+        f,
+        "  class {} fill{i}",
+        uuids.iter().map(|uuid| self.options.id(uuid)).join(",")
+      )?;
+    }
+
+    for (uuid, node) in nodes {
+      if let Some(link) = self.options.link(*uuid, node, depths.as_ref()) {
+        writeln!(
+          f,
+          "  click {} href \"{}\"",
+          self.options.id(uuid),
+          DotEscape(&link)
+        )?;
+      }
+    }
+    Ok(())
+  }
+}
+
+pub struct Ascii<'a> {
+  graph: &'a AcyclicGraph,
+  options: RenderOptions,
+}
+
+impl Display for Ascii<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let depths = self.options.depths(self.graph);
+    let ranks = self.options.ranks(self.graph, depths.as_ref());
+    let in_degrees = self.graph.in_degrees();
+    // the nodes to draw with the prefix of their line and whether they are
+    // the last child, none for a root, popped in order
+    let mut stack: Vec<(Uuid, String, Option<bool>)> = in_degrees
+      .iter()
+      .filter(|(_, degree)| **degree == 0)
+      .map(|(uuid, _)| (*uuid, String::new(), None))
+      .sorted_by_key(|(uuid, ..)| Reverse(ranks.key(uuid)))
+      .collect();
+    let mut drawn = HashSet::new();
+    while let Some((uuid, prefix, last)) = stack.pop() {
+      let node = &self.graph.nodes[&uuid];
+      let branch = match last {
+        None => "",
+        Some(false) => "├── ",
+        Some(true) => "└── ",
+      };
+      write!(f, "{prefix}{branch}")?;
+      match self.options.label(uuid, node, depths.as_ref()) {
+        Some(label) if self.options.show_uuid => {
+          write!(f, "{} {}", LineEscape(&label), self.options.id(&uuid))?
+        }
+        Some(label) => write!(f, "{}", LineEscape(&label))?,
+        None => write!(f, "{}", self.options.id(&uuid))?,
+      }
+      if !drawn.insert(uuid) {
+        writeln!(f, " (*)")?;
+        continue;
+      }
       writeln!(f)?;
+      let prefix = match last {
+        None => prefix,
+        Some(false) => format!("{prefix}│   "),
+        Some(true) => format!("{prefix}    "),
+      };
+      let childs = node.childs.iter().sorted_by_key(|child| ranks.key(child));
+      let count = childs.len();
+      for (i, child) in childs.enumerate().rev() {
+        stack.push((*child, prefix.clone(), Some(i + 1 == count)));
+      }
     }
     Ok(())
   }
@@ -299,12 +1437,26 @@ mod tests {
     let node = node.clone();
     assert_eq!(node.data, "Node".into());
     match &node.data {
-      NodeData::Text(s) => assert_eq!(s, "This is some data"),
+      NodeData::Text(s) => assert_eq!(s, "Node"),
       _ => panic!("Expected NodeData::Text"),
     }
     assert_eq!(graph.get_node(uuid), Ok(&node));
   }
 
+  #[test]
+  fn test_add_node_with_uuid() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let uuid = Uuid::from_u128(1);
+    let (added, _) = graph.add_node_with_uuid(uuid, "Node").unwrap();
+    assert_eq!(added, uuid);
+    assert_eq!(
+      graph.add_node_with_uuid(uuid, "Other"),
+      Err(Error::UuidCollision { uuid })
+    );
+    assert_eq!(graph.nodes()[&uuid].data, "Node".into());
+    assert_eq!(graph.nodes().len(), 1);
+  }
+
   #[test]
   fn test_add_child() {
     let mut graph = AcyclicGraph::new("Test Graph");
@@ -344,6 +1496,73 @@ mod tests {
     assert!(matches!(result, Err(Error::UuidNotFound { .. })));
   }
 
+  #[test]
+  fn test_remove() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (a, _) = graph.add_node("a");
+    let (b, _) = graph.add_node("b");
+    let (c, _) = graph.add_node("c");
+    graph.add_child(a, b).unwrap();
+    graph.add_child(a, c).unwrap();
+    graph.add_child(b, c).unwrap();
+
+    assert_eq!(graph.remove_child(a, c), Ok(()));
+    assert_eq!(
+      graph.remove_child(a, c),
+      Err(Error::ChildNotFound {
+        parent: a,
+        child: c
+      })
+    );
+
+    let removed = graph.remove_node(b).unwrap();
+    assert_eq!(removed.name(), Some("b"));
+    assert!(removed.childs().contains(&c));
+    assert!(graph.get_node(a).unwrap().childs().is_empty());
+    assert!(graph.get_node(c).is_ok());
+    assert_eq!(graph.check_invariants(), Ok(()));
+    assert!(!graph.parents().contains_key(&c));
+    assert_eq!(
+      graph.remove_node(b).unwrap_err(),
+      Error::UuidNotFound { uuid: b }
+    );
+
+    // the parents are found once the order is rebuilt after an undo
+    let (d, _) = graph.add_node("d");
+    graph.add_child(a, d).unwrap();
+    graph.add_child(c, d).unwrap();
+    let snapshot = graph.snapshot();
+    graph.remove_child(c, d).unwrap();
+    graph.restore(snapshot).unwrap();
+    graph.remove_node(d).unwrap();
+    assert!(graph.get_node(a).unwrap().childs().is_empty());
+    assert!(graph.get_node(c).unwrap().childs().is_empty());
+    assert_eq!(graph.check_invariants(), Ok(()));
+  }
+
+  #[test]
+  fn test_check_invariants() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (a, _) = graph.add_node("a");
+    let (b, _) = graph.add_node("b");
+    let (c, _) = graph.add_node("c");
+    graph.add_child(a, b).unwrap();
+    graph.add_child(b, c).unwrap();
+    assert_eq!(graph.check_invariants(), Ok(()));
+
+    // bypass add_child like an importer could
+    let mut missing = graph.clone();
+    let fake_uuid = Uuid::new_v4();
+    missing.get_node_mut(c).unwrap().childs.insert(fake_uuid);
+    assert_eq!(
+      missing.check_invariants(),
+      Err(Error::UuidNotFound { uuid: fake_uuid })
+    );
+
+    graph.get_node_mut(c).unwrap().childs.insert(a);
+    assert!(matches!(graph.check_invariants(), Err(Error::Cycle { .. })));
+  }
+
   #[test]
   fn test_dot_format() {
     let mut rng = StdRng::seed_from_u64(42);
@@ -366,6 +1585,182 @@ mod tests {
     pretty_assertions::assert_eq!(dot_output, expected_output);
   }
 
+  #[test]
+  fn test_dot_clusters() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node_with_rng("Root", &mut rng);
+    let (a, _) = graph.add_node_with_rng("A", &mut rng);
+    let (b, _) = graph.add_node_with_rng("B", &mut rng);
+    let (c, _) = graph.add_node_with_rng("C", &mut rng);
+    graph.add_child(root, a).unwrap();
+    graph.add_child(root, b).unwrap();
+    graph.add_child(a, c).unwrap();
+    let options = RenderOptions {
+      clusters: true,
+      ..Default::default()
+    };
+    let dot_output = format!("{}", graph.dot_with(options));
+    dot_parser::ast::Graph::try_from(dot_output.as_str()).expect("DOT format is invalid");
+
+    let expected_output = r###"digraph "Test Graph" {
+  node [shape = box]
+  graph [rankdir = TB]
+
+  "m43pF1xXxnZvhCY1VeAnMV" [label = "Root"];
+  subgraph "cluster_0" {
+    label = "A"
+    "aVKfr7R7H4Zws6XWktcAsd" [label = "C"];
+    "cDe6M3HmMtiJnhL4ihtnyx" [label = "A"];
+  }
+  subgraph "cluster_1" {
+    label = "B"
+    "r1eLoQWPBenPRfbYKAqer2" [label = "B"];
+  }
+  "cDe6M3HmMtiJnhL4ihtnyx" -> {"aVKfr7R7H4Zws6XWktcAsd"};
+  "m43pF1xXxnZvhCY1VeAnMV" -> {"cDe6M3HmMtiJnhL4ihtnyx" "r1eLoQWPBenPRfbYKAqer2"};
+}
+"###;
+    pretty_assertions::assert_eq!(dot_output, expected_output);
+  }
+
+  #[test]
+  fn test_dot_tables() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let data = serde_json::json!({ "name": "Parent", "size": 3, "note": "a < b" });
+    let (parent, _) = graph.add_node_with_rng(data, &mut rng);
+    let (child, _) = graph.add_node_with_rng(42, &mut rng);
+    graph.add_child(parent, child).unwrap();
+    let options = RenderOptions {
+      tables: true,
+      ..Default::default()
+    };
+    let dot_output = format!("{}", graph.dot_with(options));
+
+    let table = "<table border=\"0\" cellborder=\"1\" cellspacing=\"0\">";
+    let row =
+      |key, value| format!("<tr><td align=\"left\">{key}</td><td align=\"left\">{value}</td></tr>");
+    let expected_output = format!(
+      r###"digraph "Test Graph" {{
+  node [shape = box]
+  graph [rankdir = TB]
+
+  "cDe6M3HmMtiJnhL4ihtnyx" [shape = plain, label = <{table}<tr><td colspan="2"><b>42</b></td></tr><tr><td colspan="2">cDe6M3HmMtiJnhL4ihtnyx</td></tr>{}</table>>];
+  "m43pF1xXxnZvhCY1VeAnMV" [shape = plain, label = <{table}<tr><td colspan="2"><b>Parent</b></td></tr><tr><td colspan="2">m43pF1xXxnZvhCY1VeAnMV</td></tr>{}{}{}</table>>];
+  "m43pF1xXxnZvhCY1VeAnMV" -> {{"cDe6M3HmMtiJnhL4ihtnyx"}};
+}}
+"###,
+      row("data", "42"),
+      row("name", "Parent"),
+      row("note", "a &lt; b"),
+      row("size", "3"),
+    );
+    pretty_assertions::assert_eq!(dot_output, expected_output);
+  }
+
+  #[test]
+  fn test_links() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let data = serde_json::json!({ "name": "Parent", "url": "https://example.com/parent" });
+    let (parent_uuid, _) = graph.add_node_with_rng(data, &mut rng);
+    let (child_uuid, _) = graph.add_node_with_rng("Child", &mut rng);
+    assert!(graph.add_child(parent_uuid, child_uuid).is_ok());
+
+    let mermaid_output = format!("{}", graph.mermaid());
+    let expected_output = r###"---
+title: Test Graph
+---
+flowchart TB
+  cDe6M3HmMtiJnhL4ihtnyx[Child]
+  m43pF1xXxnZvhCY1VeAnMV[Parent] --> cDe6M3HmMtiJnhL4ihtnyx
+  click m43pF1xXxnZvhCY1VeAnMV href "https://example.com/parent"
+"###;
+    pretty_assertions::assert_eq!(mermaid_output, expected_output);
+
+    let options = RenderOptions {
+      link: Some("https://example.com/{id}".parse().unwrap()),
+      ..Default::default()
+    };
+    let dot_output = format!("{}", graph.dot_with(options));
+    dot_parser::ast::Graph::try_from(dot_output.as_str()).expect("DOT format is invalid");
+    let expected_output = r###"digraph "Test Graph" {
+  node [shape = box]
+  graph [rankdir = TB]
+
+  "cDe6M3HmMtiJnhL4ihtnyx" [label = "Child", URL = "https://example.com/cDe6M3HmMtiJnhL4ihtnyx"];
+  "m43pF1xXxnZvhCY1VeAnMV" [label = "Parent", URL = "https://example.com/m43pF1xXxnZvhCY1VeAnMV", tooltip = "url: https://example.com/parent"];
+  "m43pF1xXxnZvhCY1VeAnMV" -> {"cDe6M3HmMtiJnhL4ihtnyx"};
+}
+"###;
+    pretty_assertions::assert_eq!(dot_output, expected_output);
+  }
+
+  #[test]
+  fn test_sort_by() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("root");
+    let (b, _) = graph.add_node("b");
+    let (a, _) = graph.add_node("a");
+    let (c, _) = graph.add_node("c");
+    graph.add_child(root, b).unwrap();
+    graph.add_child(root, a).unwrap();
+    graph.add_child(a, c).unwrap();
+    let [root, a, b, c] = [root, a, b, c].map(|uuid| ShortUuid::from_uuid(&uuid));
+
+    let options = RenderOptions {
+      sort_by: SortBy::Name,
+      ..Default::default()
+    };
+    let expected_output = format!(
+      "---\ntitle: Test Graph\n---\nflowchart TB\n  {a}[a] --> {c}\n  {b}[b]\n  {c}[c]\n  \
+       {root}[root] --> {a} & {b}\n"
+    );
+    pretty_assertions::assert_eq!(graph.mermaid_with(options).to_string(), expected_output);
+
+    let options = RenderOptions {
+      sort_by: SortBy::Topological,
+      label: Some("{name}".parse().unwrap()),
+      ..Default::default()
+    };
+    let dot_output = graph.dot_with(options).to_string();
+    let lines: Vec<&str> = dot_output.lines().skip(4).collect();
+    // the root is first and c is last whatever the UUIDs
+    assert_eq!(lines[0], format!("  \"{root}\" [label = \"root\"];"));
+    assert!(lines[1].starts_with(&format!("  \"{root}\" -> ")));
+    assert_eq!(
+      lines[lines.len() - 2],
+      format!("  \"{c}\" [label = \"c\"];")
+    );
+  }
+
+  #[test]
+  fn test_dot_scores() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (parent_uuid, _) = graph.add_node_with_rng("Parent".to_string(), &mut rng);
+    let (child_uuid, _) = graph.add_node_with_rng("Child".to_string(), &mut rng);
+    assert!(graph.add_child(parent_uuid, child_uuid).is_ok());
+    let options = RenderOptions {
+      scores: Some(HashMap::from([(parent_uuid, 0.25), (child_uuid, 0.75)])),
+      ..Default::default()
+    };
+    let dot_output = format!("{}", graph.dot_with(options));
+    dot_parser::ast::Graph::try_from(dot_output.as_str()).expect("DOT format is invalid");
+
+    let expected_output = r###"digraph "Test Graph" {
+  node [shape = box]
+  graph [rankdir = TB]
+
+  "cDe6M3HmMtiJnhL4ihtnyx" [label = "Child", score = "0.75", fontsize = 30.0];
+  "m43pF1xXxnZvhCY1VeAnMV" [label = "Parent", score = "0.25", fontsize = 10.0];
+  "m43pF1xXxnZvhCY1VeAnMV" -> {"cDe6M3HmMtiJnhL4ihtnyx"};
+}
+"###;
+    pretty_assertions::assert_eq!(dot_output, expected_output);
+  }
+
   #[test]
   fn test_mermaid_format() {
     let mut rng = StdRng::seed_from_u64(42);
@@ -385,6 +1780,164 @@ flowchart TB
     pretty_assertions::assert_eq!(mermaid_output, expected_output);
   }
 
+  #[test]
+  fn test_ascii() {
+    // two roots sharing d, c has two parents
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let [root, a, b, c, d, e] = [1, 2, 3, 4, 5, 6].map(Uuid::from_u128);
+    for (uuid, name) in [root, a, b, c, d, e]
+      .into_iter()
+      .zip(["Root", "A", "B", "C", "D", "E"])
+    {
+      graph.add_node_with_uuid(uuid, name).unwrap();
+    }
+    for (parent, child) in [(root, a), (root, b), (a, c), (b, c), (c, d), (e, d)] {
+      graph.add_child(parent, child).unwrap();
+    }
+
+    let expected_output = "Root
+├── A
+│   └── C
+│       └── D
+└── B
+    └── C (*)
+E
+└── D (*)
+";
+    pretty_assertions::assert_eq!(graph.ascii().to_string(), expected_output);
+  }
+
+  #[test]
+  fn test_label_template() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (parent_uuid, _) = graph.add_node_with_rng("Parent".to_string(), &mut rng);
+    let (child_uuid, _) = graph.add_node_with_rng(7, &mut rng);
+    assert!(graph.add_child(parent_uuid, child_uuid).is_ok());
+    let options = RenderOptions {
+      label: Some("{name}:{data} d={depth}".parse().unwrap()),
+      ..Default::default()
+    };
+    let mermaid_output = format!("{}", graph.mermaid_with(options));
+
+    let expected_output = r###"---
+title: Test Graph
+---
+flowchart TB
+  cDe6M3HmMtiJnhL4ihtnyx[:7 d=1]
+  m43pF1xXxnZvhCY1VeAnMV[Parent:Parent d=0] --> cDe6M3HmMtiJnhL4ihtnyx
+"###;
+    pretty_assertions::assert_eq!(mermaid_output, expected_output);
+  }
+
+  #[test]
+  fn test_json_data() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let data = serde_json::json!({ "name": "Parent", "age": 42 });
+    let (parent_uuid, node) = graph.add_node_with_rng(data, &mut rng);
+    assert_eq!(node.name(), Some("Parent"));
+    let (child_uuid, _) = graph.add_node_with_rng(serde_json::json!({ "age": 7 }), &mut rng);
+    assert!(graph.add_child(parent_uuid, child_uuid).is_ok());
+    let mermaid_output = format!("{}", graph.mermaid());
+
+    let mut renamed = graph.get_node(child_uuid).unwrap().clone();
+    renamed.set_name("Child");
+    assert_eq!(
+      renamed.data(),
+      &NodeData::Json(serde_json::json!({ "name": "Child", "age": 7 }))
+    );
+
+    let expected_output = r###"---
+title: Test Graph
+---
+flowchart TB
+  cDe6M3HmMtiJnhL4ihtnyx["{#34;age#34;:7}"]
+  m43pF1xXxnZvhCY1VeAnMV[Parent] --> cDe6M3HmMtiJnhL4ihtnyx
+"###;
+    pretty_assertions::assert_eq!(mermaid_output, expected_output);
+  }
+
+  #[test]
+  fn test_attrs() {
+    let mut node = Node::new("Parent");
+    assert_eq!(node.attrs().count(), 0);
+    node.set_attr("cost", 10.5);
+    node.set_attr("team", "build");
+    assert_eq!(node.name(), Some("Parent"));
+    assert_eq!(node.attr("cost"), Some(&serde_json::json!(10.5)));
+    assert_eq!(node.attr("name"), None);
+    assert_eq!(
+      node.attrs().map(|(key, _)| key).collect::<Vec<_>>(),
+      ["cost", "team"]
+    );
+    assert_eq!(node.remove_attr("team"), Some(serde_json::json!("build")));
+    assert_eq!(node.remove_attr("name"), None);
+
+    let mut number = Node::new(7u64);
+    number.set_attr("cost", 1);
+    assert_eq!(
+      number.data(),
+      &NodeData::Json(serde_json::json!({ "data": 7, "cost": 1 }))
+    );
+
+    // a tooltip in DOT, a key per attribute in GraphML and the data in JSON
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (uuid, _) = graph.add_node("Parent");
+    graph.get_node_mut(uuid).unwrap().set_attr("cost", 10.5);
+    assert!(
+      graph
+        .dot()
+        .to_string()
+        .contains("[label = \"Parent\", tooltip = \"cost: 10.5\"];")
+    );
+    let json = serde_json::to_string(&graph).unwrap();
+    assert!(json.contains(r#""cost":10.5"#));
+  }
+
+  #[test]
+  fn test_levels() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let (a, _) = graph.add_node("A");
+    let (b, _) = graph.add_node("B");
+    let (c, _) = graph.add_node("C");
+    let (other, _) = graph.add_node("Other");
+    graph.add_child(root, a).unwrap();
+    graph.add_child(a, b).unwrap();
+    graph.add_child(b, c).unwrap();
+    graph.add_child(root, c).unwrap();
+    graph.add_child(other, c).unwrap();
+
+    let levels = graph.levels(root).unwrap();
+    assert_eq!(levels, vec![vec![root], vec![a], vec![b], vec![c]]);
+    assert_eq!(graph.levels(other).unwrap(), vec![vec![other], vec![c]]);
+    // the same without the topological order, after an undo
+    let snapshot = graph.snapshot();
+    graph.remove_child(root, c).unwrap();
+    graph.restore(snapshot).unwrap();
+    assert_eq!(graph.levels(root).unwrap(), levels);
+    assert!(matches!(
+      graph.levels(Uuid::new_v4()),
+      Err(Error::UuidNotFound { .. })
+    ));
+  }
+
+  #[test]
+  fn test_recorded_levels() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let (child, _) = graph.add_node("Child");
+    graph.add_child(root, child).unwrap();
+    assert_eq!(graph.depths(), HashMap::from([(root, 0), (child, 1)]));
+
+    // recorded levels win once every node have one
+    graph.get_node_mut(root).unwrap().set_level(3);
+    assert_eq!(graph.depths(), HashMap::from([(root, 0), (child, 1)]));
+    graph.get_node_mut(child).unwrap().set_level(4);
+    assert_eq!(graph.depths(), HashMap::from([(root, 3), (child, 4)]));
+  }
+
   #[test]
   fn test_parents() {
     let mut graph = AcyclicGraph::new("Test Graph");
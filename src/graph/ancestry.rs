@@ -0,0 +1,153 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+use uuid::Uuid;
+
+use super::{
+  AcyclicGraph,
+  Error,
+};
+
+impl AcyclicGraph {
+  /// Every node reachable from `uuid`, what it transitively leads to,
+  /// without itself
+  pub fn descendants(&self, uuid: Uuid) -> Result<HashSet<Uuid>, Error> {
+    self.get_node(uuid)?;
+    Ok(self.search(uuid, |current| {
+      self.nodes[&current].childs.iter().copied().collect()
+    }))
+  }
+
+  /// Every node that can reach `uuid`, what it transitively depends on,
+  /// without itself. The parents are read from an index kept by the graph,
+  /// nothing is rebuilt between two queries.
+  pub fn ancestors(&self, uuid: Uuid) -> Result<HashSet<Uuid>, Error> {
+    self.get_node(uuid)?;
+    let parents = self.parent_index();
+    Ok(self.search(uuid, |current| parents.get(current)))
+  }
+
+  /// Whether a path leads from `src` to `dst`, true for the same node. Only
+  /// the nodes placed between them in the topological order kept by the
+  /// graph are searched.
+  pub fn is_reachable(&self, src: Uuid, dst: Uuid) -> Result<bool, Error> {
+    self.get_node(src)?;
+    self.get_node(dst)?;
+    if src == dst {
+      return Ok(true);
+    }
+    let limit = self.order_position(dst);
+    if let (Some(src), Some(dst)) = (self.order_position(src), limit)
+      && dst < src
+    {
+      return Ok(false);
+    }
+    let mut visited = HashSet::from([src]);
+    let mut stack = vec![src];
+    while let Some(current) = stack.pop() {
+      for &child in &self.nodes[&current].childs {
+        if child == dst {
+          return Ok(true);
+        }
+        // a node placed after the destination can't reach it
+        let before = match (self.order_position(child), limit) {
+          (Some(child), Some(limit)) => child < limit,
+          _ => true,
+        };
+        if before && visited.insert(child) {
+          stack.push(child);
+        }
+      }
+    }
+    Ok(false)
+  }
+
+  /// The common ancestors of `a` and `b` that aren't an ancestor of another
+  /// one, sorted by UUID. A node counts as its own ancestor so the lowest
+  /// common ancestor of a node and one of its descendants is that node,
+  /// several are possible as a node can have several parents.
+  pub fn lowest_common_ancestors(&self, a: Uuid, b: Uuid) -> Result<Vec<Uuid>, Error> {
+    let mut common = self.ancestors(a)?;
+    common.insert(a);
+    let mut of_b = self.ancestors(b)?;
+    of_b.insert(b);
+    common.retain(|uuid| of_b.contains(uuid));
+    // a common ancestor above another one has a child in common, the one
+    // on the path to it
+    Ok(
+      common
+        .iter()
+        .filter(|uuid| {
+          !self.nodes[*uuid]
+            .childs
+            .iter()
+            .any(|child| common.contains(child))
+        })
+        .copied()
+        .sorted()
+        .collect(),
+    )
+  }
+
+  // the nodes reached from `start` without it
+  fn search(&self, start: Uuid, next: impl Fn(Uuid) -> Vec<Uuid>) -> HashSet<Uuid> {
+    let mut reached = HashSet::new();
+    let mut stack = vec![start];
+    while let Some(current) = stack.pop() {
+      for other in next(current) {
+        if reached.insert(other) {
+          stack.push(other);
+        }
+      }
+    }
+    reached
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_ancestry() {
+    // root -> a -> c, root -> b -> c, a -> d, b -> d, e alone
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let [root, a, b, c, d, e] =
+      ["Root", "A", "B", "C", "D", "E"].map(|name| graph.add_node(name).0);
+    for (parent, child) in [(root, a), (root, b), (a, c), (b, c), (a, d), (b, d)] {
+      graph.add_child(parent, child).unwrap();
+    }
+
+    assert_eq!(graph.descendants(a), Ok(HashSet::from([c, d])));
+    assert_eq!(graph.descendants(c), Ok(HashSet::new()));
+    assert_eq!(graph.ancestors(c), Ok(HashSet::from([root, a, b])));
+    assert_eq!(graph.ancestors(root), Ok(HashSet::new()));
+    let missing = Uuid::nil();
+    assert_eq!(
+      graph.ancestors(missing),
+      Err(Error::UuidNotFound { uuid: missing })
+    );
+
+    assert_eq!(graph.is_reachable(root, d), Ok(true));
+    assert_eq!(graph.is_reachable(c, c), Ok(true));
+    assert_eq!(graph.is_reachable(d, root), Ok(false));
+    assert_eq!(graph.is_reachable(c, d), Ok(false));
+    assert_eq!(graph.is_reachable(e, c), Ok(false));
+
+    let sorted = |mut uuids: Vec<Uuid>| {
+      uuids.sort();
+      uuids
+    };
+    assert_eq!(graph.lowest_common_ancestors(c, d), Ok(sorted(vec![a, b])));
+    assert_eq!(graph.lowest_common_ancestors(a, b), Ok(vec![root]));
+    assert_eq!(graph.lowest_common_ancestors(a, c), Ok(vec![a]));
+    assert_eq!(graph.lowest_common_ancestors(c, e), Ok(vec![]));
+
+    // the same answers once the order is invalidated by an undo
+    let snapshot = graph.snapshot();
+    graph.remove_child(a, d).unwrap();
+    graph.restore(snapshot).unwrap();
+    assert_eq!(graph.ancestors(d), Ok(HashSet::from([root, a, b])));
+    assert_eq!(graph.is_reachable(a, d), Ok(true));
+  }
+}
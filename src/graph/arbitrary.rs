@@ -0,0 +1,103 @@
+use proptest::{
+  arbitrary::Arbitrary,
+  collection::vec,
+  prelude::*,
+};
+use uuid::Uuid;
+
+use super::AcyclicGraph;
+
+/// Bounds of the graphs generated by the [`Arbitrary`] implementation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArbitraryParameters {
+  pub max_nodes: usize,
+  pub max_edges: usize,
+}
+
+impl Default for ArbitraryParameters {
+  fn default() -> Self {
+    Self {
+      max_nodes: 32,
+      max_edges: 64,
+    }
+  }
+}
+
+/// Node `i` has the UUID `i + 1` and the data `i`, an edge always goes from
+/// the lowest index to the highest so removing nodes or edges while shrinking
+/// can never create a cycle.
+impl Arbitrary for AcyclicGraph {
+  type Parameters = ArbitraryParameters;
+  type Strategy = BoxedStrategy<Self>;
+
+  fn arbitrary_with(parameters: Self::Parameters) -> Self::Strategy {
+    (0..=parameters.max_nodes)
+      .prop_flat_map(move |nodes| {
+        let edges = if nodes < 2 {
+          vec((0..1usize, 0..1usize), 0..=0)
+        } else {
+          vec((0..nodes, 0..nodes), 0..=parameters.max_edges)
+        };
+        (Just(nodes), edges)
+      })
+      .prop_map(|(nodes, edges)| {
+        let mut graph = AcyclicGraph::new("arbitrary");
+        let uuids: Vec<Uuid> = (0..nodes)
+          .map(|i| {
+            graph
+              .add_node_uuid(Uuid::from_u128(i as u128 + 1), i as u64)
+              .0
+          })
+          .collect();
+        for (a, b) in edges {
+          if a != b {
+            // duplicated edges are just ignored
+            let _ = graph.add_child(uuids[a.min(b)], uuids[a.max(b)]);
+          }
+        }
+        graph
+      })
+      .boxed()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashSet;
+
+  use super::*;
+
+  proptest! {
+    #[test]
+    fn test_check_invariants(graph in any::<AcyclicGraph>()) {
+      prop_assert_eq!(graph.check_invariants(), Ok(()));
+    }
+
+    #[test]
+    fn test_generations_cover_all_nodes(graph in any::<AcyclicGraph>()) {
+      let count: usize = graph.topological_generations().map(|generation| generation.len()).sum();
+      prop_assert_eq!(count, graph.nodes().len());
+    }
+
+    #[test]
+    fn test_minimum_path_cover_is_a_partition(graph in any::<AcyclicGraph>()) {
+      let paths = graph.minimum_path_cover();
+      let covered: HashSet<Uuid> = paths.iter().flatten().copied().collect();
+      prop_assert_eq!(covered.len(), graph.nodes().len());
+      prop_assert_eq!(paths.iter().map(Vec::len).sum::<usize>(), graph.nodes().len());
+      // Dilworth: a path cover can't be smaller than an antichain
+      prop_assert!(paths.len() >= graph.maximum_antichain().len());
+    }
+
+    #[test]
+    fn test_maximum_antichain_is_an_antichain(graph in any::<AcyclicGraph>()) {
+      let antichain = graph.maximum_antichain();
+      for &x in &antichain {
+        let reachable: HashSet<Uuid> = graph.levels(x).unwrap().into_iter().flatten().collect();
+        for &y in &antichain {
+          prop_assert!(x == y || !reachable.contains(&y));
+        }
+      }
+    }
+  }
+}
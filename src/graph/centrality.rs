@@ -0,0 +1,173 @@
+use std::collections::{
+  HashMap,
+  VecDeque,
+};
+
+use uuid::Uuid;
+
+use super::AcyclicGraph;
+
+impl AcyclicGraph {
+  // normalize by n - 1 like networkx, 0 for a graph of one node
+  fn degree_centrality(&self, degrees: impl Iterator<Item = (Uuid, usize)>) -> HashMap<Uuid, f64> {
+    let scale = match self.nodes.len() {
+      0 | 1 => 0.0,
+      n => 1.0 / (n - 1) as f64,
+    };
+    degrees
+      .map(|(uuid, degree)| (uuid, degree as f64 * scale))
+      .collect()
+  }
+
+  /// Fraction of the other nodes that are parents of each node
+  pub fn in_degree_centrality(&self) -> HashMap<Uuid, f64> {
+    self.degree_centrality(self.in_degrees().into_iter())
+  }
+
+  /// Fraction of the other nodes that are childs of each node
+  pub fn out_degree_centrality(&self) -> HashMap<Uuid, f64> {
+    self.degree_centrality(
+      self
+        .nodes
+        .iter()
+        .map(|(&uuid, node)| (uuid, node.childs.len())),
+    )
+  }
+
+  /// Fraction of the shortest paths between two other nodes that go through
+  /// each node, normalized by `(n - 1)(n - 2)` like networkx for directed
+  /// graphs. Brandes' algorithm, `O(nodes * edges)`.
+  pub fn betweenness_centrality(&self) -> HashMap<Uuid, f64> {
+    let mut betweenness: HashMap<Uuid, f64> = self.nodes.keys().map(|&uuid| (uuid, 0.0)).collect();
+
+    for &source in self.nodes.keys() {
+      let mut stack = Vec::new();
+      let mut predecessors: HashMap<Uuid, Vec<Uuid>> = HashMap::new();
+      let mut sigma: HashMap<Uuid, f64> = HashMap::from([(source, 1.0)]);
+      let mut distances: HashMap<Uuid, usize> = HashMap::from([(source, 0)]);
+      let mut queue = VecDeque::from([source]);
+
+      while let Some(current) = queue.pop_front() {
+        stack.push(current);
+        let distance = distances[&current];
+        for &child in &self.nodes[&current].childs {
+          let child_distance = *distances.entry(child).or_insert_with(|| {
+            queue.push_back(child);
+            distance + 1
+          });
+          if child_distance == distance + 1 {
+            *sigma.entry(child).or_default() += sigma[&current];
+            predecessors.entry(child).or_default().push(current);
+          }
+        }
+      }
+
+      let mut delta: HashMap<Uuid, f64> = HashMap::new();
+      while let Some(current) = stack.pop() {
+        let coefficient = (1.0 + delta.get(&current).copied().unwrap_or(0.0)) / sigma[&current];
+        for predecessor in predecessors.get(&current).into_iter().flatten() {
+          *delta.entry(*predecessor).or_default() += sigma[predecessor] * coefficient;
+        }
+        if current != source {
+          *betweenness.entry(current).or_default() += delta.get(&current).copied().unwrap_or(0.0);
+        }
+      }
+    }
+
+    let n = self.nodes.len();
+    if n > 2 {
+      let scale = 1.0 / ((n - 1) * (n - 2)) as f64;
+      for value in betweenness.values_mut() {
+        *value *= scale;
+      }
+    }
+    betweenness
+  }
+
+  /// PageRank of each node with the given damping factor, usually 0.85.
+  /// Leaves redistribute their rank to every node. Iterate until the total
+  /// change is below `1e-10` or 100 iterations.
+  pub fn pagerank(&self, damping: f64) -> HashMap<Uuid, f64> {
+    let n = self.nodes.len();
+    let mut ranks: HashMap<Uuid, f64> = self
+      .nodes
+      .keys()
+      .map(|&uuid| (uuid, 1.0 / n as f64))
+      .collect();
+
+    for _ in 0..100 {
+      let dangling: f64 = self
+        .nodes
+        .iter()
+        .filter(|(_, node)| node.childs.is_empty())
+        .map(|(uuid, _)| ranks[uuid])
+        .sum();
+      let base = (1.0 - damping) / n as f64 + damping * dangling / n as f64;
+
+      let mut next: HashMap<Uuid, f64> = self.nodes.keys().map(|&uuid| (uuid, base)).collect();
+      for (uuid, node) in &self.nodes {
+        let share = damping * ranks[uuid] / node.childs.len().max(1) as f64;
+        for child in &node.childs {
+          *next.entry(*child).or_default() += share;
+        }
+      }
+
+      let change: f64 = next
+        .iter()
+        .map(|(uuid, rank)| (rank - ranks[uuid]).abs())
+        .sum();
+      ranks = next;
+      if change < 1e-10 {
+        break;
+      }
+    }
+
+    ranks
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_centrality() {
+    // a -> b -> c -> d and a -> e -> d
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let [a, b, c, d, e] = ["A", "B", "C", "D", "E"].map(|name| graph.add_node(name).0);
+    for (parent, child) in [(a, b), (b, c), (c, d), (a, e), (e, d)] {
+      graph.add_child(parent, child).unwrap();
+    }
+
+    let in_degree = graph.in_degree_centrality();
+    assert_eq!(in_degree[&a], 0.0);
+    assert_eq!(in_degree[&d], 0.5);
+    let out_degree = graph.out_degree_centrality();
+    assert_eq!(out_degree[&a], 0.5);
+    assert_eq!(out_degree[&d], 0.0);
+
+    // shortest paths: a-c through b, b-d through c, a-d only through e
+    let betweenness = graph.betweenness_centrality();
+    let expected = HashMap::from([(a, 0.0), (b, 1.0), (c, 1.0), (d, 0.0), (e, 1.0)]);
+    for (uuid, value) in expected {
+      assert!((betweenness[&uuid] - value / 12.0).abs() < 1e-12);
+    }
+  }
+
+  #[test]
+  fn test_pagerank() {
+    // a -> c, b -> c: c collect the rank of a and b
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let [a, b, c] = ["A", "B", "C"].map(|name| graph.add_node(name).0);
+    graph.add_child(a, c).unwrap();
+    graph.add_child(b, c).unwrap();
+
+    let ranks = graph.pagerank(0.85);
+    assert!((ranks.values().sum::<f64>() - 1.0).abs() < 1e-9);
+    assert!((ranks[&a] - ranks[&b]).abs() < 1e-12);
+    assert!(ranks[&c] > ranks[&a]);
+    // same values as networkx.pagerank
+    assert!((ranks[&a] - 0.2128).abs() < 1e-4);
+    assert!((ranks[&c] - 0.5745).abs() < 1e-4);
+  }
+}
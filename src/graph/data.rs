@@ -0,0 +1,101 @@
+use std::fmt::{
+  self,
+  Display,
+  Formatter,
+};
+
+use serde_json::{
+  Map,
+  Value,
+  json,
+};
+
+use super::{
+  AcyclicGraph,
+  NodeData,
+  RenderOptions,
+};
+
+impl AcyclicGraph {
+  /// JSON object of the data of each node keyed by its short UUID or
+  /// sequential ID, the nodes without data are left out. Written beside an
+  /// export made with [`RenderOptions::omit_data`].
+  pub fn data_file(&self, options: RenderOptions) -> DataFile<'_> {
+    DataFile {
+      graph: self,
+      options,
+    }
+  }
+}
+
+impl NodeData {
+  pub(super) fn to_json(&self) -> Option<Value> {
+    match self {
+      NodeData::Number(n) => Some(json!(n)),
+      NodeData::Text(text) => Some(json!(text)),
+      NodeData::Json(json) => Some(json.clone()),
+      NodeData::None => None,
+    }
+  }
+}
+
+pub struct DataFile<'a> {
+  graph: &'a AcyclicGraph,
+  options: RenderOptions,
+}
+
+impl Display for DataFile<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let data: Map<String, Value> = self
+      .graph
+      .nodes
+      .iter()
+      .filter_map(|(uuid, node)| Some((self.options.id(uuid).to_string(), node.data.to_json()?)))
+      .collect();
+    // object keys are sorted so the output is deterministic
+    writeln!(f, "{:#}", Value::Object(data))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{
+    SeedableRng,
+    rngs::StdRng,
+  };
+
+  use super::*;
+
+  #[test]
+  fn test_data_file() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (parent, _) = graph.add_node_with_rng("Parent", &mut rng);
+    let (child, _) = graph.add_node_with_rng(json!({ "name": "Child", "age": 7 }), &mut rng);
+    graph.add_node_with_rng(NodeData::None, &mut rng);
+    graph.add_child(parent, child).unwrap();
+
+    let options = RenderOptions {
+      omit_data: true,
+      ..Default::default()
+    };
+    let data: Value = serde_json::from_str(&graph.data_file(options.clone()).to_string()).unwrap();
+    assert_eq!(
+      data,
+      json!({
+        "cDe6M3HmMtiJnhL4ihtnyx": { "name": "Child", "age": 7 },
+        "m43pF1xXxnZvhCY1VeAnMV": "Parent",
+      })
+    );
+
+    let jgf = graph.jgf_with(options.clone()).to_string();
+    assert!(!jgf.contains("\"data\""));
+    let dot = graph
+      .dot_with(RenderOptions {
+        tables: true,
+        ..options
+      })
+      .to_string();
+    assert!(!dot.contains("age"));
+  }
+}
@@ -0,0 +1,291 @@
+use std::fmt::{
+  self,
+  Display,
+  Formatter,
+};
+
+use itertools::Itertools;
+use short_uuid::ShortUuid;
+use uuid::Uuid;
+
+use super::{
+  AcyclicGraph,
+  NodeData,
+  RenderOptions,
+  sanitize::DotEscape,
+};
+
+const ADDED: &str = "#4daf4a";
+const REMOVED: &str = "#e41a1c";
+const CHANGED: &str = "#ff7f00";
+
+/// Structural changes from a graph to another, see [`AcyclicGraph::diff`].
+/// Everything is sorted by UUID.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GraphDiff {
+  /// Nodes only in the other graph
+  pub added_nodes: Vec<Uuid>,
+  /// Nodes only in the first graph
+  pub removed_nodes: Vec<Uuid>,
+  /// Edges by parent and child only in the other graph
+  pub added_edges: Vec<(Uuid, Uuid)>,
+  /// Edges by parent and child only in the first graph
+  pub removed_edges: Vec<(Uuid, Uuid)>,
+  /// Nodes of both graphs with other data, with the data before and after
+  pub changed_nodes: Vec<(Uuid, NodeData, NodeData)>,
+}
+
+impl GraphDiff {
+  pub fn is_empty(&self) -> bool {
+    self.added_nodes.is_empty()
+      && self.removed_nodes.is_empty()
+      && self.added_edges.is_empty()
+      && self.removed_edges.is_empty()
+      && self.changed_nodes.is_empty()
+  }
+}
+
+/// A line per change, `+` for an addition, `-` for a removal and `~` for
+/// changed data, nodes by short UUID
+impl Display for GraphDiff {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let short = |uuid: &Uuid| ShortUuid::from_uuid(uuid);
+    for uuid in &self.removed_nodes {
+      writeln!(f, "- node {}", short(uuid))?;
+    }
+    for uuid in &self.added_nodes {
+      writeln!(f, "+ node {}", short(uuid))?;
+    }
+    for (uuid, before, after) in &self.changed_nodes {
+      writeln!(
+        f,
+        "~ node {}: {} -> {}",
+        short(uuid),
+        data_text(before),
+        data_text(after)
+      )?;
+    }
+    for (parent, child) in &self.removed_edges {
+      writeln!(f, "- edge {} -> {}", short(parent), short(child))?;
+    }
+    for (parent, child) in &self.added_edges {
+      writeln!(f, "+ edge {} -> {}", short(parent), short(child))?;
+    }
+    Ok(())
+  }
+}
+
+// the data as JSON so an empty text and no data can be told apart
+fn data_text(data: &NodeData) -> String {
+  data
+    .to_json()
+    .map_or_else(|| "none".to_string(), |json| json.to_string())
+}
+
+// the edges by parent and child
+fn edges(graph: &AcyclicGraph) -> impl Iterator<Item = (Uuid, Uuid)> + '_ {
+  graph
+    .nodes
+    .iter()
+    .flat_map(|(&parent, node)| node.childs.iter().map(move |&child| (parent, child)))
+}
+
+impl AcyclicGraph {
+  /// The nodes, edges and node data added, removed or changed from this graph
+  /// to `other`, the nodes are matched by UUID like after a mutation or a
+  /// conversion. Levels and timestamps are ignored.
+  pub fn diff(&self, other: &Self) -> GraphDiff {
+    let has_edge = |graph: &AcyclicGraph, (parent, child): (Uuid, Uuid)| {
+      graph
+        .nodes
+        .get(&parent)
+        .is_some_and(|node| node.childs.contains(&child))
+    };
+    GraphDiff {
+      added_nodes: other
+        .nodes
+        .keys()
+        .filter(|uuid| !self.nodes.contains_key(uuid))
+        .copied()
+        .collect(),
+      removed_nodes: self
+        .nodes
+        .keys()
+        .filter(|uuid| !other.nodes.contains_key(uuid))
+        .copied()
+        .collect(),
+      added_edges: edges(other)
+        .filter(|&edge| !has_edge(self, edge))
+        .sorted()
+        .collect(),
+      removed_edges: edges(self)
+        .filter(|&edge| !has_edge(other, edge))
+        .sorted()
+        .collect(),
+      changed_nodes: self
+        .nodes
+        .iter()
+        .filter_map(|(&uuid, node)| {
+          let after = other.nodes.get(&uuid)?;
+          (node.data != after.data).then(|| (uuid, node.data.clone(), after.data.clone()))
+        })
+        .collect(),
+    }
+  }
+
+  /// DOT of both graphs together, the added nodes and edges in green, the
+  /// removed ones dashed in red and the nodes with changed data in orange
+  /// labeled with their new data
+  pub fn diff_dot<'a>(&'a self, other: &'a Self) -> DiffDot<'a> {
+    DiffDot {
+      before: self,
+      after: other,
+      diff: self.diff(other),
+    }
+  }
+}
+
+pub struct DiffDot<'a> {
+  before: &'a AcyclicGraph,
+  after: &'a AcyclicGraph,
+  diff: GraphDiff,
+}
+
+impl Display for DiffDot<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    writeln!(
+      f,
+      "digraph \"{} -> {}\" {{",
+      DotEscape(&self.before.name),
+      DotEscape(&self.after.name)
+    )?;
+    writeln!(f, "  node [shape = box]")?;
+    writeln!(f, "  graph [rankdir = TB]")?;
+    writeln!(f)?;
+
+    let options = RenderOptions::default();
+    let diff = &self.diff;
+    // both in UUID order, the node after the change is kept
+    let nodes = self
+      .after
+      .nodes
+      .iter()
+      .merge_by(&self.before.nodes, |(a, _), (b, _)| a <= b)
+      .dedup_by(|(a, _), (b, _)| a == b);
+    for (uuid, node) in nodes {
+      let mut attributes = Vec::new();
+      if let Some(label) = options.label(*uuid, node, None) {
+        attributes.push(format!("label = \"{}\"", DotEscape(&label)));
+      }
+      let color = if diff.added_nodes.binary_search(uuid).is_ok() {
+        Some(ADDED)
+      } else if diff.removed_nodes.binary_search(uuid).is_ok() {
+        attributes.push("style = dashed".to_string());
+        Some(REMOVED)
+      } else if diff
+        .changed_nodes
+        .binary_search_by_key(uuid, |(uuid, ..)| *uuid)
+        .is_ok()
+      {
+        Some(CHANGED)
+      } else {
+        None
+      };
+      if let Some(color) = color {
+        attributes.push(format!("color = \"{color}\", fontcolor = \"{color}\""));
+      }
+      write!(f, "  \"{}\"", ShortUuid::from_uuid(uuid))?;
+      if !attributes.is_empty() {
+        write!(f, " [{}]", attributes.join(", "))?;
+      }
+      writeln!(f, ";")?;
+    }
+
+    writeln!(f)?;
+    let edges = edges(self.before).chain(edges(self.after)).sorted().dedup();
+    for (parent, child) in edges {
+      write!(
+        f,
+        "  \"{}\" -> \"{}\"",
+        ShortUuid::from_uuid(&parent),
+        ShortUuid::from_uuid(&child)
+      )?;
+      if diff.added_edges.binary_search(&(parent, child)).is_ok() {
+        write!(f, " [color = \"{ADDED}\"]")?;
+      } else if diff.removed_edges.binary_search(&(parent, child)).is_ok() {
+        write!(f, " [color = \"{REMOVED}\", style = dashed]")?;
+      }
+      writeln!(f, ";")?;
+    }
+    writeln!(f, "}}")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_diff() {
+    // root -> a -> b, root -> c
+    let mut before = AcyclicGraph::new("Before");
+    let (root, _) = before.add_node("Root");
+    let (a, _) = before.add_node("A");
+    let (b, _) = before.add_node("B");
+    let (c, _) = before.add_node("C");
+    for (parent, child) in [(root, a), (a, b), (root, c)] {
+      before.add_child(parent, child).unwrap();
+    }
+    assert!(before.diff(&before).is_empty());
+
+    let mut after = before.clone();
+    after.remove_node(c).unwrap();
+    let (d, _) = after.add_node("D");
+    after.add_child(b, d).unwrap();
+    after.add_child(root, b).unwrap();
+    after.get_node_mut(a).unwrap().set_name("A2");
+
+    let diff = before.diff(&after);
+    assert_eq!(diff.added_nodes, [d]);
+    assert_eq!(diff.removed_nodes, [c]);
+    assert_eq!(
+      diff.added_edges,
+      [(root, b), (b, d)].into_iter().sorted().collect_vec()
+    );
+    assert_eq!(diff.removed_edges, [(root, c)]);
+    assert_eq!(
+      diff.changed_nodes,
+      [(a, NodeData::from("A"), NodeData::from("A2"))]
+    );
+    let text = diff.to_string();
+    assert_eq!(text.lines().count(), 6);
+    assert!(text.contains(&format!(
+      "~ node {}: \"A\" -> \"A2\"\n",
+      ShortUuid::from_uuid(&a)
+    )));
+    assert_eq!(after.diff(&before).added_nodes, [c]);
+
+    let dot_output = before.diff_dot(&after).to_string();
+    dot_parser::ast::Graph::try_from(dot_output.as_str()).expect("DOT format is invalid");
+    let short = |uuid: &Uuid| ShortUuid::from_uuid(uuid).to_string();
+    assert!(dot_output.contains(&format!(
+      "  \"{}\" [label = \"D\", color = \"{ADDED}\", fontcolor = \"{ADDED}\"];\n",
+      short(&d)
+    )));
+    assert!(dot_output.contains(&format!(
+      "  \"{}\" [label = \"C\", style = dashed, color = \"{REMOVED}\", fontcolor = \
+       \"{REMOVED}\"];\n",
+      short(&c)
+    )));
+    assert!(dot_output.contains(&format!(
+      "  \"{}\" [label = \"A2\", color = \"{CHANGED}\", fontcolor = \"{CHANGED}\"];\n",
+      short(&a)
+    )));
+    assert!(dot_output.contains(&format!(
+      "  \"{}\" -> \"{}\" [color = \"{REMOVED}\", style = dashed];\n",
+      short(&root),
+      short(&c)
+    )));
+    assert!(dot_output.contains(&format!("  \"{}\" -> \"{}\";\n", short(&a), short(&b))));
+  }
+}
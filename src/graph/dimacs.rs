@@ -0,0 +1,93 @@
+use std::fmt::{
+  self,
+  Display,
+  Formatter,
+};
+
+use itertools::Itertools;
+
+use super::{
+  AcyclicGraph,
+  RenderOptions,
+  sanitize::LineEscape,
+};
+
+impl AcyclicGraph {
+  /// DIMACS edge format, nodes are numbered from 1 by UUID order and a
+  /// comment legend maps each number to its short UUID and label
+  pub fn dimacs_with(&self, options: RenderOptions) -> Dimacs<'_> {
+    Dimacs {
+      graph: self,
+      options,
+    }
+  }
+}
+
+pub struct Dimacs<'a> {
+  graph: &'a AcyclicGraph,
+  options: RenderOptions,
+}
+
+impl Display for Dimacs<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let depths = self.options.depths(self.graph);
+    let (uuids, indexes) = self.graph.indexes();
+    let nodes: Vec<_> = uuids
+      .iter()
+      .map(|uuid| (uuid, &self.graph.nodes[uuid]))
+      .collect();
+    let edges: usize = nodes.iter().map(|(_, node)| node.childs.len()).sum();
+
+    writeln!(f, "c {}", LineEscape(&self.options.name(self.graph)))?;
+    writeln!(f, "c edges go from the parent to the child")?;
+    for (i, (uuid, node)) in nodes.iter().enumerate() {
+      write!(f, "c {} {}", i + 1, self.options.id(uuid))?;
+      if let Some(label) = self.options.label(**uuid, node, depths.as_ref()) {
+        write!(f, " {}", LineEscape(&label))?;
+      }
+      writeln!(f)?;
+    }
+    writeln!(f, "p edge {} {edges}", nodes.len())?;
+    for (i, (_, node)) in nodes.iter().enumerate() {
+      for child in node.childs.iter().map(|child| indexes[child]).sorted() {
+        writeln!(f, "e {} {}", i + 1, child + 1)?;
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{
+    SeedableRng,
+    rngs::StdRng,
+  };
+
+  use super::*;
+
+  #[test]
+  fn test_dimacs() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (parent_uuid, _) = graph.add_node_with_rng("Parent", &mut rng);
+    let (child_uuid, _) = graph.add_node_with_rng("Child", &mut rng);
+    let (other_uuid, _) = graph.add_node_with_rng(42, &mut rng);
+    graph.add_child(parent_uuid, child_uuid).unwrap();
+    graph.add_child(parent_uuid, other_uuid).unwrap();
+
+    let expected_output = r###"c Test Graph
+c edges go from the parent to the child
+c 1 cDe6M3HmMtiJnhL4ihtnyx Child
+c 2 m43pF1xXxnZvhCY1VeAnMV Parent
+c 3 r1eLoQWPBenPRfbYKAqer2 42
+p edge 3 2
+e 2 1
+e 2 3
+"###;
+    pretty_assertions::assert_eq!(
+      graph.dimacs_with(RenderOptions::default()).to_string(),
+      expected_output
+    );
+  }
+}
@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use super::{
+  AcyclicGraph,
+  Error,
+};
+
+impl AcyclicGraph {
+  /// Immediate dominator of every node reachable from `root`, `root` itself
+  /// is not in the map. A node dominates another if every path from `root`
+  /// goes through it. In a DAG the immediate dominator of a node is the
+  /// common dominator of all its parents, so one pass in topological order is
+  /// enough.
+  pub fn dominators(&self, root: Uuid) -> Result<HashMap<Uuid, Uuid>, Error> {
+    let depths = self.longest_paths([root])?;
+    let mut order: Vec<(usize, Uuid)> =
+      depths.iter().map(|(&uuid, &depth)| (depth, uuid)).collect();
+    order.sort();
+    let parents = self.parents();
+
+    let mut idoms = HashMap::new();
+    let mut dominator_depths = HashMap::from([(root, 0)]);
+    for (_, uuid) in order.into_iter().filter(|&(_, uuid)| uuid != root) {
+      let idom = parents[&uuid]
+        .iter()
+        .copied()
+        .filter(|parent| depths.contains_key(parent))
+        .reduce(|a, b| intersect(&idoms, &dominator_depths, a, b))
+        .unwrap_or(root);
+      dominator_depths.insert(uuid, dominator_depths[&idom] + 1);
+      idoms.insert(uuid, idom);
+    }
+
+    Ok(idoms)
+  }
+}
+
+// walk up the dominator tree until both nodes meet
+fn intersect(
+  idoms: &HashMap<Uuid, Uuid>, depths: &HashMap<Uuid, usize>, mut a: Uuid, mut b: Uuid,
+) -> Uuid {
+  while a != b {
+    if depths[&a] > depths[&b] {
+      a = idoms[&a];
+    } else {
+      b = idoms[&b];
+    }
+  }
+  a
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_dominators() {
+    // root -> a -> b -> d, a -> c -> d, d -> e, root -> f -> e
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let [a, b, c, d, e, f] = ["A", "B", "C", "D", "E", "F"].map(|name| graph.add_node(name).0);
+    for (parent, child) in [
+      (root, a),
+      (a, b),
+      (b, d),
+      (a, c),
+      (c, d),
+      (d, e),
+      (root, f),
+      (f, e),
+    ] {
+      graph.add_child(parent, child).unwrap();
+    }
+
+    let idoms = graph.dominators(root).unwrap();
+    assert_eq!(
+      idoms,
+      HashMap::from([(a, root), (b, a), (c, a), (d, a), (e, root), (f, root)])
+    );
+
+    let idoms = graph.dominators(a).unwrap();
+    assert_eq!(idoms, HashMap::from([(b, a), (c, a), (d, a), (e, d)]));
+  }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use dot_parser::{
+  ast,
+  canonical,
+};
+use itertools::Itertools;
+use serde_json::{
+  Map,
+  Value,
+};
+
+use super::{
+  AcyclicGraph,
+  Error,
+  parse_uuid,
+};
+
+impl AcyclicGraph {
+  /// Build a graph from a DOT digraph, like a hand written dependency graph
+  /// or an export of [`AcyclicGraph::dot`]. A node keeps its ID as UUID when
+  /// it's a short UUID or an UUID, its data is its label or else its ID, and
+  /// the edge attributes become edge data. An edge closing a cycle is an
+  /// [`Error::Cycle`], a repeated edge is merged.
+  pub fn from_dot(dot: &str) -> Result<Self, Error> {
+    let ast = ast::Graph::try_from(dot).map_err(|e| Error::InvalidDot {
+      message: e.to_string(),
+    })?;
+    if !ast.is_digraph {
+      return Err(Error::UndirectedDot);
+    }
+    let dot = canonical::Graph::from(ast);
+    let name = dot
+      .name
+      .as_deref()
+      .map_or_else(|| "dot".to_string(), unquote);
+    let mut graph = AcyclicGraph::new(name);
+
+    let labels: HashMap<String, String> = dot
+      .nodes
+      .set
+      .values()
+      .filter_map(|node| {
+        let label = attributes(&node.attr)
+          .filter(|(key, _)| key == "label")
+          .last()?;
+        Some((unquote(&node.id), label.1))
+      })
+      .collect();
+    // the nodes only found in edges too, sorted so the IDs are added in order
+    let ids = dot
+      .nodes
+      .set
+      .values()
+      .map(|node| unquote(&node.id))
+      .chain(
+        dot
+          .edges
+          .set
+          .iter()
+          .flat_map(|edge| [unquote(&edge.from), unquote(&edge.to)]),
+      )
+      .sorted()
+      .dedup();
+    let mut uuids = HashMap::new();
+    for id in ids {
+      let data = labels.get(&id).cloned().unwrap_or_else(|| id.clone());
+      let uuid = match parse_uuid(&id) {
+        Some(uuid) => graph.add_node_with_uuid(uuid, data)?.0,
+        None => graph.add_node(data).0,
+      };
+      uuids.insert(id, uuid);
+    }
+
+    for edge in &dot.edges.set {
+      let parent = uuids[&unquote(&edge.from)];
+      let child = uuids[&unquote(&edge.to)];
+      match graph.add_child(parent, child) {
+        Ok(()) | Err(Error::ChildAlreadyExist { .. }) => {}
+        Err(e) => return Err(e),
+      }
+      let data: Map<String, Value> = attributes(&edge.attr)
+        .map(|(key, value)| (key, Value::String(value)))
+        .collect();
+      if !data.is_empty() {
+        graph.set_edge_data(parent, child, data)?;
+      }
+    }
+    Ok(graph)
+  }
+}
+
+fn attributes<'a>(
+  attr: &'a ast::AttrList<(&'a str, &'a str)>,
+) -> impl Iterator<Item = (String, String)> + 'a {
+  attr
+    .elems
+    .iter()
+    .flat_map(|list| &list.elems)
+    .map(|(key, value)| (unquote(key), unquote(value)))
+}
+
+// the IDs and values keep their quotes
+fn unquote(id: &str) -> String {
+  match id.strip_prefix('"').and_then(|id| id.strip_suffix('"')) {
+    Some(id) => id.replace("\\\"", "\""),
+    None => id.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::graph::NodeData;
+
+  #[test]
+  fn test_from_dot() {
+    let graph = AcyclicGraph::from_dot(
+      r#"digraph deps {
+  node [shape = box]
+  build [label = "Build"];
+  build -> test -> "deploy prod" [weight = "2"];
+  build -> lint;
+  build -> test;
+}"#,
+    )
+    .unwrap();
+    assert_eq!(graph.name(), "deps");
+    assert_eq!(graph.nodes().len(), 4);
+    let find = |data: &str| {
+      graph
+        .nodes()
+        .iter()
+        .find(|(_, node)| node.data() == &NodeData::from(data))
+        .map(|(uuid, _)| *uuid)
+        .unwrap()
+    };
+    let (build, test, deploy) = (find("Build"), find("test"), find("deploy prod"));
+    assert_eq!(graph.nodes()[&build].childs().len(), 2);
+    assert_eq!(
+      graph.edge_data(test, deploy),
+      Some(&Map::from_iter([("weight".to_string(), Value::from("2"))]))
+    );
+
+    // an export keeps its UUIDs
+    assert_eq!(AcyclicGraph::from_dot(&graph.dot().to_string()), Ok(graph));
+
+    assert!(matches!(
+      AcyclicGraph::from_dot("digraph { a -> b -> c -> a }"),
+      Err(Error::Cycle { .. })
+    ));
+    assert_eq!(
+      AcyclicGraph::from_dot("graph { a -- b }"),
+      Err(Error::UndirectedDot)
+    );
+    assert!(matches!(
+      AcyclicGraph::from_dot("digraph {"),
+      Err(Error::InvalidDot { .. })
+    ));
+  }
+}
@@ -0,0 +1,196 @@
+use std::{
+  borrow::Cow,
+  collections::BTreeSet,
+};
+
+use serde_json::{
+  Map,
+  Value,
+};
+use uuid::Uuid;
+
+use super::{
+  AcyclicGraph,
+  Error,
+  history::Change,
+};
+
+/// Weight and label of an edge, stored in its data under the `weight` and
+/// `label` keys, see [`AcyclicGraph::add_child_with`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct EdgeData {
+  pub weight: f64,
+  pub label: Option<String>,
+}
+
+impl From<EdgeData> for Map<String, Value> {
+  fn from(edge: EdgeData) -> Self {
+    let mut data = Map::from_iter([("weight".to_string(), Value::from(edge.weight))]);
+    data.extend(
+      edge
+        .label
+        .map(|label| ("label".to_string(), Value::from(label))),
+    );
+    data
+  }
+}
+
+impl AcyclicGraph {
+  /// Add an edge with a weight and an optional label, like
+  /// [`AcyclicGraph::add_child`] then [`AcyclicGraph::set_edge_data`]
+  pub fn add_child_with(&mut self, parent: Uuid, child: Uuid, edge: EdgeData) -> Result<(), Error> {
+    self.add_child(parent, child)?;
+    self.set_edge_data(parent, child, edge.into())?;
+    Ok(())
+  }
+
+  /// The `weight` of the edge data as a number or a numeric string, like
+  /// after a DOT import, 1 for an edge without weight and None without edge
+  pub fn edge_weight(&self, parent: Uuid, child: Uuid) -> Option<f64> {
+    if !self.nodes.get(&parent)?.childs.contains(&child) {
+      return None;
+    }
+    let weight = self
+      .edge_data(parent, child)
+      .and_then(|data| data.get("weight"))
+      .and_then(|weight| match weight {
+        Value::String(weight) => weight.parse().ok(),
+        weight => weight.as_f64(),
+      });
+    Some(weight.unwrap_or(1.0))
+  }
+
+  /// The `label` of the edge data
+  pub fn edge_label(&self, parent: Uuid, child: Uuid) -> Option<&str> {
+    self.edge_data(parent, child)?.get("label")?.as_str()
+  }
+
+  /// Attributes of the edge from `parent` to `child`, exported as DOT edge
+  /// attributes and JGF edge metadata. They are dropped with the edge and
+  /// brought back by undoing its removal.
+  pub fn edge_data(&self, parent: Uuid, child: Uuid) -> Option<&Map<String, Value>> {
+    self.edge_data.get(&(parent, child))
+  }
+
+  /// Replace the attributes of an existing edge, the previous ones are
+  /// returned. The change can be undone like the edge changes.
+  pub fn set_edge_data(
+    &mut self, parent: Uuid, child: Uuid, data: Map<String, Value>,
+  ) -> Result<Option<Map<String, Value>>, Error> {
+    if !self.get_node(parent)?.childs.contains(&child) {
+      return Err(Error::ChildNotFound { parent, child });
+    }
+    let previous = self.edge_data.insert((parent, child), data);
+    self.history.record(|| Change::EdgeData {
+      parent,
+      child,
+      data: previous.clone(),
+    });
+    Ok(previous)
+  }
+
+  // sorted keys of every edge data, the XML formats declare them first
+  pub(super) fn edge_keys(&self) -> BTreeSet<&str> {
+    self
+      .edge_data
+      .values()
+      .flat_map(|data| data.keys())
+      .map(String::as_str)
+      .collect()
+  }
+}
+
+// an edge value as attribute text, strings without their quotes
+pub(super) fn edge_text(value: &Value) -> Cow<'_, str> {
+  match value {
+    Value::String(value) => Cow::Borrowed(value),
+    value => Cow::Owned(value.to_string()),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn test_edge_data() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let (a, _) = graph.add_node("A");
+    let (b, _) = graph.add_node("B");
+    graph.add_child(root, a).unwrap();
+    graph.add_child(a, b).unwrap();
+    let data = |latency| Map::from_iter([("latency".to_string(), json!(latency))]);
+
+    assert_eq!(
+      graph.set_edge_data(root, b, data(1.5)),
+      Err(Error::ChildNotFound {
+        parent: root,
+        child: b
+      })
+    );
+    assert_eq!(graph.set_edge_data(root, a, data(1.5)), Ok(None));
+    assert_eq!(graph.set_edge_data(a, b, data(2.5)), Ok(None));
+    assert_eq!(graph.edge_data(root, a), Some(&data(1.5)));
+
+    let serialized = serde_json::to_string(&graph).unwrap();
+    let deserialized: AcyclicGraph = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, graph);
+
+    let jgf = graph.jgf_with(Default::default()).to_string();
+    assert!(jgf.contains("\"latency\": 1.5"));
+    let dot = graph.dot_with(Default::default()).to_string();
+    assert!(dot.contains("[\"latency\" = \"2.5\"];"));
+
+    graph.remove_child(root, a).unwrap();
+    assert_eq!(graph.edge_data(root, a), None);
+    graph.remove_node(b).unwrap();
+    assert_eq!(graph.edge_data(a, b), None);
+    assert!(graph.edge_data.is_empty());
+  }
+
+  #[test]
+  fn test_add_child_with() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    // a before b in the Mermaid links of the root
+    let [root, a, b] = [(1, "Root"), (2, "A"), (3, "B")].map(|(i, name)| {
+      graph
+        .add_node_with_uuid(Uuid::from_u128(i), name)
+        .unwrap()
+        .0
+    });
+    let edge = |weight, label: Option<&str>| EdgeData {
+      weight,
+      label: label.map(str::to_string),
+    };
+    graph
+      .add_child_with(root, a, edge(2.5, Some("build")))
+      .unwrap();
+    graph.add_child_with(a, b, edge(1.5, None)).unwrap();
+    graph.add_child(root, b).unwrap();
+    assert_eq!(
+      graph.add_child_with(root, a, edge(1.0, None)),
+      Err(Error::ChildAlreadyExist {
+        parent: root,
+        child: a
+      })
+    );
+    assert_eq!(graph.edge_weight(root, a), Some(2.5));
+    assert_eq!(graph.edge_label(root, a), Some("build"));
+    assert_eq!(graph.edge_label(a, b), None);
+    // without weight
+    assert_eq!(graph.edge_weight(root, b), Some(1.0));
+    assert_eq!(graph.edge_weight(b, root), None);
+
+    let dot = graph.dot().to_string();
+    assert!(dot.contains("[\"label\" = \"build\", \"weight\" = \"2.5\"];"));
+    let mermaid = graph.mermaid().to_string();
+    let [root, a, b] = [root, a, b].map(|uuid| short_uuid::ShortUuid::from_uuid(&uuid));
+    assert!(mermaid.contains(&format!(
+      "  {root}[Root] -->|build 2.5| {a}\n  {root} --> {b}\n"
+    )));
+    assert!(mermaid.contains(&format!("  {a}[A] -->|1.5| {b}\n")));
+  }
+}
@@ -0,0 +1,260 @@
+use std::collections::{
+  HashMap,
+  HashSet,
+};
+
+use uuid::Uuid;
+
+use super::{
+  AcyclicGraph,
+  Node,
+};
+
+const NONE: usize = usize::MAX;
+
+/// Same name and same nodes by UUID with the same data, level, timestamp and
+/// childs and the same edge data, snapshots and observers are ignored
+impl PartialEq for AcyclicGraph {
+  fn eq(&self, other: &Self) -> bool {
+    self.name == other.name && self.nodes == other.nodes && self.edge_data == other.edge_data
+  }
+}
+
+impl Eq for AcyclicGraph {}
+
+// nodes of a graph indexed in topological order with the indexes of their
+// childs and parents
+struct Indexed<'a> {
+  nodes: Vec<&'a Node>,
+  childs: Vec<HashSet<usize>>,
+  parents: Vec<Vec<usize>>,
+}
+
+impl<'a> Indexed<'a> {
+  fn new(graph: &'a AcyclicGraph) -> Self {
+    let uuids: Vec<Uuid> = graph.topological_generations().flatten().collect();
+    let indexes: HashMap<Uuid, usize> = uuids.iter().enumerate().map(|(i, &u)| (u, i)).collect();
+    let nodes: Vec<&Node> = uuids.iter().map(|uuid| &graph.nodes[uuid]).collect();
+    let childs: Vec<HashSet<usize>> = nodes
+      .iter()
+      .map(|node| node.childs.iter().map(|child| indexes[child]).collect())
+      .collect();
+    let mut parents = vec![Vec::new(); nodes.len()];
+    for (parent, childs) in childs.iter().enumerate() {
+      for &child in childs {
+        parents[child].push(parent);
+      }
+    }
+    Self {
+      nodes,
+      childs,
+      parents,
+    }
+  }
+
+  fn edges(&self) -> usize {
+    self.childs.iter().map(HashSet::len).sum()
+  }
+}
+
+// color refinement of both graphs at once so the colors can be compared,
+// nodes of the second graph are offset by the size of the first
+fn colors(a: &Indexed, b: &Indexed) -> Vec<usize> {
+  let offset = a.nodes.len();
+  let nodes = || a.nodes.iter().chain(&b.nodes);
+  let neighbors = |i: usize| {
+    let (graph, i, offset) = if i < offset {
+      (a, i, 0)
+    } else {
+      (b, i - offset, offset)
+    };
+    let childs = graph.childs[i].iter().map(move |c| c + offset);
+    let parents = graph.parents[i].iter().map(move |p| p + offset);
+    (childs, parents)
+  };
+
+  // JSON values aren't hashable, the debug output tell variants apart
+  let mut ids = HashMap::new();
+  let mut colors: Vec<usize> = nodes()
+    .map(|node| {
+      let key = (
+        format!("{:?}", node.data),
+        node.level,
+        node.timestamp.map(|timestamp| timestamp.0),
+      );
+      let len = ids.len();
+      *ids.entry(key).or_insert(len)
+    })
+    .collect();
+  let mut classes = ids.len();
+
+  loop {
+    let mut ids = HashMap::new();
+    let refined: Vec<usize> = (0..colors.len())
+      .map(|i| {
+        let (childs, parents) = neighbors(i);
+        let mut childs: Vec<usize> = childs.map(|c| colors[c]).collect();
+        let mut parents: Vec<usize> = parents.map(|p| colors[p]).collect();
+        childs.sort_unstable();
+        parents.sort_unstable();
+        let len = ids.len();
+        *ids.entry((colors[i], childs, parents)).or_insert(len)
+      })
+      .collect();
+    colors = refined;
+    // a refinement only split classes, the same count means it's stable
+    if ids.len() == classes {
+      return colors;
+    }
+    classes = ids.len();
+  }
+}
+
+impl AcyclicGraph {
+  /// Whether the graphs are the same up to the UUIDs: a bijection between
+  /// their nodes keeps the data, level, timestamp and edges. The graph name
+  /// is ignored. Candidates are narrowed by color refinement then matched by
+  /// backtracking, which is only slow for very symmetric graphs.
+  pub fn structurally_eq(&self, other: &Self) -> bool {
+    let a = Indexed::new(self);
+    let b = Indexed::new(other);
+    let n = a.nodes.len();
+    if n != b.nodes.len() || a.edges() != b.edges() {
+      return false;
+    }
+
+    let colors = colors(&a, &b);
+    let mut candidates: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (i, &color) in colors[n..].iter().enumerate() {
+      candidates.entry(color).or_default().push(i);
+    }
+    let mut counts: HashMap<usize, usize> = HashMap::new();
+    for &color in &colors[..n] {
+      *counts.entry(color).or_default() += 1;
+    }
+    if counts
+      .iter()
+      .any(|(color, count)| candidates.get(color).map(Vec::len) != Some(*count))
+    {
+      return false;
+    }
+
+    // map the nodes of a in topological order so every parent is mapped
+    // first, each edge of a is then checked once and the edge counts are
+    // equal so the mapping keeps every edge
+    let mut mapping = vec![NONE; n];
+    let mut used = vec![false; n];
+    let mut next = vec![0; n];
+    let mut u = 0;
+    while u < n {
+      if mapping[u] != NONE {
+        used[mapping[u]] = false;
+        mapping[u] = NONE;
+      }
+      let candidates = &candidates[&colors[u]];
+      let found = candidates[next[u]..].iter().position(|&v| {
+        !used[v]
+          && a.parents[u]
+            .iter()
+            .all(|&parent| b.childs[mapping[parent]].contains(&v))
+      });
+      if let Some(position) = found {
+        let v = candidates[next[u] + position];
+        next[u] += position + 1;
+        mapping[u] = v;
+        used[v] = true;
+        u += 1;
+      } else if u == 0 {
+        return false;
+      } else {
+        next[u] = 0;
+        u -= 1;
+      }
+    }
+    true
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // root with two identical subtrees, the UUIDs are random
+  fn two_subtrees() -> AcyclicGraph {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    for _ in 0..2 {
+      let (child, _) = graph.add_node("Child");
+      graph.add_child(root, child).unwrap();
+      for _ in 0..2 {
+        let (leaf, _) = graph.add_node("Leaf");
+        graph.add_child(child, leaf).unwrap();
+      }
+    }
+    graph
+  }
+
+  #[test]
+  fn test_eq() {
+    let graph = two_subtrees();
+    let mut other = graph.clone();
+    other.snapshot();
+    assert_eq!(graph, other);
+
+    let (root, _) = other
+      .nodes()
+      .iter()
+      .find(|(_, node)| node.name() == Some("Root"))
+      .unwrap();
+    other.get_node_mut(*root).unwrap().set_level(0);
+    assert_ne!(graph, other);
+    assert_ne!(graph, AcyclicGraph::new("Test Graph"));
+  }
+
+  #[test]
+  fn test_structurally_eq() {
+    let graph = two_subtrees();
+    let other = two_subtrees();
+    assert_ne!(graph, other);
+    assert!(graph.structurally_eq(&other));
+
+    // moving a leaf to the other child keeps the counts and the data
+    let mut moved = two_subtrees();
+    let childs: Vec<Uuid> = moved
+      .nodes()
+      .iter()
+      .filter(|(_, node)| node.name() == Some("Child"))
+      .map(|(uuid, _)| *uuid)
+      .collect();
+    let leaf = *moved.nodes()[&childs[0]].childs().iter().next().unwrap();
+    moved.remove_child(childs[0], leaf).unwrap();
+    moved.add_child(childs[1], leaf).unwrap();
+    assert!(!graph.structurally_eq(&moved));
+
+    let mut renamed = two_subtrees();
+    let (leaf, _) = renamed
+      .nodes()
+      .iter()
+      .find(|(_, node)| node.name() == Some("Leaf"))
+      .unwrap();
+    renamed.get_node_mut(*leaf).unwrap().set_name("Other");
+    assert!(!graph.structurally_eq(&renamed));
+  }
+
+  #[test]
+  fn test_structurally_eq_backtracking() {
+    // same data everywhere: a -> b, c -> d against a -> d, c -> b is still
+    // isomorphic, while a -> b -> c, d is not
+    let build = |edges: &[(usize, usize)]| {
+      let mut graph = AcyclicGraph::new("Test Graph");
+      let uuids: Vec<Uuid> = (0..4).map(|_| graph.add_node("Node").0).collect();
+      for &(parent, child) in edges {
+        graph.add_child(uuids[parent], uuids[child]).unwrap();
+      }
+      graph
+    };
+    let graph = build(&[(0, 1), (2, 3)]);
+    assert!(graph.structurally_eq(&build(&[(0, 3), (2, 1)])));
+    assert!(!graph.structurally_eq(&build(&[(0, 1), (1, 2)])));
+  }
+}
@@ -0,0 +1,243 @@
+use std::fmt::{
+  self,
+  Display,
+  Formatter,
+};
+
+use itertools::Itertools;
+
+use super::{
+  AcyclicGraph,
+  RenderOptions,
+  edge_data::edge_text,
+  sanitize::XmlEscape,
+};
+
+impl AcyclicGraph {
+  /// GEXF 1.3, <https://gexf.net>, the format of Gephi. Nodes are keyed by
+  /// short UUID or sequential ID with their label, their UUID, data, level
+  /// and timestamp are node attributes and the edge data keys are edge
+  /// attributes
+  pub fn gexf_with(&self, options: RenderOptions) -> Gexf<'_> {
+    Gexf {
+      graph: self,
+      options,
+    }
+  }
+}
+
+pub struct Gexf<'a> {
+  graph: &'a AcyclicGraph,
+  options: RenderOptions,
+}
+
+impl Display for Gexf<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let depths = self.options.depths(self.graph);
+    let ranks = self.options.ranks(self.graph, depths.as_ref());
+    let edge_keys = self.graph.edge_keys();
+
+    writeln!(f, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(f, r#"<gexf xmlns="http://gexf.net/1.3" version="1.3">"#)?;
+    writeln!(f, "  <meta>")?;
+    writeln!(
+      f,
+      "    <description>{}</description>",
+      XmlEscape(&self.options.name(self.graph))
+    )?;
+    writeln!(f, "  </meta>")?;
+    writeln!(f, r#"  <graph defaultedgetype="directed" mode="static">"#)?;
+    writeln!(f, r#"    <attributes class="node">"#)?;
+    for (id, kind) in [
+      ("uuid", "string"),
+      ("data", "string"),
+      ("level", "long"),
+      ("timestamp", "string"),
+    ] {
+      writeln!(
+        f,
+        r#"      <attribute id="{id}" title="{id}" type="{kind}"/>"#
+      )?;
+    }
+    writeln!(f, "    </attributes>")?;
+    if !edge_keys.is_empty() {
+      writeln!(f, r#"    <attributes class="edge">"#)?;
+      for (i, key) in edge_keys.iter().enumerate() {
+        writeln!(
+          f,
+          r#"      <attribute id="e{i}" title="{}" type="string"/>"#,
+          XmlEscape(key)
+        )?;
+      }
+      writeln!(f, "    </attributes>")?;
+    }
+
+    let nodes = ranks
+      .nodes(self.graph)
+      .map(|(&uuid, node)| (uuid, node))
+      .collect_vec();
+    writeln!(f, "    <nodes>")?;
+    for &(uuid, node) in &nodes {
+      let id = self.options.id(&uuid);
+      // Gephi shows the ID of a node without label
+      let label = self
+        .options
+        .label(uuid, node, depths.as_ref())
+        .unwrap_or_else(|| id.to_string());
+      writeln!(f, r#"      <node id="{id}" label="{}">"#, XmlEscape(&label))?;
+      let mut values = vec![("uuid", uuid.to_string())];
+      if !self.options.omit_data {
+        values.extend(node.data.to_json().map(|json| match json {
+          serde_json::Value::String(text) => ("data", text),
+          json => ("data", json.to_string()),
+        }));
+      }
+      values.extend(node.level.map(|level| ("level", level.to_string())));
+      values.extend(
+        node
+          .timestamp
+          .map(|timestamp| ("timestamp", timestamp.to_string())),
+      );
+      writeln!(f, "        <attvalues>")?;
+      for (key, value) in values {
+        writeln!(
+          f,
+          r#"          <attvalue for="{key}" value="{}"/>"#,
+          XmlEscape(&value)
+        )?;
+      }
+      writeln!(f, "        </attvalues>")?;
+      writeln!(f, "      </node>")?;
+    }
+    writeln!(f, "    </nodes>")?;
+
+    writeln!(f, "    <edges>")?;
+    let edges = nodes.iter().flat_map(|&(uuid, node)| {
+      node
+        .childs
+        .iter()
+        .sorted_by_key(|child| ranks.key(child))
+        .map(move |child| (uuid, *child))
+    });
+    for (i, (parent, child)) in edges.enumerate() {
+      let mut edge = format!(
+        r#"<edge id="{i}" source="{}" target="{}""#,
+        self.options.id(&parent),
+        self.options.id(&child)
+      );
+      // Gephi reads the weight and the label of an edge from its attributes
+      let data = self.graph.edge_data(parent, child);
+      if data.is_some_and(|data| data.contains_key("weight"))
+        && let Some(weight) = self.graph.edge_weight(parent, child)
+      {
+        edge += &format!(r#" weight="{weight}""#);
+      }
+      if let Some(label) = self.graph.edge_label(parent, child) {
+        edge += &format!(r#" label="{}""#, XmlEscape(label));
+      }
+      let Some(data) = data else {
+        writeln!(f, "      {edge}/>")?;
+        continue;
+      };
+      writeln!(f, "      {edge}>")?;
+      writeln!(f, "        <attvalues>")?;
+      for (i, key) in edge_keys.iter().enumerate() {
+        if let Some(value) = data.get(*key) {
+          writeln!(
+            f,
+            r#"          <attvalue for="e{i}" value="{}"/>"#,
+            XmlEscape(&edge_text(value))
+          )?;
+        }
+      }
+      writeln!(f, "        </attvalues>")?;
+      writeln!(f, "      </edge>")?;
+    }
+    writeln!(f, "    </edges>")?;
+    writeln!(f, "  </graph>")?;
+    writeln!(f, "</gexf>")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{
+    SeedableRng,
+    rngs::StdRng,
+  };
+  use serde_json::{
+    Map,
+    json,
+  };
+
+  use super::*;
+
+  #[test]
+  fn test_gexf() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut graph = AcyclicGraph::new("Test <Graph>");
+    let (parent_uuid, _) = graph.add_node_with_rng("Parent & co", &mut rng);
+    let (child_uuid, _) = graph.add_node_with_rng(json!({ "name": "Child", "age": 7 }), &mut rng);
+    let (other_uuid, _) = graph.add_node_with_rng(42, &mut rng);
+    graph.add_child(parent_uuid, child_uuid).unwrap();
+    graph.add_child(parent_uuid, other_uuid).unwrap();
+    graph
+      .set_edge_data(
+        parent_uuid,
+        other_uuid,
+        Map::from_iter([("latency".to_string(), json!(1.5))]),
+      )
+      .unwrap();
+
+    let expected_output = r###"<?xml version="1.0" encoding="UTF-8"?>
+<gexf xmlns="http://gexf.net/1.3" version="1.3">
+  <meta>
+    <description>Test &lt;Graph&gt;</description>
+  </meta>
+  <graph defaultedgetype="directed" mode="static">
+    <attributes class="node">
+      <attribute id="uuid" title="uuid" type="string"/>
+      <attribute id="data" title="data" type="string"/>
+      <attribute id="level" title="level" type="long"/>
+      <attribute id="timestamp" title="timestamp" type="string"/>
+    </attributes>
+    <attributes class="edge">
+      <attribute id="e0" title="latency" type="string"/>
+    </attributes>
+    <nodes>
+      <node id="cDe6M3HmMtiJnhL4ihtnyx" label="Child">
+        <attvalues>
+          <attvalue for="uuid" value="5e471e1f-3739-492e-9243-da17fc8090eb"/>
+          <attvalue for="data" value="{&quot;age&quot;:7,&quot;name&quot;:&quot;Child&quot;}"/>
+        </attvalues>
+      </node>
+      <node id="m43pF1xXxnZvhCY1VeAnMV" label="Parent &amp; co">
+        <attvalues>
+          <attvalue for="uuid" value="a2637d13-d171-4278-aadf-a8a3fbe8379b"/>
+          <attvalue for="data" value="Parent &amp; co"/>
+        </attvalues>
+      </node>
+      <node id="r1eLoQWPBenPRfbYKAqer2" label="42">
+        <attvalues>
+          <attvalue for="uuid" value="ca7cf321-e47a-4fc9-bfd0-32abc31b253f"/>
+          <attvalue for="data" value="42"/>
+        </attvalues>
+      </node>
+    </nodes>
+    <edges>
+      <edge id="0" source="m43pF1xXxnZvhCY1VeAnMV" target="cDe6M3HmMtiJnhL4ihtnyx"/>
+      <edge id="1" source="m43pF1xXxnZvhCY1VeAnMV" target="r1eLoQWPBenPRfbYKAqer2">
+        <attvalues>
+          <attvalue for="e0" value="1.5"/>
+        </attvalues>
+      </edge>
+    </edges>
+  </graph>
+</gexf>
+"###;
+    pretty_assertions::assert_eq!(
+      graph.gexf_with(RenderOptions::default()).to_string(),
+      expected_output
+    );
+  }
+}
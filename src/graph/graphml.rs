@@ -0,0 +1,236 @@
+use std::{
+  collections::BTreeMap,
+  fmt::{
+    self,
+    Display,
+    Formatter,
+  },
+};
+
+use itertools::Itertools;
+
+use super::{
+  AcyclicGraph,
+  RenderOptions,
+  edge_data::edge_text,
+  sanitize::XmlEscape,
+};
+
+impl AcyclicGraph {
+  /// GraphML, <http://graphml.graphdrawing.org>, read by yEd and Gephi. Nodes
+  /// are keyed by short UUID or sequential ID with their label, UUID, data,
+  /// level, timestamp and attributes, each edge data key is declared as a
+  /// string
+  pub fn graphml_with(&self, options: RenderOptions) -> Graphml<'_> {
+    Graphml {
+      graph: self,
+      options,
+    }
+  }
+}
+
+pub struct Graphml<'a> {
+  graph: &'a AcyclicGraph,
+  options: RenderOptions,
+}
+
+impl Display for Graphml<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let depths = self.options.depths(self.graph);
+    let ranks = self.options.ranks(self.graph, depths.as_ref());
+    let edge_keys = self.graph.edge_keys();
+    let attr_keys = match self.options.omit_data {
+      true => BTreeMap::new(),
+      false => attr_keys(self.graph),
+    };
+
+    writeln!(f, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+      f,
+      r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://graphml.graphdrawing.org/xmlns http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd">"#
+    )?;
+    for (key, kind) in [
+      ("label", "string"),
+      ("uuid", "string"),
+      ("data", "string"),
+      ("level", "long"),
+      ("timestamp", "string"),
+    ] {
+      writeln!(
+        f,
+        r#"  <key id="{key}" for="node" attr.name="{key}" attr.type="{kind}"/>"#
+      )?;
+    }
+    for (i, (key, kind)) in attr_keys.iter().enumerate() {
+      writeln!(
+        f,
+        r#"  <key id="a{i}" for="node" attr.name="{}" attr.type="{kind}"/>"#,
+        XmlEscape(key)
+      )?;
+    }
+    for (i, key) in edge_keys.iter().enumerate() {
+      writeln!(
+        f,
+        r#"  <key id="e{i}" for="edge" attr.name="{}" attr.type="string"/>"#,
+        XmlEscape(key)
+      )?;
+    }
+    writeln!(
+      f,
+      r#"  <graph id="{}" edgedefault="directed">"#,
+      XmlEscape(&self.options.name(self.graph))
+    )?;
+
+    let nodes = ranks
+      .nodes(self.graph)
+      .map(|(&uuid, node)| (uuid, node))
+      .collect_vec();
+    for &(uuid, node) in &nodes {
+      writeln!(f, r#"    <node id="{}">"#, self.options.id(&uuid))?;
+      let mut data = Vec::new();
+      if let Some(label) = self.options.label(uuid, node, depths.as_ref()) {
+        data.push(("label", label));
+      }
+      data.push(("uuid", uuid.to_string()));
+      if !self.options.omit_data {
+        data.extend(node.data.to_json().map(|json| match json {
+          serde_json::Value::String(text) => ("data", text),
+          json => ("data", json.to_string()),
+        }));
+      }
+      data.extend(node.level.map(|level| ("level", level.to_string())));
+      data.extend(
+        node
+          .timestamp
+          .map(|timestamp| ("timestamp", timestamp.to_string())),
+      );
+      for (key, value) in data {
+        writeln!(f, r#"      <data key="{key}">{}</data>"#, XmlEscape(&value))?;
+      }
+      for (i, key) in attr_keys.keys().enumerate() {
+        if let Some(value) = node.attr(key) {
+          writeln!(
+            f,
+            r#"      <data key="a{i}">{}</data>"#,
+            XmlEscape(&edge_text(value))
+          )?;
+        }
+      }
+      writeln!(f, "    </node>")?;
+    }
+
+    for &(uuid, node) in &nodes {
+      for child in node.childs.iter().sorted_by_key(|child| ranks.key(child)) {
+        let source = self.options.id(&uuid);
+        let target = self.options.id(child);
+        let Some(data) = self.graph.edge_data(uuid, *child) else {
+          writeln!(f, r#"    <edge source="{source}" target="{target}"/>"#)?;
+          continue;
+        };
+        writeln!(f, r#"    <edge source="{source}" target="{target}">"#)?;
+        for (i, key) in edge_keys.iter().enumerate() {
+          if let Some(value) = data.get(*key) {
+            writeln!(
+              f,
+              r#"      <data key="e{i}">{}</data>"#,
+              XmlEscape(&edge_text(value))
+            )?;
+          }
+        }
+        writeln!(f, "    </edge>")?;
+      }
+    }
+    writeln!(f, "  </graph>")?;
+    writeln!(f, "</graphml>")
+  }
+}
+
+// the attributes of every node with their GraphML type, a double or a
+// boolean when every value is one
+fn attr_keys(graph: &AcyclicGraph) -> BTreeMap<&str, &'static str> {
+  let mut keys = BTreeMap::new();
+  for (key, value) in graph.nodes.values().flat_map(|node| node.attrs()) {
+    let kind = match value {
+      serde_json::Value::Number(_) => "double",
+      serde_json::Value::Bool(_) => "boolean",
+      _ => "string",
+    };
+    keys
+      .entry(key)
+      .and_modify(|other| {
+        if *other != kind {
+          *other = "string";
+        }
+      })
+      .or_insert(kind);
+  }
+  keys
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{
+    SeedableRng,
+    rngs::StdRng,
+  };
+  use serde_json::{
+    Map,
+    json,
+  };
+
+  use super::*;
+
+  #[test]
+  fn test_graphml() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut graph = AcyclicGraph::new("Test <Graph>");
+    let (parent_uuid, _) = graph.add_node_with_rng("Parent & co", &mut rng);
+    let (child_uuid, _) = graph.add_node_with_rng(42, &mut rng);
+    graph.add_child(parent_uuid, child_uuid).unwrap();
+    graph.get_node_mut(parent_uuid).unwrap().set_level(0);
+    graph
+      .get_node_mut(parent_uuid)
+      .unwrap()
+      .set_attr("cost", 2.5);
+    graph
+      .set_edge_data(
+        parent_uuid,
+        child_uuid,
+        Map::from_iter([("latency".to_string(), json!(1.5))]),
+      )
+      .unwrap();
+
+    let expected_output = r###"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://graphml.graphdrawing.org/xmlns http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd">
+  <key id="label" for="node" attr.name="label" attr.type="string"/>
+  <key id="uuid" for="node" attr.name="uuid" attr.type="string"/>
+  <key id="data" for="node" attr.name="data" attr.type="string"/>
+  <key id="level" for="node" attr.name="level" attr.type="long"/>
+  <key id="timestamp" for="node" attr.name="timestamp" attr.type="string"/>
+  <key id="a0" for="node" attr.name="cost" attr.type="double"/>
+  <key id="e0" for="edge" attr.name="latency" attr.type="string"/>
+  <graph id="Test &lt;Graph&gt;" edgedefault="directed">
+    <node id="cDe6M3HmMtiJnhL4ihtnyx">
+      <data key="label">42</data>
+      <data key="uuid">5e471e1f-3739-492e-9243-da17fc8090eb</data>
+      <data key="data">42</data>
+    </node>
+    <node id="m43pF1xXxnZvhCY1VeAnMV">
+      <data key="label">Parent &amp; co</data>
+      <data key="uuid">a2637d13-d171-4278-aadf-a8a3fbe8379b</data>
+      <data key="data">{&quot;cost&quot;:2.5,&quot;name&quot;:&quot;Parent &amp; co&quot;}</data>
+      <data key="level">0</data>
+      <data key="a0">2.5</data>
+    </node>
+    <edge source="m43pF1xXxnZvhCY1VeAnMV" target="cDe6M3HmMtiJnhL4ihtnyx">
+      <data key="e0">1.5</data>
+    </edge>
+  </graph>
+</graphml>
+"###;
+    pretty_assertions::assert_eq!(
+      graph.graphml_with(RenderOptions::default()).to_string(),
+      expected_output
+    );
+  }
+}
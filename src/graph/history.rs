@@ -0,0 +1,250 @@
+use serde_json::{
+  Map,
+  Value,
+};
+use uuid::Uuid;
+
+use super::{
+  AcyclicGraph,
+  Error,
+  Node,
+  Removed,
+};
+
+/// Identify a snapshot taken with [`AcyclicGraph::snapshot`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SnapshotId(usize);
+
+// what is needed to revert a change
+#[derive(Debug, Clone)]
+pub(super) enum Change {
+  AddNode {
+    uuid: Uuid,
+  },
+  AddChild {
+    parent: Uuid,
+    child: Uuid,
+  },
+  RemoveChild {
+    parent: Uuid,
+    child: Uuid,
+    data: Option<Map<String, Value>>,
+  },
+  RemoveNode {
+    uuid: Uuid,
+    node: Node,
+    parents: Vec<Uuid>,
+    // of the edges from and to the node
+    edge_data: Vec<((Uuid, Uuid), Map<String, Value>)>,
+  },
+  // the data before set_edge_data
+  EdgeData {
+    parent: Uuid,
+    child: Uuid,
+    data: Option<Map<String, Value>>,
+  },
+  // the node before it was lent by get_node_mut
+  Node {
+    uuid: Uuid,
+    node: Node,
+  },
+}
+
+/// Journal of the changes since the first snapshot, nothing is recorded
+/// without snapshot
+#[derive(Debug, Clone, Default)]
+pub(super) struct History {
+  changes: Vec<Change>,
+  // number of changes when each snapshot was taken
+  snapshots: Vec<usize>,
+}
+
+impl History {
+  pub(super) fn record(&mut self, change: impl FnOnce() -> Change) {
+    if !self.snapshots.is_empty() {
+      self.changes.push(change());
+    }
+  }
+}
+
+impl AcyclicGraph {
+  /// Start recording the changes, the graph can be restored to this point
+  /// later. Only the changes are kept, not a copy of the graph.
+  pub fn snapshot(&mut self) -> SnapshotId {
+    self.history.snapshots.push(self.history.changes.len());
+    SnapshotId(self.history.snapshots.len() - 1)
+  }
+
+  /// Revert every change since the snapshot, the later snapshots are
+  /// forgotten but this one can be restored again
+  pub fn restore(&mut self, id: SnapshotId) -> Result<(), Error> {
+    let &len = self
+      .history
+      .snapshots
+      .get(id.0)
+      .ok_or(Error::SnapshotNotFound { id: id.0 })?;
+    while self.history.changes.len() > len {
+      self.undo_change();
+    }
+    self.history.snapshots.truncate(id.0 + 1);
+    Ok(())
+  }
+
+  /// Revert the last change since the last snapshot, return false if there
+  /// is none
+  pub fn undo(&mut self) -> bool {
+    let since = self.history.snapshots.last().copied().unwrap_or_default();
+    if self.history.changes.len() > since {
+      self.undo_change();
+      true
+    } else {
+      false
+    }
+  }
+
+  /// Forget every snapshot and stop recording the changes
+  pub fn clear_history(&mut self) {
+    self.history = Default::default();
+  }
+
+  fn undo_change(&mut self) {
+    let Some(change) = self.history.changes.pop() else {
+      return;
+    };
+    // changes are reverted in order so every node they refer to exists, the
+    // observers see the revert as new changes
+    match change {
+      Change::AddNode { uuid } => {
+        self.order.remove(&self.nodes, uuid);
+        if let Some(node) = self.nodes.remove(&uuid) {
+          self.names.remove(node.name());
+          self.observers.removed(Removed::Node { uuid, node: &node });
+        }
+      }
+      Change::AddChild { parent, child } => {
+        self.nodes.get_mut(&parent).unwrap().childs.remove(&child);
+        self.edge_data.remove(&(parent, child));
+        self.order.remove_edge(&self.nodes, parent, child);
+        self.observers.removed(Removed::Edge { parent, child });
+      }
+      // the order may have changed since the removal
+      Change::RemoveChild {
+        parent,
+        child,
+        data,
+      } => {
+        self.order.invalidate();
+        self.nodes.get_mut(&parent).unwrap().childs.insert(child);
+        self
+          .edge_data
+          .extend(data.map(|data| ((parent, child), data)));
+        self.observers.edge_added(parent, child);
+      }
+      Change::RemoveNode {
+        uuid,
+        node,
+        parents,
+        edge_data,
+      } => {
+        self.names.insert(uuid, node.name());
+        self.order.invalidate();
+        self.observers.node_added(uuid, &node);
+        self.nodes.insert(uuid, node);
+        for parent in parents {
+          self.nodes.get_mut(&parent).unwrap().childs.insert(uuid);
+          self.observers.edge_added(parent, uuid);
+        }
+        self.edge_data.extend(edge_data);
+      }
+      Change::EdgeData {
+        parent,
+        child,
+        data,
+      } => match data {
+        Some(data) => {
+          self.edge_data.insert((parent, child), data);
+        }
+        None => {
+          self.edge_data.remove(&(parent, child));
+        }
+      },
+      Change::Node { uuid, node } => {
+        self.names.insert(uuid, node.name());
+        if let Some(changed) = self.nodes.insert(uuid, node)
+          && changed.name() != self.nodes[&uuid].name()
+        {
+          self.names.remove(changed.name());
+        }
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_restore() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let (a, _) = graph.add_node("A");
+    let (b, _) = graph.add_node("B");
+    graph.add_child(root, a).unwrap();
+    graph.add_child(a, b).unwrap();
+    let before = graph.nodes().clone();
+
+    let data = |weight: u32| Map::from_iter([("weight".to_string(), weight.into())]);
+    graph.set_edge_data(root, a, data(1)).unwrap();
+    graph.set_edge_data(a, b, data(2)).unwrap();
+    let before_data = graph.edge_data.clone();
+
+    let snapshot = graph.snapshot();
+    let (c, _) = graph.add_node("C");
+    graph.add_child(b, c).unwrap();
+    graph.set_edge_data(b, c, data(3)).unwrap();
+    graph.set_edge_data(root, a, data(4)).unwrap();
+    graph.remove_child(root, a).unwrap();
+    graph.get_node_mut(a).unwrap().set_level(1);
+    graph.remove_node(b).unwrap();
+    assert_ne!(graph.nodes(), &before);
+    assert!(graph.edge_data.is_empty());
+
+    graph.restore(snapshot).unwrap();
+    assert_eq!(graph.nodes(), &before);
+    assert_eq!(graph.edge_data, before_data);
+
+    // the snapshot is kept
+    graph.remove_node(a).unwrap();
+    graph.restore(snapshot).unwrap();
+    assert_eq!(graph.nodes(), &before);
+
+    assert_eq!(
+      graph.restore(SnapshotId(1)),
+      Err(Error::SnapshotNotFound { id: 1 })
+    );
+  }
+
+  #[test]
+  fn test_undo() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    // nothing is recorded before the first snapshot
+    assert!(!graph.undo());
+
+    graph.snapshot();
+    let (a, _) = graph.add_node("A");
+    graph.add_child(root, a).unwrap();
+    assert!(graph.undo());
+    assert!(graph.get_node(root).unwrap().childs().is_empty());
+    assert!(graph.undo());
+    assert_eq!(graph.get_node(a), Err(Error::UuidNotFound { uuid: a }));
+    // the snapshot is the limit
+    assert!(!graph.undo());
+    assert_eq!(graph.nodes().len(), 1);
+
+    graph.clear_history();
+    graph.add_node("B");
+    assert!(!graph.undo());
+  }
+}
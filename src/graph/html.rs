@@ -0,0 +1,278 @@
+use std::{
+  collections::HashMap,
+  fmt::{
+    self,
+    Display,
+    Formatter,
+  },
+};
+
+use itertools::Itertools;
+use uuid::Uuid;
+
+use super::{
+  AcyclicGraph,
+  RenderOptions,
+};
+
+const NODE_HEIGHT: f64 = 24.0;
+const ROW_GAP: f64 = 56.0;
+const NODE_GAP: f64 = 16.0;
+const MARGIN: f64 = 16.0;
+// rough width of a character of the 12px font
+const CHAR_WIDTH: f64 = 7.0;
+
+// wheel to zoom around the cursor, drag to pan, by changing the view box
+const SCRIPT: &str = r#"const svg = document.querySelector("svg");
+let [x, y, w, h] = svg.getAttribute("viewBox").split(" ").map(Number);
+const view = () => svg.setAttribute("viewBox", `${x} ${y} ${w} ${h}`);
+svg.addEventListener("wheel", (e) => {
+  e.preventDefault();
+  const r = svg.getBoundingClientRect();
+  const s = e.deltaY > 0 ? 1.1 : 1 / 1.1;
+  const px = x + ((e.clientX - r.left) / r.width) * w;
+  const py = y + ((e.clientY - r.top) / r.height) * h;
+  [x, y, w, h] = [px - (px - x) * s, py - (py - y) * s, w * s, h * s];
+  view();
+});
+let drag = null;
+svg.addEventListener("pointerdown", (e) => (drag = [e.clientX, e.clientY]));
+window.addEventListener("pointerup", () => (drag = null));
+window.addEventListener("pointermove", (e) => {
+  if (!drag) return;
+  const r = svg.getBoundingClientRect();
+  x -= ((e.clientX - drag[0]) / r.width) * w;
+  y -= ((e.clientY - drag[1]) / r.height) * h;
+  drag = [e.clientX, e.clientY];
+  view();
+});"#;
+
+impl AcyclicGraph {
+  /// A self-contained HTML page with an SVG drawing of the graph, nodes are
+  /// placed in rows by longest path from a root, with pan and zoom
+  pub fn html_with(&self, options: RenderOptions) -> Html<'_> {
+    Html {
+      graph: self,
+      options,
+    }
+  }
+
+  // rows of nodes by longest path from a root, each row is ordered by the
+  // average position of the parents to reduce crossings
+  fn rows(&self) -> Vec<Vec<Uuid>> {
+    let parents = self.parents();
+    let roots = self.nodes.keys().filter(|uuid| !parents.contains_key(uuid));
+    // roots come from the graph so they exist
+    let depths = self.longest_paths(roots.copied()).unwrap_or_default();
+
+    let mut rows: Vec<Vec<Uuid>> = Vec::new();
+    for (uuid, depth) in depths.into_iter().sorted() {
+      if rows.len() <= depth {
+        rows.resize_with(depth + 1, Vec::new);
+      }
+      rows[depth].push(uuid);
+    }
+
+    let mut positions: HashMap<Uuid, f64> = HashMap::new();
+    for row in &mut rows {
+      let barycenter = |uuid: &Uuid| match parents.get(uuid) {
+        Some(parents) => {
+          parents.iter().map(|parent| positions[parent]).sum::<f64>() / parents.len() as f64
+        }
+        None => 0.0,
+      };
+      row.sort_by(|a, b| barycenter(a).total_cmp(&barycenter(b)).then(a.cmp(b)));
+      for (i, uuid) in row.iter().enumerate() {
+        positions.insert(*uuid, i as f64 / row.len() as f64);
+      }
+    }
+    rows
+  }
+}
+
+pub struct Html<'a> {
+  graph: &'a AcyclicGraph,
+  options: RenderOptions,
+}
+
+impl Display for Html<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let depths = self.options.depths(self.graph);
+    let labels: HashMap<Uuid, String> = self
+      .graph
+      .nodes
+      .iter()
+      .map(|(&uuid, node)| {
+        let label = self
+          .options
+          .label(uuid, node, depths.as_ref())
+          .unwrap_or_else(|| self.options.id(&uuid).to_string());
+        (uuid, label)
+      })
+      .collect();
+    let node_width = |uuid: &Uuid| labels[uuid].chars().count() as f64 * CHAR_WIDTH + 16.0;
+
+    let rows = self.graph.rows();
+    let row_widths: Vec<f64> = rows
+      .iter()
+      .map(|row| {
+        row
+          .iter()
+          .map(|uuid| node_width(uuid) + NODE_GAP)
+          .sum::<f64>()
+          - NODE_GAP
+      })
+      .collect();
+    let width = row_widths.iter().copied().fold(0.0, f64::max) + 2.0 * MARGIN;
+    let height = rows.len() as f64 * (NODE_HEIGHT + ROW_GAP) - ROW_GAP + 2.0 * MARGIN;
+
+    // top left corner of each node, rows are centered
+    let mut boxes: HashMap<Uuid, (f64, f64)> = HashMap::new();
+    for (i, (row, row_width)) in rows.iter().zip(&row_widths).enumerate() {
+      let mut x = (width - row_width) / 2.0;
+      let y = MARGIN + i as f64 * (NODE_HEIGHT + ROW_GAP);
+      for uuid in row {
+        boxes.insert(*uuid, (x, y));
+        x += node_width(uuid) + NODE_GAP;
+      }
+    }
+
+    let name = self.options.name(self.graph);
+    let title = Escape(&name);
+    writeln!(f, "<!DOCTYPE html>")?;
+    writeln!(f, "<html>")?;
+    writeln!(f, "<head>")?;
+    writeln!(f, "<meta charset=\"utf-8\">")?;
+    writeln!(f, "<title>{title}</title>")?;
+    writeln!(
+      f,
+      "<style>body {{ margin: 0; font-family: sans-serif; }} h1 {{ margin: 8px; font-size: 16px; \
+       }} svg {{ width: 100vw; height: calc(100vh - 40px); cursor: grab; }} rect {{ fill: #fff; \
+       stroke: #333; }} line {{ stroke: #888; }} text {{ font-size: 12px; dominant-baseline: \
+       middle; text-anchor: middle; }}</style>"
+    )?;
+    writeln!(f, "</head>")?;
+    writeln!(f, "<body>")?;
+    writeln!(f, "<h1>{title}</h1>")?;
+    writeln!(
+      f,
+      "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {width:.1} {height:.1}\">"
+    )?;
+    writeln!(
+      f,
+      "<defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" markerWidth=\"6\" \
+       markerHeight=\"6\" orient=\"auto\"><path d=\"M 0 0 L 10 5 L 0 10 z\" \
+       fill=\"#888\"/></marker></defs>"
+    )?;
+
+    for (uuid, node) in self.graph.nodes.iter().sorted_by_key(|node| node.0) {
+      let (x, y) = boxes[uuid];
+      for child in node.childs.iter().sorted() {
+        let (child_x, child_y) = boxes[child];
+        writeln!(
+          f,
+          "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{child_y:.1}\" \
+           marker-end=\"url(#arrow)\"/>",
+          x + node_width(uuid) / 2.0,
+          y + NODE_HEIGHT,
+          child_x + node_width(child) / 2.0,
+        )?;
+      }
+    }
+
+    for (uuid, node) in self.graph.nodes.iter().sorted_by_key(|node| node.0) {
+      let (x, y) = boxes[uuid];
+      let link = self.options.link(*uuid, node, depths.as_ref());
+      if let Some(link) = &link {
+        write!(f, "<a href=\"{}\">", Escape(link))?;
+      }
+      write!(
+        f,
+        "<g id=\"{}\"><rect x=\"{x:.1}\" y=\"{y:.1}\" width=\"{:.1}\" height=\"{NODE_HEIGHT}\" \
+         rx=\"4\"/><text x=\"{:.1}\" y=\"{:.1}\">{}</text></g>",
+        self.options.id(uuid),
+        node_width(uuid),
+        x + node_width(uuid) / 2.0,
+        y + NODE_HEIGHT / 2.0,
+        Escape(&labels[uuid]),
+      )?;
+      if link.is_some() {
+        write!(f, "</a>")?;
+      }
+      writeln!(f)?;
+    }
+
+    writeln!(f, "</svg>")?;
+    writeln!(f, "<script>")?;
+    writeln!(f, "{SCRIPT}")?;
+    writeln!(f, "</script>")?;
+    writeln!(f, "</body>")?;
+    writeln!(f, "</html>")
+  }
+}
+
+// escape text for HTML content and attributes
+pub(super) struct Escape<'a>(pub(super) &'a str);
+
+impl Display for Escape<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    for c in self.0.chars() {
+      match c {
+        '&' => f.write_str("&amp;")?,
+        '<' => f.write_str("&lt;")?,
+        '>' => f.write_str("&gt;")?,
+        '"' => f.write_str("&quot;")?,
+        c => write!(f, "{c}")?,
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{
+    SeedableRng,
+    rngs::StdRng,
+  };
+
+  use super::*;
+
+  #[test]
+  fn test_rows() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let (a, _) = graph.add_node("A");
+    let (b, _) = graph.add_node("B");
+    let (c, _) = graph.add_node("C");
+    graph.add_child(root, a).unwrap();
+    graph.add_child(root, c).unwrap();
+    graph.add_child(a, b).unwrap();
+    // c is below b, the longest path from the root
+    graph.add_child(b, c).unwrap();
+    assert_eq!(graph.rows(), vec![vec![root], vec![a], vec![b], vec![c]]);
+  }
+
+  #[test]
+  fn test_html() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut graph = AcyclicGraph::new("<Test & Graph>");
+    let (parent_uuid, _) = graph.add_node_with_rng("Parent", &mut rng);
+    let (child_uuid, _) = graph.add_node_with_rng("\"Child\"", &mut rng);
+    graph.add_child(parent_uuid, child_uuid).unwrap();
+    let options = RenderOptions {
+      link: Some("https://example.com/?id={id}&x=1".parse().unwrap()),
+      ..Default::default()
+    };
+    let html = graph.html_with(options).to_string();
+
+    assert!(html.starts_with("<!DOCTYPE html>\n"));
+    assert!(html.contains("<title>&lt;Test &amp; Graph&gt;</title>"));
+    assert!(html.contains(">&quot;Child&quot;</text>"));
+    assert!(html.contains("<a href=\"https://example.com/?id=m43pF1xXxnZvhCY1VeAnMV&amp;x=1\">"));
+    assert_eq!(html.matches("<line ").count(), 1);
+    assert_eq!(html.matches("<rect ").count(), 2);
+    // no external resource
+    assert!(!html.contains("src="));
+  }
+}
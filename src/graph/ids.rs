@@ -0,0 +1,128 @@
+use std::{
+  collections::HashMap,
+  fmt::{
+    self,
+    Display,
+    Formatter,
+  },
+};
+
+use short_uuid::ShortUuid;
+use uuid::Uuid;
+
+use super::{
+  AcyclicGraph,
+  RenderOptions,
+  sanitize::LineEscape,
+};
+
+/// Identifier of an exported node, its short UUID or its sequential ID
+pub(super) enum NodeId {
+  Short(ShortUuid),
+  Sequential(usize),
+}
+
+impl Display for NodeId {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self {
+      NodeId::Short(short) => write!(f, "{short}"),
+      NodeId::Sequential(id) => write!(f, "n{id:04}"),
+    }
+  }
+}
+
+impl AcyclicGraph {
+  /// Numbers from 1 by UUID order, the METIS and DIMACS numbers, exported as
+  /// `n0001`, `n0002`... with [`RenderOptions::ids`]
+  pub fn sequential_ids(&self) -> HashMap<Uuid, usize> {
+    let (_, indexes) = self.indexes();
+    indexes
+      .into_iter()
+      .map(|(uuid, index)| (uuid, index + 1))
+      .collect()
+  }
+
+  /// One tab separated `id uuid label` line per node, by UUID order, to find
+  /// the node of an exported ID
+  pub fn id_mapping(&self, options: RenderOptions) -> IdMapping<'_> {
+    IdMapping {
+      graph: self,
+      options,
+    }
+  }
+}
+
+impl RenderOptions {
+  // short UUID of the nodes without sequential ID
+  pub(super) fn id(&self, uuid: &Uuid) -> NodeId {
+    match self.ids.as_ref().and_then(|ids| ids.get(uuid)) {
+      Some(&id) => NodeId::Sequential(id),
+      None => NodeId::Short(ShortUuid::from_uuid(uuid)),
+    }
+  }
+}
+
+pub struct IdMapping<'a> {
+  graph: &'a AcyclicGraph,
+  options: RenderOptions,
+}
+
+impl Display for IdMapping<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let depths = self.options.depths(self.graph);
+    let (uuids, _) = self.graph.indexes();
+    for uuid in &uuids {
+      let label = self
+        .options
+        .label(*uuid, &self.graph.nodes[uuid], depths.as_ref())
+        .unwrap_or_default();
+      writeln!(
+        f,
+        "{}\t{uuid}\t{}",
+        self.options.id(uuid),
+        LineEscape(&label)
+      )?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{
+    SeedableRng,
+    rngs::StdRng,
+  };
+
+  use super::*;
+
+  #[test]
+  fn test_sequential_ids() {
+    // sorted by UUID: child, parent
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (parent, _) = graph.add_node_with_rng("Parent", &mut rng);
+    let (child, _) = graph.add_node_with_rng("Child", &mut rng);
+    graph.add_child(parent, child).unwrap();
+    let options = RenderOptions {
+      ids: Some(graph.sequential_ids()),
+      ..Default::default()
+    };
+
+    assert_eq!(
+      graph.dot_with(options.clone()).to_string(),
+      "digraph \"Test Graph\" {\n  node [shape = box]\n  graph [rankdir = TB]\n\n  \"n0001\" \
+       [label = \"Child\"];\n  \"n0002\" [label = \"Parent\"];\n  \"n0002\" -> {\"n0001\"};\n}\n"
+    );
+    assert!(
+      graph
+        .mermaid_with(options.clone())
+        .to_string()
+        .ends_with("  n0001[Child]\n  n0002[Parent] --> n0001\n")
+    );
+    assert_eq!(
+      graph.id_mapping(options).to_string(),
+      format!("n0001\t{child}\tChild\nn0002\t{parent}\tParent\n")
+    );
+  }
+}
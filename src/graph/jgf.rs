@@ -0,0 +1,144 @@
+use std::fmt::{
+  self,
+  Display,
+  Formatter,
+};
+
+use itertools::Itertools;
+use serde_json::{
+  Map,
+  Value,
+  json,
+};
+
+use super::{
+  AcyclicGraph,
+  RenderOptions,
+};
+
+impl AcyclicGraph {
+  /// JSON Graph Format, <https://jsongraphformat.info>, nodes are keyed by
+  /// short UUID or sequential ID and their UUID, data, level and timestamp
+  /// are kept in the metadata
+  pub fn jgf_with(&self, options: RenderOptions) -> Jgf<'_> {
+    Jgf {
+      graph: self,
+      options,
+    }
+  }
+}
+
+pub struct Jgf<'a> {
+  graph: &'a AcyclicGraph,
+  options: RenderOptions,
+}
+
+impl Jgf<'_> {
+  fn value(&self) -> Value {
+    let depths = self.options.depths(self.graph);
+
+    let nodes: Map<String, Value> = self
+      .graph
+      .nodes
+      .iter()
+      .map(|(&uuid, node)| {
+        let mut metadata = Map::from_iter([("uuid".to_string(), json!(uuid.to_string()))]);
+        if !self.options.omit_data {
+          metadata.extend(node.data.to_json().map(|data| ("data".to_string(), data)));
+        }
+        metadata.extend(node.level.map(|level| ("level".to_string(), json!(level))));
+        metadata.extend(
+          node
+            .timestamp
+            .map(|timestamp| ("timestamp".to_string(), json!(timestamp.to_string()))),
+        );
+
+        let mut object = Map::new();
+        if let Some(label) = self.options.label(uuid, node, depths.as_ref()) {
+          object.insert("label".to_string(), json!(label));
+        }
+        object.insert("metadata".to_string(), Value::Object(metadata));
+        (self.options.id(&uuid).to_string(), Value::Object(object))
+      })
+      .collect();
+
+    let edges: Vec<Value> = self
+      .graph
+      .nodes
+      .iter()
+      .flat_map(|(parent, node)| node.childs.iter().map(move |child| (parent, child)))
+      .sorted()
+      .map(|(parent, child)| {
+        let mut edge = json!({
+          "source": self.options.id(parent).to_string(),
+          "target": self.options.id(child).to_string(),
+        });
+        if let Some(data) = self.graph.edge_data(*parent, *child) {
+          edge["metadata"] = Value::Object(data.clone());
+        }
+        edge
+      })
+      .collect();
+
+    json!({
+      "graph": {
+        "label": self.options.name(self.graph),
+        "directed": true,
+        "nodes": nodes,
+        "edges": edges,
+      }
+    })
+  }
+}
+
+impl Display for Jgf<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    // object keys are sorted so the output is deterministic
+    writeln!(f, "{:#}", self.value())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{
+    SeedableRng,
+    rngs::StdRng,
+  };
+
+  use super::*;
+
+  #[test]
+  fn test_jgf() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (parent_uuid, _) = graph.add_node_with_rng("Parent", &mut rng);
+    let (child_uuid, _) = graph.add_node_with_rng(json!({ "name": "Child", "age": 7 }), &mut rng);
+    graph.add_child(parent_uuid, child_uuid).unwrap();
+    graph.get_node_mut(parent_uuid).unwrap().set_level(0);
+
+    let jgf: Value =
+      serde_json::from_str(&graph.jgf_with(RenderOptions::default()).to_string()).unwrap();
+    pretty_assertions::assert_eq!(
+      jgf,
+      json!({
+        "graph": {
+          "label": "Test Graph",
+          "directed": true,
+          "nodes": {
+            "cDe6M3HmMtiJnhL4ihtnyx": {
+              "label": "Child",
+              "metadata": { "uuid": child_uuid.to_string(), "data": { "name": "Child", "age": 7 } },
+            },
+            "m43pF1xXxnZvhCY1VeAnMV": {
+              "label": "Parent",
+              "metadata": { "uuid": parent_uuid.to_string(), "data": "Parent", "level": 0 },
+            },
+          },
+          "edges": [
+            { "source": "m43pF1xXxnZvhCY1VeAnMV", "target": "cDe6M3HmMtiJnhL4ihtnyx" },
+          ],
+        }
+      })
+    );
+  }
+}
@@ -0,0 +1,109 @@
+use std::fmt::{
+  self,
+  Display,
+  Formatter,
+};
+
+use itertools::Itertools;
+
+use super::{
+  AcyclicGraph,
+  RenderOptions,
+  sanitize::LineEscape,
+};
+
+impl AcyclicGraph {
+  /// METIS graph format, nodes are numbered from 1 by UUID order and every
+  /// edge is listed for both of its nodes as METIS graphs are undirected, see
+  /// [`Metis::mapping`] for the numbers
+  pub fn metis_with(&self, options: RenderOptions) -> Metis<'_> {
+    Metis {
+      graph: self,
+      options,
+    }
+  }
+}
+
+pub struct Metis<'a> {
+  graph: &'a AcyclicGraph,
+  options: RenderOptions,
+}
+
+impl Metis<'_> {
+  /// One tab separated `number uuid label` line per node
+  pub fn mapping(&self) -> MetisMapping<'_> {
+    MetisMapping(self)
+  }
+}
+
+impl Display for Metis<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let (uuids, indexes) = self.graph.indexes();
+    let parents = self.graph.parents();
+    let edges: usize = self
+      .graph
+      .nodes
+      .values()
+      .map(|node| node.childs.len())
+      .sum();
+
+    writeln!(f, "{} {edges}", uuids.len())?;
+    for uuid in &uuids {
+      let neighbors = self.graph.nodes[uuid]
+        .childs
+        .iter()
+        .chain(parents.get(uuid).into_iter().flatten())
+        .map(|neighbor| indexes[neighbor] + 1)
+        .sorted();
+      writeln!(f, "{}", neighbors.format(" "))?;
+    }
+    Ok(())
+  }
+}
+
+pub struct MetisMapping<'a>(&'a Metis<'a>);
+
+impl Display for MetisMapping<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let Metis { graph, options } = self.0;
+    let depths = options.depths(graph);
+    let (uuids, _) = graph.indexes();
+    for (i, uuid) in uuids.iter().enumerate() {
+      let label = options
+        .label(*uuid, &graph.nodes[uuid], depths.as_ref())
+        .unwrap_or_default();
+      writeln!(f, "{}\t{uuid}\t{}", i + 1, LineEscape(&label))?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{
+    SeedableRng,
+    rngs::StdRng,
+  };
+
+  use super::*;
+
+  #[test]
+  fn test_metis() {
+    // sorted by UUID: child, parent, other
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (parent_uuid, _) = graph.add_node_with_rng("Parent", &mut rng);
+    let (child_uuid, _) = graph.add_node_with_rng("Child", &mut rng);
+    let (other_uuid, _) = graph.add_node_with_rng(42, &mut rng);
+    graph.add_child(parent_uuid, child_uuid).unwrap();
+    graph.add_child(parent_uuid, other_uuid).unwrap();
+    graph.add_child(child_uuid, other_uuid).unwrap();
+
+    let metis = graph.metis_with(RenderOptions::default());
+    pretty_assertions::assert_eq!(metis.to_string(), "3 3\n2 3\n1 3\n1 2\n");
+    pretty_assertions::assert_eq!(
+      metis.mapping().to_string(),
+      format!("1\t{child_uuid}\tChild\n2\t{parent_uuid}\tParent\n3\t{other_uuid}\t42\n")
+    );
+  }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use rand::RngCore;
+use uuid::Uuid;
+
+use super::{
+  AcyclicGraph,
+  Error,
+  Node,
+  NodeData,
+};
+
+/// Index of the node names in unique names mode, nodes without a name aren't
+/// indexed
+#[derive(Debug, Clone, Default)]
+pub(super) struct Names(Option<HashMap<String, Uuid>>);
+
+impl Names {
+  pub(super) fn check(&self, name: Option<&str>) -> Result<(), Error> {
+    match (&self.0, name) {
+      (Some(index), Some(name)) => match index.get(name) {
+        Some(&uuid) => Err(Error::NameAlreadyExists {
+          name: name.to_string(),
+          uuid,
+        }),
+        None => Ok(()),
+      },
+      _ => Ok(()),
+    }
+  }
+
+  pub(super) fn insert(&mut self, uuid: Uuid, name: Option<&str>) {
+    if let (Some(index), Some(name)) = (&mut self.0, name) {
+      index.insert(name.to_string(), uuid);
+    }
+  }
+
+  pub(super) fn remove(&mut self, name: Option<&str>) {
+    if let (Some(index), Some(name)) = (&mut self.0, name) {
+      index.remove(name);
+    }
+  }
+}
+
+impl AcyclicGraph {
+  /// Require the node names to be unique from now on, fail if two nodes
+  /// already share a name. Formats keyed by label need it.
+  pub fn enable_unique_names(&mut self) -> Result<(), Error> {
+    let mut index = HashMap::with_capacity(self.nodes.len());
+    for (&uuid, node) in &self.nodes {
+      if let Some(name) = node.name()
+        && let Some(&other) = index.get(name)
+      {
+        // report the same node whatever the iteration order
+        return Err(Error::NameAlreadyExists {
+          name: name.to_string(),
+          uuid: uuid.min(other),
+        });
+      }
+      if let Some(name) = node.name() {
+        index.insert(name.to_string(), uuid);
+      }
+    }
+    self.names = Names(Some(index));
+    Ok(())
+  }
+
+  pub fn unique_names(&self) -> bool {
+    self.names.0.is_some()
+  }
+
+  /// The node with this name, with an index in unique names mode otherwise
+  /// the first one by UUID
+  pub fn find_by_name(&self, name: &str) -> Option<Uuid> {
+    match &self.names.0 {
+      Some(index) => index.get(name).copied(),
+      None => self
+        .nodes
+        .iter()
+        .filter(|(_, node)| node.name() == Some(name))
+        .map(|(uuid, _)| *uuid)
+        .min(),
+    }
+  }
+
+  pub fn try_add_node_with_rng(
+    &mut self, data: impl Into<NodeData>, rng: &mut impl RngCore,
+  ) -> Result<(Uuid, &Node), Error> {
+    let uuid = self.uuids.next_with_rng(rng);
+    self.add_node_with_uuid(uuid, data)
+  }
+
+  pub fn try_add_node(&mut self, data: impl Into<NodeData>) -> Result<(Uuid, &Node), Error> {
+    let uuid = self.uuids.next();
+    self.add_node_with_uuid(uuid, data)
+  }
+
+  /// Rename a node, see [`Node::set_name`], keeping the index of unique
+  /// names mode up to date
+  pub fn set_node_name(&mut self, uuid: Uuid, name: impl Into<String>) -> Result<(), Error> {
+    let name = name.into();
+    let old = self.get_node(uuid)?.name().map(str::to_string);
+    if old.as_ref() == Some(&name) {
+      return Ok(());
+    }
+    self.names.check(Some(&name))?;
+    self.names.remove(old.as_deref());
+    self.names.insert(uuid, Some(&name));
+    self.get_node_mut(uuid)?.set_name(name);
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_unique_names() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (a, _) = graph.add_node("A");
+    graph.add_node(42);
+    graph.enable_unique_names().unwrap();
+    assert!(graph.unique_names());
+    assert_eq!(graph.find_by_name("A"), Some(a));
+
+    assert_eq!(
+      graph.try_add_node("A").unwrap_err(),
+      Error::NameAlreadyExists {
+        name: "A".to_string(),
+        uuid: a,
+      }
+    );
+    // nodes without name are never in conflict
+    graph.try_add_node(42).unwrap();
+    let (b, _) = graph.try_add_node("B").unwrap();
+
+    assert_eq!(
+      graph.set_node_name(b, "A"),
+      Err(Error::NameAlreadyExists {
+        name: "A".to_string(),
+        uuid: a,
+      })
+    );
+    graph.set_node_name(a, "C").unwrap();
+    assert_eq!(graph.find_by_name("A"), None);
+    assert_eq!(graph.find_by_name("C"), Some(a));
+    graph.set_node_name(b, "A").unwrap();
+    assert_eq!(graph.find_by_name("A"), Some(b));
+
+    graph.remove_node(b).unwrap();
+    assert_eq!(graph.find_by_name("A"), None);
+    graph.try_add_node("A").unwrap();
+  }
+
+  #[test]
+  fn test_unique_names_undo() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    graph.enable_unique_names().unwrap();
+    let (a, _) = graph.add_node("A");
+    let snapshot = graph.snapshot();
+    graph.set_node_name(a, "B").unwrap();
+    graph.remove_node(a).unwrap();
+    let (c, _) = graph.add_node("C");
+
+    graph.restore(snapshot).unwrap();
+    assert_eq!(graph.find_by_name("A"), Some(a));
+    assert_eq!(graph.find_by_name("B"), None);
+    assert_eq!(graph.find_by_name("C"), None);
+    assert_eq!(graph.get_node(c), Err(Error::UuidNotFound { uuid: c }));
+  }
+
+  #[test]
+  fn test_enable_unique_names() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (a, _) = graph.add_node("A");
+    let (b, _) = graph.add_node("A");
+    assert_eq!(graph.find_by_name("A"), Some(a.min(b)));
+    assert_eq!(
+      graph.enable_unique_names(),
+      Err(Error::NameAlreadyExists {
+        name: "A".to_string(),
+        uuid: a.min(b),
+      })
+    );
+    assert!(!graph.unique_names());
+  }
+}
@@ -0,0 +1,285 @@
+use std::{
+  collections::{
+    BTreeMap,
+    btree_map,
+  },
+  fmt,
+  ops::Index,
+};
+
+use uuid::Uuid;
+
+use super::Node;
+
+/// The nodes of a graph, see [`AcyclicGraph::nodes`]. Each node gets a
+/// compact u32 ID, the nodes are stored in a vector indexed by ID and the
+/// UUIDs are kept sorted with their ID, so the nodes are always visited by
+/// UUID order without sorting them and the other indexes of the graph are
+/// vectors of u32 rather than maps keyed by UUID.
+///
+/// The UUID is stored a second time beside its node, 16 bytes per node, so
+/// the topological order gets the UUID of an ID without a reverse map. The
+/// childs of a [`Node`] stay UUIDs rather than IDs: a node is read, written
+/// and compared alone, apart from the IDs of its graph.
+///
+/// [`AcyclicGraph::nodes`]: super::AcyclicGraph::nodes
+#[derive(Clone, Default)]
+pub struct NodeMap {
+  ids: BTreeMap<Uuid, u32>,
+  // the node of each ID, None once removed until its ID is reused
+  slots: Vec<Option<(Uuid, Node)>>,
+  free: Vec<u32>,
+}
+
+impl NodeMap {
+  pub fn len(&self) -> usize {
+    self.ids.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.ids.is_empty()
+  }
+
+  pub fn contains_key(&self, uuid: &Uuid) -> bool {
+    self.ids.contains_key(uuid)
+  }
+
+  pub fn get(&self, uuid: &Uuid) -> Option<&Node> {
+    let &id = self.ids.get(uuid)?;
+    Some(self.node(id))
+  }
+
+  pub(super) fn get_mut(&mut self, uuid: &Uuid) -> Option<&mut Node> {
+    let &id = self.ids.get(uuid)?;
+    self.slots[id as usize].as_mut().map(|(_, node)| node)
+  }
+
+  /// The nodes by UUID order
+  pub fn iter(&self) -> Iter<'_> {
+    Iter {
+      ids: self.ids.iter(),
+      nodes: self,
+    }
+  }
+
+  /// The UUIDs in increasing order
+  pub fn keys(&self) -> btree_map::Keys<'_, Uuid, u32> {
+    self.ids.keys()
+  }
+
+  /// The nodes by UUID order
+  pub fn values(&self) -> impl DoubleEndedIterator<Item = &Node> + ExactSizeIterator + Clone {
+    self.iter().map(|(_, node)| node)
+  }
+
+  /// Replace the node of an UUID already used, else give it an ID
+  pub(super) fn insert(&mut self, uuid: Uuid, node: Node) -> Option<Node> {
+    if let Some(&id) = self.ids.get(&uuid) {
+      return Some(std::mem::replace(self.node_mut(id), node));
+    }
+    let id = match self.free.pop() {
+      Some(id) => {
+        self.slots[id as usize] = Some((uuid, node));
+        id
+      }
+      None => {
+        self.slots.push(Some((uuid, node)));
+        (self.slots.len() - 1) as u32
+      }
+    };
+    self.ids.insert(uuid, id);
+    None
+  }
+
+  /// The ID of a removed node is given to the next inserted one
+  pub(super) fn remove(&mut self, uuid: &Uuid) -> Option<Node> {
+    let id = self.ids.remove(uuid)?;
+    self.free.push(id);
+    self.slots[id as usize].take().map(|(_, node)| node)
+  }
+
+  /// The ID of a node
+  pub(super) fn id(&self, uuid: &Uuid) -> Option<u32> {
+    self.ids.get(uuid).copied()
+  }
+
+  /// One more than the highest ID, the length of a vector indexed by ID
+  pub(super) fn id_bound(&self) -> usize {
+    self.slots.len()
+  }
+
+  /// The UUID of the node of an ID, the ID is used
+  pub(super) fn uuid(&self, id: u32) -> Uuid {
+    self.entry(id).0
+  }
+
+  /// The node of an ID, the ID is used
+  pub(super) fn node(&self, id: u32) -> &Node {
+    &self.entry(id).1
+  }
+
+  fn node_mut(&mut self, id: u32) -> &mut Node {
+    match &mut self.slots[id as usize] {
+      Some((_, node)) => node,
+      None => panic!("node ID {id} is free"),
+    }
+  }
+
+  fn entry(&self, id: u32) -> &(Uuid, Node) {
+    match &self.slots[id as usize] {
+      Some(entry) => entry,
+      None => panic!("node ID {id} is free"),
+    }
+  }
+}
+
+impl fmt::Debug for NodeMap {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    f.debug_map().entries(self.iter()).finish()
+  }
+}
+
+/// Equal with the same nodes by UUID, whatever their IDs
+impl PartialEq for NodeMap {
+  fn eq(&self, other: &Self) -> bool {
+    self.len() == other.len() && self.iter().eq(other.iter())
+  }
+}
+
+impl Eq for NodeMap {}
+
+impl Index<&Uuid> for NodeMap {
+  type Output = Node;
+
+  fn index(&self, uuid: &Uuid) -> &Node {
+    match self.get(uuid) {
+      Some(node) => node,
+      None => panic!("UUID not found: {uuid}"),
+    }
+  }
+}
+
+impl<'a> IntoIterator for &'a NodeMap {
+  type IntoIter = Iter<'a>;
+  type Item = (&'a Uuid, &'a Node);
+
+  fn into_iter(self) -> Iter<'a> {
+    self.iter()
+  }
+}
+
+/// The nodes of a [`NodeMap`] by UUID order
+#[derive(Clone)]
+pub struct Iter<'a> {
+  ids: btree_map::Iter<'a, Uuid, u32>,
+  nodes: &'a NodeMap,
+}
+
+impl<'a> Iterator for Iter<'a> {
+  type Item = (&'a Uuid, &'a Node);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let (uuid, &id) = self.ids.next()?;
+    Some((uuid, self.nodes.node(id)))
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.ids.size_hint()
+  }
+}
+
+impl DoubleEndedIterator for Iter<'_> {
+  fn next_back(&mut self) -> Option<Self::Item> {
+    let (uuid, &id) = self.ids.next_back()?;
+    Some((uuid, self.nodes.node(id)))
+  }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+impl IntoIterator for NodeMap {
+  type IntoIter = IntoIter;
+  type Item = (Uuid, Node);
+
+  /// The nodes by UUID order
+  fn into_iter(self) -> IntoIter {
+    IntoIter {
+      ids: self.ids.into_iter(),
+      slots: self.slots,
+    }
+  }
+}
+
+/// The owned nodes of a [`NodeMap`] by UUID order
+pub struct IntoIter {
+  ids: btree_map::IntoIter<Uuid, u32>,
+  slots: Vec<Option<(Uuid, Node)>>,
+}
+
+impl Iterator for IntoIter {
+  type Item = (Uuid, Node);
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let (_, id) = self.ids.next()?;
+    self.slots[id as usize].take()
+  }
+
+  fn size_hint(&self) -> (usize, Option<usize>) {
+    self.ids.size_hint()
+  }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+impl FromIterator<(Uuid, Node)> for NodeMap {
+  fn from_iter<I: IntoIterator<Item = (Uuid, Node)>>(nodes: I) -> Self {
+    let mut map = NodeMap::default();
+    for (uuid, node) in nodes {
+      map.insert(uuid, node);
+    }
+    map
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_node_map() {
+    let uuids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+    let mut nodes: NodeMap = uuids
+      .iter()
+      .enumerate()
+      .map(|(i, &uuid)| (uuid, Node::new(i as u64)))
+      .collect();
+    let mut sorted = uuids.clone();
+    sorted.sort();
+    assert_eq!(nodes.keys().copied().collect::<Vec<_>>(), sorted);
+    assert_eq!(nodes.len(), 4);
+    assert_eq!(nodes[&uuids[2]], Node::new(2));
+    assert_eq!(nodes.uuid(nodes.id(&uuids[1]).unwrap()), uuids[1]);
+
+    // the ID of a removed node is reused
+    let id = nodes.id(&uuids[0]).unwrap();
+    assert_eq!(nodes.remove(&uuids[0]), Some(Node::new(0)));
+    assert_eq!(nodes.remove(&uuids[0]), None);
+    assert!(!nodes.contains_key(&uuids[0]));
+    let other = Uuid::new_v4();
+    assert_eq!(nodes.insert(other, Node::new(5)), None);
+    assert_eq!(nodes.id(&other), Some(id));
+    assert_eq!(nodes.id_bound(), 4);
+    assert_eq!(nodes.insert(other, Node::new(6)), Some(Node::new(5)));
+
+    // equal whatever the IDs
+    let rebuilt: NodeMap = nodes
+      .iter()
+      .rev()
+      .map(|(&uuid, node)| (uuid, node.clone()))
+      .collect();
+    assert_eq!(rebuilt, nodes);
+    nodes.remove(&other);
+    assert_eq!(nodes.len(), 3);
+    assert_ne!(rebuilt, nodes);
+  }
+}
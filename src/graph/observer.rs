@@ -0,0 +1,181 @@
+use std::{
+  fmt::{
+    self,
+    Debug,
+    Formatter,
+  },
+  sync::{
+    Arc,
+    Mutex,
+    PoisonError,
+  },
+};
+
+use uuid::Uuid;
+
+use super::{
+  AcyclicGraph,
+  Node,
+};
+
+/// Notified of the changes of a graph, to maintain derived indexes as the
+/// graph is built. Changes of a node through `get_node_mut` aren't reported.
+pub trait Observer {
+  fn on_node_added(&mut self, _uuid: Uuid, _node: &Node) {}
+
+  fn on_edge_added(&mut self, _parent: Uuid, _child: Uuid) {}
+
+  fn on_removed(&mut self, _removed: Removed<'_>) {}
+}
+
+/// Removed part of the graph, the edges to a removed node are reported
+/// before the node itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Removed<'a> {
+  Node { uuid: Uuid, node: &'a Node },
+  Edge { parent: Uuid, child: Uuid },
+}
+
+/// Keep a handle on the observer to read it while the graph use it
+impl<T: Observer> Observer for Arc<Mutex<T>> {
+  fn on_node_added(&mut self, uuid: Uuid, node: &Node) {
+    lock(self).on_node_added(uuid, node);
+  }
+
+  fn on_edge_added(&mut self, parent: Uuid, child: Uuid) {
+    lock(self).on_edge_added(parent, child);
+  }
+
+  fn on_removed(&mut self, removed: Removed<'_>) {
+    lock(self).on_removed(removed);
+  }
+}
+
+fn lock<T>(observer: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+  observer.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Observers of a graph, a clone of the graph starts without observer as
+/// they describe the original
+#[derive(Default)]
+pub(super) struct Observers(Vec<Box<dyn Observer + Send + Sync>>);
+
+impl Observers {
+  pub(super) fn node_added(&mut self, uuid: Uuid, node: &Node) {
+    for observer in &mut self.0 {
+      observer.on_node_added(uuid, node);
+    }
+  }
+
+  pub(super) fn edge_added(&mut self, parent: Uuid, child: Uuid) {
+    for observer in &mut self.0 {
+      observer.on_edge_added(parent, child);
+    }
+  }
+
+  pub(super) fn removed(&mut self, removed: Removed<'_>) {
+    for observer in &mut self.0 {
+      observer.on_removed(removed);
+    }
+  }
+}
+
+impl Clone for Observers {
+  fn clone(&self) -> Self {
+    Self::default()
+  }
+}
+
+impl Debug for Observers {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "Observers({})", self.0.len())
+  }
+}
+
+impl AcyclicGraph {
+  /// Notify the observer of every following change, the existing nodes and
+  /// edges aren't reported
+  pub fn observe(&mut self, observer: impl Observer + Send + Sync + 'static) {
+    self.observers.0.push(Box::new(observer));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use super::*;
+
+  // name lookup and number of parents of each node
+  #[derive(Default)]
+  struct Index {
+    names: HashMap<String, Uuid>,
+    parents: HashMap<Uuid, usize>,
+  }
+
+  impl Observer for Index {
+    fn on_node_added(&mut self, uuid: Uuid, node: &Node) {
+      if let Some(name) = node.name() {
+        self.names.insert(name.to_string(), uuid);
+      }
+    }
+
+    fn on_edge_added(&mut self, _parent: Uuid, child: Uuid) {
+      *self.parents.entry(child).or_default() += 1;
+    }
+
+    fn on_removed(&mut self, removed: Removed<'_>) {
+      match removed {
+        Removed::Node { uuid, node } => {
+          if let Some(name) = node.name() {
+            self.names.remove(name);
+          }
+          for child in node.childs() {
+            *self.parents.get_mut(child).unwrap() -= 1;
+          }
+          self.parents.remove(&uuid);
+        }
+        Removed::Edge { child, .. } => *self.parents.get_mut(&child).unwrap() -= 1,
+      }
+    }
+  }
+
+  #[test]
+  fn test_observer() {
+    let index = Arc::new(Mutex::new(Index::default()));
+    let mut graph = AcyclicGraph::new("Test Graph");
+    graph.observe(index.clone());
+
+    let (root, _) = graph.add_node("Root");
+    let (a, _) = graph.add_node("A");
+    let (b, _) = graph.add_node("B");
+    graph.add_child(root, a).unwrap();
+    graph.add_child(root, b).unwrap();
+    graph.add_child(a, b).unwrap();
+    {
+      let index = index.lock().unwrap();
+      assert_eq!(index.names["A"], a);
+      assert_eq!(index.parents[&b], 2);
+    }
+
+    graph.remove_child(root, b).unwrap();
+    assert_eq!(index.lock().unwrap().parents[&b], 1);
+    graph.remove_node(a).unwrap();
+    {
+      let index = index.lock().unwrap();
+      assert_eq!(index.names.get("A"), None);
+      assert_eq!(index.parents[&b], 0);
+      assert_eq!(index.names.len(), 2);
+    }
+
+    let snapshot = graph.snapshot();
+    graph.add_child(root, b).unwrap();
+    graph.restore(snapshot).unwrap();
+    assert_eq!(index.lock().unwrap().parents[&b], 0);
+
+    // a clone doesn't notify the observers of the original
+    let mut clone = graph.clone();
+    clone.add_node("C");
+    assert_eq!(index.lock().unwrap().names.get("C"), None);
+  }
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use uuid::Uuid;
+
+use super::AcyclicGraph;
+
+// down and up sweeps of the barycenter heuristic, the best order is kept
+const SWEEPS: usize = 4;
+
+impl AcyclicGraph {
+  /// Nodes grouped by their longest path from a root, the ranks of Graphviz,
+  /// each rank ordered to reduce the crossings of the edges between
+  /// consecutive ranks, see [`AcyclicGraph::crossings`]
+  pub fn rank_order(&self) -> Vec<Vec<Uuid>> {
+    let parents = self.parents();
+    let roots = self.nodes.keys().filter(|uuid| !parents.contains_key(uuid));
+    // roots come from the graph so they exist
+    let depths = self.longest_paths(roots.copied()).unwrap_or_default();
+    let mut ranks: Vec<Vec<Uuid>> = Vec::new();
+    for (uuid, depth) in depths.into_iter().sorted() {
+      if ranks.len() <= depth {
+        ranks.resize_with(depth + 1, Vec::new);
+      }
+      ranks[depth].push(uuid);
+    }
+
+    // each node is placed at the mean position of its neighbours on the rank
+    // swept from, the ones without neighbour there keep their position
+    let mut best = (self.crossings(&ranks), ranks.clone());
+    for _ in 0..SWEEPS {
+      for i in 1..ranks.len() {
+        let positions = positions(&ranks[i - 1]);
+        sort_by_barycenter(&mut ranks[i], |uuid| {
+          parents
+            .get(uuid)
+            .into_iter()
+            .flatten()
+            .filter_map(|parent| positions.get(parent).copied())
+            .collect()
+        });
+      }
+      for i in (0..ranks.len().saturating_sub(1)).rev() {
+        let positions = positions(&ranks[i + 1]);
+        sort_by_barycenter(&mut ranks[i], |uuid| {
+          self.nodes[uuid]
+            .childs
+            .iter()
+            .filter_map(|child| positions.get(child).copied())
+            .collect()
+        });
+      }
+      let crossings = self.crossings(&ranks);
+      if crossings < best.0 {
+        best = (crossings, ranks.clone());
+      }
+    }
+    best.1
+  }
+
+  /// Number of crossings of the edges between consecutive ranks drawn in this
+  /// order, the edges skipping a rank are not counted
+  pub fn crossings(&self, ranks: &[Vec<Uuid>]) -> usize {
+    ranks
+      .windows(2)
+      .map(|pair| {
+        let (above, below) = (positions(&pair[0]), positions(&pair[1]));
+        let edges: Vec<(usize, usize)> = pair[0]
+          .iter()
+          .flat_map(|uuid| {
+            self.nodes[uuid]
+              .childs
+              .iter()
+              .filter_map(|child| Some((above[uuid], *below.get(child)?)))
+          })
+          .sorted()
+          .collect();
+        // two edges cross when the later parent has the earlier child, counted
+        // with a Fenwick tree of the child positions seen so far
+        let mut tree = vec![0; below.len() + 1];
+        let mut crossings = 0;
+        for (seen, &(_, child)) in edges.iter().enumerate() {
+          let mut i = child + 1;
+          let mut before = 0;
+          while i > 0 {
+            before += tree[i];
+            i &= i - 1;
+          }
+          crossings += seen - before;
+          let mut i = child + 1;
+          while i < tree.len() {
+            tree[i] += 1;
+            i += i & i.wrapping_neg();
+          }
+        }
+        crossings
+      })
+      .sum()
+  }
+}
+
+fn positions(rank: &[Uuid]) -> HashMap<Uuid, usize> {
+  rank
+    .iter()
+    .enumerate()
+    .map(|(i, &uuid)| (uuid, i))
+    .collect()
+}
+
+// stable so the ties keep their order
+fn sort_by_barycenter(rank: &mut [Uuid], neighbours: impl Fn(&Uuid) -> Vec<usize>) {
+  let keys: HashMap<Uuid, f64> = rank
+    .iter()
+    .enumerate()
+    .map(|(i, uuid)| {
+      let positions = neighbours(uuid);
+      let key = if positions.is_empty() {
+        i as f64
+      } else {
+        positions.iter().sum::<usize>() as f64 / positions.len() as f64
+      };
+      (*uuid, key)
+    })
+    .collect();
+  rank.sort_by(|a, b| keys[a].total_cmp(&keys[b]));
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::graph::RenderOptions;
+
+  #[test]
+  fn test_rank_order() {
+    // root -> a -> d, root -> b -> c, a -> e -> f
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let (a, _) = graph.add_node("A");
+    let (b, _) = graph.add_node("B");
+    let (c, _) = graph.add_node("C");
+    let (d, _) = graph.add_node("D");
+    let (e, _) = graph.add_node("E");
+    let (f, _) = graph.add_node("F");
+    for (parent, child) in [(root, a), (root, b), (a, d), (b, c), (a, e), (e, f)] {
+      graph.add_child(parent, child).unwrap();
+    }
+
+    assert_eq!(graph.crossings(&[vec![a, b], vec![c, d, e]]), 2);
+    assert_eq!(graph.crossings(&[vec![a, b], vec![d, e, c]]), 0);
+    let ranks = graph.rank_order();
+    assert_eq!(ranks.len(), 4);
+    assert_eq!(ranks[0], vec![root]);
+    assert_eq!(ranks[3], vec![f]);
+    assert_eq!(graph.crossings(&ranks), 0);
+
+    let dot = graph
+      .dot_with(RenderOptions {
+        rank_hints: true,
+        ..Default::default()
+      })
+      .to_string();
+    let id = |uuid: &Uuid| RenderOptions::default().id(uuid).to_string();
+    let hint = ranks[2]
+      .iter()
+      .map(|uuid| format!("\"{}\"", id(uuid)))
+      .join(" -> ");
+    assert!(dot.contains(&format!("  {{rank = same; {hint} [style = invis]}}\n")));
+    // nodes in rank order
+    let first = |uuid| dot.find(&format!("  \"{}\" [", id(uuid))).unwrap();
+    assert!(first(&root) < first(&a) && first(&a) < first(&f));
+  }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+
+use num_bigint::BigUint;
+use rand::Rng;
+use uuid::Uuid;
+
+use super::AcyclicGraph;
+
+impl AcyclicGraph {
+  // number of paths from each node to a leaf, computed from the leaves up
+  fn paths_to_leaves(&self) -> HashMap<Uuid, BigUint> {
+    let order: Vec<Uuid> = self.topological_generations().flatten().collect();
+    let mut counts: HashMap<Uuid, BigUint> = HashMap::with_capacity(order.len());
+    for uuid in order.into_iter().rev() {
+      let childs = &self.nodes[&uuid].childs;
+      let count = if childs.is_empty() {
+        BigUint::from(1u8)
+      } else {
+        childs.iter().map(|child| &counts[child]).sum()
+      };
+      counts.insert(uuid, count);
+    }
+    counts
+  }
+
+  /// Number of distinct paths from a root to a leaf, it grows exponentially
+  /// with the number of nodes having several parents
+  pub fn path_count(&self) -> BigUint {
+    let parents = self.parents();
+    self
+      .paths_to_leaves()
+      .into_iter()
+      .filter(|(uuid, _)| !parents.contains_key(uuid))
+      .map(|(_, count)| count)
+      .sum()
+  }
+
+  /// Number of distinct root to leaf paths going through each edge, keyed by
+  /// parent and child
+  pub fn edge_path_counts(&self) -> HashMap<(Uuid, Uuid), BigUint> {
+    let to_leaves = self.paths_to_leaves();
+    let mut from_roots: HashMap<Uuid, BigUint> = HashMap::with_capacity(to_leaves.len());
+    let mut counts = HashMap::new();
+    for uuid in self.topological_generations().flatten() {
+      let from_root = from_roots
+        .remove(&uuid)
+        .unwrap_or_else(|| BigUint::from(1u8));
+      for &child in &self.nodes[&uuid].childs {
+        counts.insert((uuid, child), &from_root * &to_leaves[&child]);
+        *from_roots.entry(child).or_default() += &from_root;
+      }
+    }
+    counts
+  }
+
+  /// Sample `n` root to leaf paths, every path has the same probability to be
+  /// picked. A path is picked by its rank among all paths, then the rank is
+  /// followed down using the number of paths under each node.
+  pub fn sample_paths(&self, n: usize, rng: &mut impl Rng) -> Vec<Vec<Uuid>> {
+    let counts = self.paths_to_leaves();
+    let parents = self.parents();
+    let mut roots: Vec<Uuid> = self
+      .nodes
+      .keys()
+      .filter(|uuid| !parents.contains_key(uuid))
+      .copied()
+      .collect();
+    roots.sort();
+    let total: BigUint = roots.iter().map(|root| &counts[root]).sum();
+    if total == BigUint::ZERO {
+      return Vec::new();
+    }
+
+    (0..n)
+      .map(|_| {
+        let mut rank = random_below(&total, rng);
+        let mut path = Vec::new();
+        let mut candidates = roots.clone();
+        let find = |candidates: Vec<Uuid>, rank: &mut BigUint| {
+          candidates.into_iter().find(|candidate| {
+            let count = &counts[candidate];
+            if *rank < *count {
+              true
+            } else {
+              *rank -= count;
+              false
+            }
+          })
+        };
+        while let Some(next) = find(candidates, &mut rank) {
+          path.push(next);
+          candidates = self.nodes[&next].childs.iter().copied().collect();
+          candidates.sort();
+        }
+        path
+      })
+      .collect()
+  }
+}
+
+// uniform in [0, max) by rejection, less than two draws on average
+fn random_below(max: &BigUint, rng: &mut impl Rng) -> BigUint {
+  let bits = max.bits();
+  let mut bytes = vec![0; bits.div_ceil(8) as usize];
+  loop {
+    rng.fill_bytes(&mut bytes);
+    if let Some(last) = bytes.last_mut() {
+      *last &= u8::MAX >> ((8 - bits % 8) % 8);
+    }
+    let value = BigUint::from_bytes_le(&bytes);
+    if value < *max {
+      return value;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{
+    SeedableRng,
+    rngs::StdRng,
+  };
+
+  use super::*;
+
+  #[test]
+  fn test_path_count() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    assert_eq!(graph.path_count(), BigUint::ZERO);
+
+    // a chain of diamonds doubles the paths at each step
+    let (mut top, _) = graph.add_node("Top");
+    for _ in 0..100 {
+      let (left, _) = graph.add_node("Left");
+      let (right, _) = graph.add_node("Right");
+      let (bottom, _) = graph.add_node("Bottom");
+      graph.add_child(top, left).unwrap();
+      graph.add_child(top, right).unwrap();
+      graph.add_child(left, bottom).unwrap();
+      graph.add_child(right, bottom).unwrap();
+      top = bottom;
+    }
+    assert_eq!(graph.path_count(), BigUint::from(1u8) << 100);
+
+    let (alone, _) = graph.add_node("Alone");
+    assert_eq!(graph.path_count(), (BigUint::from(1u8) << 100) + 1u8);
+    assert_eq!(graph.paths_to_leaves()[&alone], BigUint::from(1u8));
+  }
+
+  #[test]
+  fn test_edge_path_counts() {
+    // root -> a -> c, root -> b -> c, c -> d, c -> e
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let (a, _) = graph.add_node("A");
+    let (b, _) = graph.add_node("B");
+    let (c, _) = graph.add_node("C");
+    let (d, _) = graph.add_node("D");
+    let (e, _) = graph.add_node("E");
+    for (parent, child) in [(root, a), (root, b), (a, c), (b, c), (c, d), (c, e)] {
+      graph.add_child(parent, child).unwrap();
+    }
+
+    let counts = graph.edge_path_counts();
+    assert_eq!(counts.len(), 6);
+    assert_eq!(counts[&(root, a)], BigUint::from(2u8));
+    assert_eq!(counts[&(a, c)], BigUint::from(2u8));
+    assert_eq!(counts[&(c, d)], BigUint::from(2u8));
+    // every path goes through one edge of the root
+    assert_eq!(
+      &counts[&(root, a)] + &counts[&(root, b)],
+      graph.path_count()
+    );
+  }
+
+  #[test]
+  fn test_sample_paths() {
+    // root -> a -> c, root -> b -> c, root -> b -> d, e
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let mut rng = StdRng::seed_from_u64(42);
+    assert!(graph.sample_paths(3, &mut rng).is_empty());
+
+    let [root, a, b, c, d, e] =
+      ["Root", "A", "B", "C", "D", "E"].map(|name| graph.add_node(name).0);
+    for (parent, child) in [(root, a), (root, b), (a, c), (b, c), (b, d)] {
+      graph.add_child(parent, child).unwrap();
+    }
+
+    let paths = graph.sample_paths(4000, &mut rng);
+    assert_eq!(paths.len(), 4000);
+    let mut counts: HashMap<Vec<Uuid>, usize> = HashMap::new();
+    for path in paths {
+      *counts.entry(path).or_default() += 1;
+    }
+    let expected = [
+      vec![root, a, c],
+      vec![root, b, c],
+      vec![root, b, d],
+      vec![e],
+    ];
+    assert_eq!(counts.len(), expected.len());
+    for path in expected {
+      assert!((900..1100).contains(&counts[&path]), "{path:?} {counts:?}");
+    }
+  }
+
+  #[test]
+  fn test_random_below() {
+    let mut rng = StdRng::seed_from_u64(42);
+    for max in [1u32, 2, 7, 8, 255, 256, 257, 1000] {
+      let max = BigUint::from(max);
+      for _ in 0..100 {
+        assert!(random_below(&max, &mut rng) < max);
+      }
+    }
+  }
+}
@@ -0,0 +1,232 @@
+use std::collections::{
+  HashSet,
+  VecDeque,
+};
+
+use rand::RngCore;
+use uuid::Uuid;
+
+use super::{
+  AcyclicGraph,
+  Error,
+  Node,
+  NodeData,
+  uuids::Uuids,
+};
+
+/// A persistent version of [`AcyclicGraph`], every change return a new graph
+/// that share the unchanged nodes with the previous one, so keeping many
+/// versions to explore alternatives is cheap. Clones are O(1).
+#[derive(Debug, Clone)]
+pub struct PersistentGraph {
+  name: String,
+  nodes: im::HashMap<Uuid, Node>,
+  uuids: Uuids,
+}
+
+impl PersistentGraph {
+  pub fn new(name: impl Into<String>) -> Self {
+    Self {
+      name: name.into(),
+      nodes: im::HashMap::new(),
+      uuids: Uuids::default(),
+    }
+  }
+
+  pub fn name(&self) -> &str {
+    &self.name
+  }
+
+  pub fn nodes(&self) -> &im::HashMap<Uuid, Node> {
+    &self.nodes
+  }
+
+  pub fn get_node(&self, uuid: Uuid) -> Result<&Node, Error> {
+    self.nodes.get(&uuid).ok_or(Error::UuidNotFound { uuid })
+  }
+
+  /// See [`AcyclicGraph::add_node_with_uuid`]
+  pub fn add_node_with_uuid(
+    &self, uuid: Uuid, data: impl Into<NodeData>,
+  ) -> Result<(Self, Uuid), Error> {
+    let mut graph = self.clone();
+    graph.insert(uuid, data)?;
+    Ok((graph, uuid))
+  }
+
+  /// The UUID follows the UUID version of the graph it was converted from
+  pub fn add_node_with_rng(
+    &self, data: impl Into<NodeData>, rng: &mut impl RngCore,
+  ) -> Result<(Self, Uuid), Error> {
+    let mut graph = self.clone();
+    let uuid = graph.uuids.next_with_rng(rng);
+    graph.insert(uuid, data)?;
+    Ok((graph, uuid))
+  }
+
+  pub fn add_node(&self, data: impl Into<NodeData>) -> Result<(Self, Uuid), Error> {
+    let mut graph = self.clone();
+    let uuid = graph.uuids.next();
+    graph.insert(uuid, data)?;
+    Ok((graph, uuid))
+  }
+
+  fn insert(&mut self, uuid: Uuid, data: impl Into<NodeData>) -> Result<(), Error> {
+    if self.nodes.contains_key(&uuid) {
+      return Err(Error::UuidCollision { uuid });
+    }
+    self.nodes.insert(uuid, Node::new(data));
+    Ok(())
+  }
+
+  fn check_cycle(&self, parent: Uuid, child: Uuid) -> Result<(), Error> {
+    let mut queue = VecDeque::from([child]);
+    let mut queued = HashSet::from([child]);
+
+    while let Some(current) = queue.pop_front() {
+      if current == parent {
+        return Err(Error::Cycle {
+          src: parent,
+          dst: child,
+        });
+      }
+
+      for &child in &self.get_node(current)?.childs {
+        if queued.insert(child) {
+          queue.push_back(child);
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  pub fn add_child(&self, parent: Uuid, child: Uuid) -> Result<Self, Error> {
+    self.check_cycle(parent, child)?;
+    let mut graph = self.clone();
+    // only the parent is copied, the other nodes stay shared
+    let node = graph
+      .nodes
+      .get_mut(&parent)
+      .ok_or(Error::UuidNotFound { uuid: parent })?;
+    if node.childs.insert(child) {
+      Ok(graph)
+    } else {
+      Err(Error::ChildAlreadyExist { parent, child })
+    }
+  }
+
+  pub fn remove_child(&self, parent: Uuid, child: Uuid) -> Result<Self, Error> {
+    let mut graph = self.clone();
+    let node = graph
+      .nodes
+      .get_mut(&parent)
+      .ok_or(Error::UuidNotFound { uuid: parent })?;
+    if node.childs.remove(&child) {
+      Ok(graph)
+    } else {
+      Err(Error::ChildNotFound { parent, child })
+    }
+  }
+}
+
+/// The edge data is not kept
+impl From<&AcyclicGraph> for PersistentGraph {
+  fn from(graph: &AcyclicGraph) -> Self {
+    Self {
+      name: graph.name.clone(),
+      nodes: graph
+        .nodes
+        .iter()
+        .map(|(uuid, node)| (*uuid, node.clone()))
+        .collect(),
+      uuids: graph.uuids.clone(),
+    }
+  }
+}
+
+/// To use the queries and exporters of [`AcyclicGraph`]
+impl From<&PersistentGraph> for AcyclicGraph {
+  fn from(graph: &PersistentGraph) -> Self {
+    Self {
+      name: graph.name.clone(),
+      nodes: graph
+        .nodes
+        .iter()
+        .map(|(uuid, node)| (*uuid, node.clone()))
+        .collect(),
+      history: Default::default(),
+      observers: Default::default(),
+      names: Default::default(),
+      uuids: graph.uuids.clone(),
+      edge_data: Default::default(),
+      order: Default::default(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::graph::UuidVersion;
+
+  #[test]
+  fn test_branches() {
+    let (base, root) = PersistentGraph::new("Test Graph").add_node("Root").unwrap();
+    let (base, a) = base.add_node("A").unwrap();
+    let base = base.add_child(root, a).unwrap();
+
+    let (left, b) = base.add_node("B").unwrap();
+    let left = left.add_child(a, b).unwrap();
+    let (right, c) = base.add_node("C").unwrap();
+    let right = right.add_child(root, c).unwrap();
+
+    // each branch only see its own changes
+    assert_eq!(base.nodes().len(), 2);
+    assert_eq!(base.get_node(a).unwrap().childs().len(), 0);
+    assert_eq!(left.get_node(a).unwrap().childs(), &HashSet::from([b]));
+    assert_eq!(left.get_node(root).unwrap().childs(), &HashSet::from([a]));
+    assert_eq!(
+      right.get_node(root).unwrap().childs(),
+      &HashSet::from([a, c])
+    );
+    assert_eq!(right.get_node(b), Err(Error::UuidNotFound { uuid: b }));
+
+    assert_eq!(
+      left.add_child(b, root).unwrap_err(),
+      Error::Cycle { src: b, dst: root }
+    );
+    assert_eq!(
+      left
+        .remove_child(a, b)
+        .unwrap()
+        .get_node(a)
+        .unwrap()
+        .childs()
+        .len(),
+      0
+    );
+  }
+
+  #[test]
+  fn test_conversion() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let (child, _) = graph.add_node(42);
+    graph.add_child(root, child).unwrap();
+
+    graph.set_uuid_version(UuidVersion::V7);
+    let persistent = PersistentGraph::from(&graph);
+    assert_eq!(persistent.name(), "Test Graph");
+    let back = AcyclicGraph::from(&persistent);
+    assert_eq!(back.nodes(), graph.nodes());
+    assert_eq!(back.uuid_version(), UuidVersion::V7);
+
+    let (persistent, uuid) = persistent.add_node("V7").unwrap();
+    assert_eq!(uuid.get_version(), Some(uuid::Version::SortRand));
+    assert_eq!(
+      persistent.add_node_with_uuid(uuid, "Again").unwrap_err(),
+      Error::UuidCollision { uuid }
+    );
+  }
+}
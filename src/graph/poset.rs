@@ -0,0 +1,290 @@
+use std::collections::{
+  HashMap,
+  VecDeque,
+};
+
+use uuid::Uuid;
+
+use super::{
+  AcyclicGraph,
+  transitive::Reach,
+};
+
+const NONE: usize = usize::MAX;
+
+// the right vertices of each left vertex of a bipartite graph, walked with a
+// cursor so the transitive closure stays in its bitsets
+trait Adjacency {
+  fn left(&self) -> usize;
+
+  // the right vertex at the cursor or after it and the cursor after it
+  fn next(&self, u: usize, cursor: usize) -> Option<(usize, usize)>;
+
+  fn adjacent(&self, u: usize) -> impl Iterator<Item = usize> + '_ {
+    std::iter::successors(self.next(u, 0), move |&(_, cursor)| self.next(u, cursor)).map(|(v, _)| v)
+  }
+}
+
+// lists of right vertices
+impl Adjacency for [Vec<usize>] {
+  fn left(&self) -> usize {
+    self.len()
+  }
+
+  fn next(&self, u: usize, cursor: usize) -> Option<(usize, usize)> {
+    Some((*self[u].get(cursor)?, cursor + 1))
+  }
+}
+
+// every descendant of a node, the cursor is the index of the node
+impl Adjacency for Reach {
+  fn left(&self) -> usize {
+    self.uuids.len()
+  }
+
+  fn next(&self, u: usize, cursor: usize) -> Option<(usize, usize)> {
+    let v = self.next_descendant(u, cursor)?;
+    Some((v, v + 1))
+  }
+}
+
+/// Maximum matching of a bipartite graph with Hopcroft-Karp, `adjacency`
+/// gives the right vertices of each left vertex. Return the right vertex
+/// matched to each left vertex and the left vertex matched to each right
+/// vertex, `NONE` if unmatched.
+fn maximum_matching(
+  adjacency: &(impl Adjacency + ?Sized), right: usize,
+) -> (Vec<usize>, Vec<usize>) {
+  let left = adjacency.left();
+  let mut match_left = vec![NONE; left];
+  let mut match_right = vec![NONE; right];
+  let mut dist = vec![NONE; left];
+
+  loop {
+    // BFS from free left vertices to layer the graph
+    let mut queue: VecDeque<usize> = (0..left).filter(|&u| match_left[u] == NONE).collect();
+    for u in 0..left {
+      dist[u] = if match_left[u] == NONE { 0 } else { NONE };
+    }
+    let mut found = false;
+    while let Some(u) = queue.pop_front() {
+      for v in adjacency.adjacent(u) {
+        let w = match_right[v];
+        if w == NONE {
+          found = true;
+        } else if dist[w] == NONE {
+          dist[w] = dist[u] + 1;
+          queue.push_back(w);
+        }
+      }
+    }
+    if !found {
+      break;
+    }
+
+    // iterative DFS along the layers to find disjoint augmenting paths
+    let mut cursors = vec![0; left];
+    for start in 0..left {
+      if match_left[start] != NONE {
+        continue;
+      }
+      let mut stack = vec![start];
+      let mut path = Vec::new();
+      while let Some(&u) = stack.last() {
+        if let Some((v, cursor)) = adjacency.next(u, cursors[u]) {
+          cursors[u] = cursor;
+          let w = match_right[v];
+          if w == NONE {
+            path.push(v);
+            for (&u, &v) in stack.iter().zip(&path) {
+              match_left[u] = v;
+              match_right[v] = u;
+            }
+            break;
+          } else if dist[w] == dist[u].wrapping_add(1) {
+            path.push(v);
+            stack.push(w);
+          }
+        } else {
+          dist[u] = NONE;
+          stack.pop();
+          if !stack.is_empty() {
+            path.pop();
+          }
+        }
+      }
+    }
+  }
+
+  (match_left, match_right)
+}
+
+impl AcyclicGraph {
+  // nodes sorted by UUID and the index of each of them
+  pub(super) fn indexes(&self) -> (Vec<Uuid>, HashMap<Uuid, usize>) {
+    let mut uuids: Vec<Uuid> = self.nodes.keys().copied().collect();
+    uuids.sort();
+    let indexes = uuids
+      .iter()
+      .enumerate()
+      .map(|(i, &uuid)| (uuid, i))
+      .collect();
+    (uuids, indexes)
+  }
+
+  /// A largest set of nodes where no node can reach another one, its size is
+  /// the width of the graph: the maximum number of nodes that can be processed
+  /// in parallel. Computed with Dilworth's theorem, a maximum matching on the
+  /// transitive closure read from its bitsets. Quadratic memory of a bit per
+  /// pair of nodes, sorted by UUID.
+  pub fn maximum_antichain(&self) -> Vec<Uuid> {
+    let reach = self.reach();
+    let uuids = &reach.uuids;
+    let n = uuids.len();
+    let (match_left, match_right) = maximum_matching(&reach, n);
+
+    // König's theorem, alternating BFS from the free left vertices
+    let mut visited_left = vec![false; n];
+    let mut visited_right = vec![false; n];
+    let mut queue: VecDeque<usize> = (0..n).filter(|&u| match_left[u] == NONE).collect();
+    for &u in &queue {
+      visited_left[u] = true;
+    }
+    while let Some(u) = queue.pop_front() {
+      for v in reach.descendants(u) {
+        if !visited_right[v] {
+          visited_right[v] = true;
+          let w = match_right[v];
+          if w != NONE && !visited_left[w] {
+            visited_left[w] = true;
+            queue.push_back(w);
+          }
+        }
+      }
+    }
+
+    (0..n)
+      .filter(|&u| visited_left[u] && !visited_right[u])
+      .map(|u| uuids[u])
+      .collect()
+  }
+
+  /// A smallest set of vertex-disjoint paths covering every node, computed
+  /// with a maximum matching between parents and childs. Paths are sorted by
+  /// their first node.
+  pub fn minimum_path_cover(&self) -> Vec<Vec<Uuid>> {
+    let (uuids, indexes) = self.indexes();
+    let adjacency: Vec<Vec<usize>> = uuids
+      .iter()
+      .map(|uuid| {
+        let mut childs: Vec<usize> = self.nodes[uuid]
+          .childs
+          .iter()
+          .map(|child| indexes[child])
+          .collect();
+        childs.sort();
+        childs
+      })
+      .collect();
+    let (next, previous) = maximum_matching(adjacency.as_slice(), uuids.len());
+
+    (0..uuids.len())
+      .filter(|&u| previous[u] == NONE)
+      .map(|start| {
+        let mut path = vec![uuids[start]];
+        let mut current = start;
+        while next[current] != NONE {
+          current = next[current];
+          path.push(uuids[current]);
+        }
+        path
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_matching() {
+    let adjacency = vec![vec![0, 1], vec![0], vec![1, 2]];
+    let (match_left, match_right) = maximum_matching(adjacency.as_slice(), 3);
+    assert!(match_left.iter().all(|&v| v != NONE));
+    for (u, &v) in match_left.iter().enumerate() {
+      assert_eq!(match_right[v], u);
+    }
+  }
+
+  #[test]
+  fn test_maximum_antichain() {
+    // root -> a -> c, root -> b -> c, b -> d
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let (a, _) = graph.add_node("A");
+    let (b, _) = graph.add_node("B");
+    let (c, _) = graph.add_node("C");
+    let (d, _) = graph.add_node("D");
+    graph.add_child(root, a).unwrap();
+    graph.add_child(root, b).unwrap();
+    graph.add_child(a, c).unwrap();
+    graph.add_child(b, c).unwrap();
+    graph.add_child(b, d).unwrap();
+
+    let antichain = graph.maximum_antichain();
+    assert_eq!(antichain.len(), 2);
+    for &x in &antichain {
+      let reachable: Vec<Uuid> = graph.levels(x).unwrap().into_iter().flatten().collect();
+      for &y in &antichain {
+        assert!(x == y || !reachable.contains(&y));
+      }
+    }
+  }
+
+  #[test]
+  fn test_minimum_path_cover() {
+    // a -> b -> c, a -> d, e -> c
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (a, _) = graph.add_node("A");
+    let (b, _) = graph.add_node("B");
+    let (c, _) = graph.add_node("C");
+    let (d, _) = graph.add_node("D");
+    let (e, _) = graph.add_node("E");
+    graph.add_child(a, b).unwrap();
+    graph.add_child(b, c).unwrap();
+    graph.add_child(a, d).unwrap();
+    graph.add_child(e, c).unwrap();
+
+    let paths = graph.minimum_path_cover();
+    assert_eq!(paths.len(), 3);
+    let mut covered: Vec<Uuid> = paths.iter().flatten().copied().collect();
+    covered.sort();
+    let mut expected = vec![a, b, c, d, e];
+    expected.sort();
+    assert_eq!(covered, expected);
+    for path in &paths {
+      for pair in path.windows(2) {
+        assert!(graph.nodes[&pair[0]].childs.contains(&pair[1]));
+      }
+    }
+  }
+
+  #[test]
+  fn test_maximum_antichain_tree() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let mut leaves = Vec::new();
+    for _ in 0..3 {
+      let (child, _) = graph.add_node("Child");
+      graph.add_child(root, child).unwrap();
+      for _ in 0..2 {
+        let (leaf, _) = graph.add_node("Leaf");
+        graph.add_child(child, leaf).unwrap();
+        leaves.push(leaf);
+      }
+    }
+    leaves.sort();
+    assert_eq!(graph.maximum_antichain(), leaves);
+  }
+}
@@ -0,0 +1,234 @@
+use std::{
+  borrow::Cow,
+  fmt::{
+    self,
+    Display,
+    Formatter,
+  },
+};
+
+use super::{
+  AcyclicGraph,
+  Error,
+  RenderOptions,
+};
+
+/// How the renderers handle unsafe characters in the graph name and the node
+/// labels: control characters and ``"\[]{}()<>|#&;` ``
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Sanitize {
+  /// Escape them with the syntax of each format
+  #[default]
+  Escape,
+  /// Remove them
+  Strip,
+  /// Replace each of them by `_`
+  Replace,
+  /// Refuse them, see [`AcyclicGraph::check_names`], renderers escape them
+  Error,
+}
+
+/// Characters that break or change the meaning of at least one format
+pub fn is_unsafe(c: char) -> bool {
+  c.is_control() || "\"\\[]{}()<>|#&;`".contains(c)
+}
+
+impl Sanitize {
+  pub(super) fn apply(self, name: &str) -> Cow<'_, str> {
+    if !name.contains(is_unsafe) {
+      return Cow::Borrowed(name);
+    }
+    match self {
+      Sanitize::Escape | Sanitize::Error => Cow::Borrowed(name),
+      Sanitize::Strip => name.chars().filter(|&c| !is_unsafe(c)).collect(),
+      Sanitize::Replace => name
+        .chars()
+        .map(|c| if is_unsafe(c) { '_' } else { c })
+        .collect(),
+    }
+  }
+}
+
+impl RenderOptions {
+  pub(super) fn name<'a>(&self, graph: &'a AcyclicGraph) -> Cow<'a, str> {
+    self.sanitize.apply(&graph.name)
+  }
+}
+
+impl AcyclicGraph {
+  /// Fail on the graph name or the first label by UUID with an unsafe
+  /// character, see [`Sanitize`]
+  pub fn check_names(&self, options: &RenderOptions) -> Result<(), Error> {
+    if self.name.contains(is_unsafe) {
+      return Err(Error::UnsafeName {
+        uuid: None,
+        name: self.name.clone(),
+      });
+    }
+    let depths = options.depths(self);
+    for (&uuid, node) in &self.nodes {
+      if let Some(label) = options.label(uuid, node, depths.as_ref())
+        && label.contains(is_unsafe)
+      {
+        return Err(Error::UnsafeName {
+          uuid: Some(uuid),
+          name: label,
+        });
+      }
+    }
+    Ok(())
+  }
+}
+
+// escape a DOT quoted string
+pub(super) struct DotEscape<'a>(pub(super) &'a str);
+
+impl Display for DotEscape<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    for c in self.0.chars() {
+      match c {
+        '"' => f.write_str("\\\"")?,
+        '\\' => f.write_str("\\\\")?,
+        '\n' => f.write_str("\\n")?,
+        c if c.is_control() => {}
+        c => write!(f, "{c}")?,
+      }
+    }
+    Ok(())
+  }
+}
+
+// a Mermaid node text, quoted with entity codes when it has unsafe
+// characters
+pub(super) struct MermaidText<'a>(pub(super) &'a str);
+
+impl Display for MermaidText<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    if !self.0.contains(is_unsafe) {
+      return f.write_str(self.0);
+    }
+    f.write_str("\"")?;
+    for c in self.0.chars() {
+      match c {
+        '"' | '#' => write!(f, "#{};", c as u32)?,
+        '\n' => f.write_str("<br>")?,
+        c if c.is_control() => {}
+        c => write!(f, "{c}")?,
+      }
+    }
+    f.write_str("\"")
+  }
+}
+
+// escape the line breaks and tabulations of line based formats
+pub(super) struct LineEscape<'a>(pub(super) &'a str);
+
+impl Display for LineEscape<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    for c in self.0.chars() {
+      match c {
+        '\n' => f.write_str("\\n")?,
+        '\r' => f.write_str("\\r")?,
+        '\t' => f.write_str("\\t")?,
+        c => write!(f, "{c}")?,
+      }
+    }
+    Ok(())
+  }
+}
+
+// escape XML text and attribute values, the control characters other than
+// the line breaks and tabulations aren't allowed in XML 1.0
+pub(super) struct XmlEscape<'a>(pub(super) &'a str);
+
+impl Display for XmlEscape<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    for c in self.0.chars() {
+      match c {
+        '&' => f.write_str("&amp;")?,
+        '<' => f.write_str("&lt;")?,
+        '>' => f.write_str("&gt;")?,
+        '"' => f.write_str("&quot;")?,
+        '\n' => f.write_str("&#10;")?,
+        '\r' => f.write_str("&#13;")?,
+        '\t' => f.write_str("&#9;")?,
+        c if c.is_control() => {}
+        c => write!(f, "{c}")?,
+      }
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use uuid::Uuid;
+
+  use super::*;
+
+  fn graph() -> (AcyclicGraph, Uuid) {
+    let mut graph = AcyclicGraph::new("Test \"Graph\"");
+    let (root, _) = graph.add_node("Root [a|b]");
+    let (child, _) = graph.add_node("Child");
+    graph.add_child(root, child).unwrap();
+    (graph, root)
+  }
+
+  #[test]
+  fn test_sanitize() {
+    assert_eq!(Sanitize::Strip.apply("a[b]\n#c"), "abc");
+    assert_eq!(Sanitize::Replace.apply("a[b]\n#c"), "a_b___c");
+    assert_eq!(Sanitize::Escape.apply("a[b]"), "a[b]");
+    assert!(matches!(Sanitize::Replace.apply("safe"), Cow::Borrowed(_)));
+  }
+
+  #[test]
+  fn test_escape() {
+    let (graph, root) = graph();
+    let root = short_uuid::ShortUuid::from_uuid(&root);
+
+    let dot = graph.dot().to_string();
+    assert!(dot.starts_with("digraph \"Test \\\"Graph\\\"\" {"));
+    dot_parser::ast::Graph::try_from(dot.as_str()).expect("DOT format is invalid");
+    let mermaid = graph.mermaid().to_string();
+    assert!(mermaid.contains(&format!("  {root}[\"Root [a|b]\"] --> ")));
+    assert!(mermaid.contains("title: \"Test \\\"Graph\\\"\"\n"));
+
+    let options = RenderOptions {
+      sanitize: Sanitize::Replace,
+      ..Default::default()
+    };
+    let mermaid = graph.mermaid_with(options.clone()).to_string();
+    assert!(mermaid.contains(&format!("  {root}[Root _a_b_] --> ")));
+    assert!(mermaid.contains("title: Test _Graph_\n"));
+    let dimacs = graph.dimacs_with(options).to_string();
+    assert!(dimacs.starts_with("c Test _Graph_\n"));
+  }
+
+  #[test]
+  fn test_check_names() {
+    let (mut graph, root) = graph();
+    let options = RenderOptions {
+      sanitize: Sanitize::Error,
+      ..Default::default()
+    };
+    assert_eq!(
+      graph.check_names(&options),
+      Err(Error::UnsafeName {
+        uuid: None,
+        name: "Test \"Graph\"".to_string()
+      })
+    );
+    graph.name = "Test Graph".to_string();
+    assert_eq!(
+      graph.check_names(&options),
+      Err(Error::UnsafeName {
+        uuid: Some(root),
+        name: "Root [a|b]".to_string()
+      })
+    );
+    graph.set_node_name(root, "Root").unwrap();
+    assert_eq!(graph.check_names(&options), Ok(()));
+  }
+}
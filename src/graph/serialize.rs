@@ -0,0 +1,262 @@
+use std::{
+  collections::{
+    BTreeMap,
+    HashSet,
+  },
+  io::{
+    Read,
+    Write,
+  },
+};
+
+use itertools::Itertools;
+use serde::{
+  Deserialize,
+  Deserializer,
+  Serialize,
+  Serializer,
+  de,
+};
+use serde_json::{
+  Map,
+  Value,
+};
+use uuid::Uuid;
+
+use super::{
+  AcyclicGraph,
+  Error,
+  Node,
+  NodeData,
+};
+
+// serialized form, nodes and childs are sorted by UUID
+#[derive(Deserialize)]
+pub(super) struct GraphData {
+  name: String,
+  nodes: Vec<NodeEntry>,
+}
+
+#[derive(Deserialize)]
+struct NodeEntry {
+  uuid: Uuid,
+  #[serde(flatten)]
+  node: Node,
+  // data of the edges to the childs
+  #[serde(default)]
+  edges: BTreeMap<Uuid, Map<String, Value>>,
+}
+
+// the same form borrowing the graph, so writing it doesn't clone it
+#[derive(Serialize)]
+struct GraphDataRef<'a> {
+  name: &'a str,
+  #[serde(serialize_with = "node_entries")]
+  nodes: &'a AcyclicGraph,
+}
+
+#[derive(Serialize)]
+struct NodeEntryRef<'a> {
+  uuid: &'a Uuid,
+  #[serde(flatten)]
+  node: &'a Node,
+  #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+  edges: BTreeMap<&'a Uuid, &'a Map<String, Value>>,
+}
+
+fn node_entries<S: Serializer>(graph: &&AcyclicGraph, serializer: S) -> Result<S::Ok, S::Error> {
+  serializer.collect_seq(graph.nodes.iter().map(|(uuid, node)| {
+    let edges = node
+      .childs
+      .iter()
+      .filter_map(|child| Some((child, graph.edge_data.get(&(*uuid, *child))?)))
+      .collect();
+    NodeEntryRef { uuid, node, edges }
+  }))
+}
+
+pub(super) fn is_none(data: &NodeData) -> bool {
+  *data == NodeData::None
+}
+
+pub(super) fn sorted<S: Serializer>(
+  childs: &HashSet<Uuid>, serializer: S,
+) -> Result<S::Ok, S::Error> {
+  serializer.collect_seq(childs.iter().sorted())
+}
+
+// a duplicated edge is an error instead of being merged
+pub(super) fn unique<'de, D: Deserializer<'de>>(
+  deserializer: D,
+) -> Result<HashSet<Uuid>, D::Error> {
+  let childs = Vec::<Uuid>::deserialize(deserializer)?;
+  let mut unique = HashSet::with_capacity(childs.len());
+  for child in childs {
+    if !unique.insert(child) {
+      return Err(de::Error::custom(format!("child {child} listed twice")));
+    }
+  }
+  Ok(unique)
+}
+
+impl AcyclicGraph {
+  /// Load a graph written by [`AcyclicGraph::to_json_writer`], checked like
+  /// with [`AcyclicGraph::from_parts`]. A cycle, a missing child or a
+  /// duplicated UUID or edge is a data error.
+  pub fn from_json_reader(reader: impl Read) -> serde_json::Result<Self> {
+    serde_json::from_reader(reader)
+  }
+
+  /// Lossless pretty JSON of the graph with its node data, levels, timestamps
+  /// and edge data, sorted by UUID
+  pub fn to_json_writer(&self, mut writer: impl Write) -> serde_json::Result<()> {
+    serde_json::to_writer_pretty(&mut writer, self)?;
+    writer.write_all(b"\n").map_err(serde_json::Error::io)
+  }
+
+  /// Build a graph from nodes that may come from an untrusted source, like a
+  /// hand edited file. The JSON import and the checkpoints go through it so
+  /// a loaded graph always has unique UUIDs, existing childs and no cycle,
+  /// [`AcyclicGraph::from_dot`] gets the same guarantees by adding the nodes
+  /// and edges one by one.
+  pub fn from_parts(
+    name: impl Into<String>, nodes: impl IntoIterator<Item = (Uuid, Node)>,
+  ) -> Result<Self, Error> {
+    let mut graph = AcyclicGraph::new(name);
+    for (uuid, node) in nodes {
+      if graph.nodes.insert(uuid, node).is_some() {
+        return Err(Error::UuidCollision { uuid });
+      }
+    }
+
+    // the smallest dangling edge so the error doesn't depend on the order
+    let dangling = graph
+      .nodes
+      .iter()
+      .flat_map(|(&parent, node)| node.childs.iter().map(move |&child| (parent, child)))
+      .filter(|(_, child)| !graph.nodes.contains_key(child))
+      .min();
+    if let Some((parent, child)) = dangling {
+      return Err(Error::ChildNotFound { parent, child });
+    }
+    graph.check_invariants()?;
+    graph.rebuild_order();
+    Ok(graph)
+  }
+}
+
+impl Serialize for AcyclicGraph {
+  fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+    GraphDataRef {
+      name: &self.name,
+      nodes: self,
+    }
+    .serialize(serializer)
+  }
+}
+
+impl TryFrom<GraphData> for AcyclicGraph {
+  type Error = Error;
+
+  fn try_from(data: GraphData) -> Result<Self, Error> {
+    let mut edges = Vec::new();
+    let nodes = data.nodes.into_iter().map(|entry| {
+      edges.extend(
+        entry
+          .edges
+          .into_iter()
+          .map(|(child, data)| (entry.uuid, child, data)),
+      );
+      (entry.uuid, entry.node)
+    });
+    let mut graph = AcyclicGraph::from_parts(data.name, nodes)?;
+    // an edge must be listed in the childs to have data
+    for (parent, child, data) in edges {
+      graph.set_edge_data(parent, child, data)?;
+    }
+    Ok(graph)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+  use crate::schema::Timestamp;
+
+  #[test]
+  fn test_roundtrip() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let (a, _) = graph.add_node(json!({ "name": "A", "age": 7 }));
+    let (b, _) = graph.add_node(42);
+    let (c, _) = graph.add_node(());
+    graph.add_child(root, a).unwrap();
+    graph.add_child(root, b).unwrap();
+    graph.add_child(a, c).unwrap();
+    graph.add_child(b, c).unwrap();
+    let node = graph.get_node_mut(root).unwrap();
+    node.set_level(0);
+    node.set_timestamp(Timestamp(86400));
+
+    let serialized = serde_json::to_string(&graph).unwrap();
+    let deserialized: AcyclicGraph = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(deserialized, graph);
+    // the output is sorted
+    assert_eq!(serde_json::to_string(&deserialized).unwrap(), serialized);
+
+    let mut json = Vec::new();
+    graph.to_json_writer(&mut json).unwrap();
+    assert_eq!(
+      AcyclicGraph::from_json_reader(json.as_slice()).unwrap(),
+      graph
+    );
+    let node: Node =
+      serde_json::from_str(&serde_json::to_string(&graph.nodes[&root]).unwrap()).unwrap();
+    assert_eq!(node, graph.nodes[&root]);
+  }
+
+  #[test]
+  fn test_untrusted() {
+    let a = Uuid::from_u128(1);
+    let b = Uuid::from_u128(2);
+    let parse = |nodes: serde_json::Value| {
+      serde_json::from_value::<AcyclicGraph>(json!({ "name": "Test Graph", "nodes": nodes }))
+        .map_err(|e| e.to_string())
+    };
+
+    let graph = parse(json!([
+      { "uuid": a, "data": { "text": "A" }, "childs": [b] },
+      { "uuid": b },
+    ]))
+    .unwrap();
+    assert_eq!(graph.get_node(a).unwrap().childs().len(), 1);
+
+    assert_eq!(
+      parse(json!([{ "uuid": a, "childs": [b] }])).unwrap_err(),
+      Error::ChildNotFound {
+        parent: a,
+        child: b
+      }
+      .to_string()
+    );
+    assert_eq!(
+      parse(json!([{ "uuid": a, "childs": [b] }, { "uuid": b, "childs": [a] }])).unwrap_err(),
+      Error::Cycle { src: a, dst: b }.to_string()
+    );
+    assert_eq!(
+      parse(json!([{ "uuid": a, "childs": [a] }])).unwrap_err(),
+      Error::Cycle { src: a, dst: a }.to_string()
+    );
+    assert_eq!(
+      parse(json!([{ "uuid": a }, { "uuid": a }])).unwrap_err(),
+      Error::UuidCollision { uuid: a }.to_string()
+    );
+    assert!(
+      parse(json!([{ "uuid": a, "childs": [b, b] }, { "uuid": b }]))
+        .unwrap_err()
+        .contains(&format!("child {b} listed twice"))
+    );
+  }
+}
@@ -0,0 +1,142 @@
+use std::collections::{
+  HashMap,
+  HashSet,
+};
+
+use itertools::Itertools;
+use uuid::Uuid;
+
+use super::AcyclicGraph;
+
+/// Cost of an edit path between two graphs, see [`AcyclicGraph::similarity`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Similarity {
+  /// Nodes removed from the first graph or added from the second one
+  pub node_edits: usize,
+  /// Edges removed from the first graph or added from the second one
+  pub edge_edits: usize,
+  /// 1 when no edit is needed, 0 when every node and edge is replaced
+  pub score: f64,
+}
+
+impl AcyclicGraph {
+  /// Approximate graph edit distance to `other`, the data is ignored. Nodes
+  /// with the same UUID are matched, like after a mutation, then the others
+  /// are paired by depth and degrees, like after a regeneration. The edits
+  /// turn one graph into the other through that matching so the distance is
+  /// never below the exact one.
+  pub fn similarity(&self, other: &Self) -> Similarity {
+    let mut matching: HashMap<Uuid, Uuid> = self
+      .nodes
+      .keys()
+      .filter(|uuid| other.nodes.contains_key(uuid))
+      .map(|&uuid| (uuid, uuid))
+      .collect();
+
+    // the remaining nodes of each depth sorted by degrees then zipped
+    let unmatched = |graph: &AcyclicGraph, other: &AcyclicGraph| {
+      let parents = graph.parents();
+      let mut depths: HashMap<usize, Vec<(usize, usize, Uuid)>> = HashMap::new();
+      for (uuid, depth) in graph.depths() {
+        if !other.nodes.contains_key(&uuid) {
+          let parents = parents.get(&uuid).map_or(0, HashSet::len);
+          let childs = graph.nodes[&uuid].childs.len();
+          depths
+            .entry(depth)
+            .or_default()
+            .push((parents, childs, uuid));
+        }
+      }
+      depths
+    };
+    let mut left = unmatched(self, other);
+    let right = unmatched(other, self);
+    for (depth, nodes) in right {
+      let Some(left) = left.remove(&depth) else {
+        continue;
+      };
+      for (a, b) in left.into_iter().sorted().zip(nodes.into_iter().sorted()) {
+        matching.insert(a.2, b.2);
+      }
+    }
+
+    let node_edits = self.nodes.len() + other.nodes.len() - 2 * matching.len();
+    let kept = self
+      .nodes
+      .iter()
+      .filter_map(|(parent, node)| Some((matching.get(parent)?, node)))
+      .flat_map(|(parent, node)| {
+        node
+          .childs
+          .iter()
+          .filter_map(|child| matching.get(child))
+          .filter(|child| other.nodes[parent].childs.contains(*child))
+      })
+      .count();
+    let edges = |graph: &AcyclicGraph| graph.nodes.values().map(|node| node.childs.len()).sum();
+    let (edges_a, edges_b): (usize, usize) = (edges(self), edges(other));
+    let edge_edits = edges_a + edges_b - 2 * kept;
+
+    let total = self.nodes.len() + other.nodes.len() + edges_a + edges_b;
+    let score = match total {
+      0 => 1.0,
+      total => 1.0 - (node_edits + edge_edits) as f64 / total as f64,
+    };
+    Similarity {
+      node_edits,
+      edge_edits,
+      score,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // root -> a -> b, root -> c
+  fn sample() -> (AcyclicGraph, [Uuid; 4]) {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let (a, _) = graph.add_node("A");
+    let (b, _) = graph.add_node("B");
+    let (c, _) = graph.add_node("C");
+    for (parent, child) in [(root, a), (a, b), (root, c)] {
+      graph.add_child(parent, child).unwrap();
+    }
+    (graph, [root, a, b, c])
+  }
+
+  #[test]
+  fn test_similarity() {
+    let (graph, [root, a, b, _]) = sample();
+    let same = Similarity {
+      node_edits: 0,
+      edge_edits: 0,
+      score: 1.0,
+    };
+    assert_eq!(graph.similarity(&graph), same);
+    // other UUIDs
+    let (other, _) = sample();
+    assert_eq!(graph.similarity(&other), same);
+
+    let mut mutated = graph.clone();
+    mutated.remove_child(a, b).unwrap();
+    mutated.add_child(root, b).unwrap();
+    let similarity = graph.similarity(&mutated);
+    assert_eq!((similarity.node_edits, similarity.edge_edits), (0, 2));
+    assert_eq!(similarity.score, 1.0 - 2.0 / 14.0);
+
+    let mut single = AcyclicGraph::new("Single");
+    single.add_node("Root");
+    let similarity = graph.similarity(&single);
+    assert_eq!((similarity.node_edits, similarity.edge_edits), (3, 3));
+    assert_eq!(single.similarity(&graph).node_edits, 3);
+    assert_eq!(
+      AcyclicGraph::new("Empty")
+        .similarity(&AcyclicGraph::new("Empty"))
+        .score,
+      1.0
+    );
+  }
+}
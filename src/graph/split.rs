@@ -0,0 +1,148 @@
+use std::{
+  collections::{
+    HashMap,
+    HashSet,
+  },
+  num::NonZeroUsize,
+};
+
+use itertools::Itertools;
+use uuid::Uuid;
+
+use super::AcyclicGraph;
+
+/// A slice of a split graph, small enough for the renderers
+#[derive(Debug, Clone)]
+pub struct Part {
+  /// The nodes of the slice, the references and the edges between them
+  pub graph: AcyclicGraph,
+  /// Nodes of other slices linked to this one, repeated so each edge leaving
+  /// the slice is visible, see [`super::RenderOptions::references`]
+  pub references: HashSet<Uuid>,
+}
+
+impl AcyclicGraph {
+  /// Slices of `levels` consecutive depths, see [`AcyclicGraph::depths`]
+  pub fn split_levels(&self, levels: NonZeroUsize) -> Vec<Part> {
+    let parts = self
+      .depths()
+      .into_iter()
+      .map(|(uuid, depth)| (uuid, depth / levels))
+      .collect();
+    self.split(parts)
+  }
+
+  /// The roots in the first slice then one slice per subtree of a child of a
+  /// root, a node reachable from several subtrees is in the first one by
+  /// UUID order
+  pub fn split_by_subtree(&self) -> Vec<Part> {
+    let (_, clusters) = self.clusters();
+    let parts = self
+      .nodes
+      .keys()
+      .map(|uuid| (*uuid, clusters.get(uuid).map_or(0, |cluster| cluster + 1)))
+      .collect();
+    self.split(parts)
+  }
+
+  // one part per distinct value of `parts`, empty parts are skipped
+  fn split(&self, parts: HashMap<Uuid, usize>) -> Vec<Part> {
+    let indexes: Vec<usize> = parts.values().copied().sorted().dedup().collect();
+    let count = indexes.len();
+    indexes
+      .into_iter()
+      .enumerate()
+      .map(|(i, index)| {
+        let mut graph = AcyclicGraph::new(format!("{} ({}/{count})", self.name, i + 1));
+        let mut references = HashSet::new();
+        for (&uuid, node) in &self.nodes {
+          if parts[&uuid] != index {
+            continue;
+          }
+          graph.nodes.insert(uuid, node.clone());
+          for child in &node.childs {
+            if parts[child] != index {
+              references.insert(*child);
+            }
+          }
+        }
+        for (&uuid, node) in &self.nodes {
+          if parts[&uuid] != index && node.childs.iter().any(|child| parts[child] == index) {
+            references.insert(uuid);
+          }
+        }
+        for &uuid in &references {
+          let mut node = self.nodes[&uuid].clone();
+          // only the edges into the slice, a reference from a later slice
+          // keeps none
+          node
+            .childs
+            .retain(|child| parts[child] == index && parts[&uuid] != index);
+          graph.nodes.insert(uuid, node);
+        }
+        // the nodes were inserted with their edges
+        graph.rebuild_order();
+        Part { graph, references }
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_split_levels() {
+    // root -> a -> c -> d, root -> b
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let (a, _) = graph.add_node("A");
+    let (b, _) = graph.add_node("B");
+    let (c, _) = graph.add_node("C");
+    let (d, _) = graph.add_node("D");
+    for (parent, child) in [(root, a), (root, b), (a, c), (c, d)] {
+      graph.add_child(parent, child).unwrap();
+    }
+
+    let parts = graph.split_levels(NonZeroUsize::new(2).unwrap());
+    assert_eq!(parts.len(), 2);
+    assert_eq!(parts[0].graph.name, "Test Graph (1/2)");
+    assert_eq!(parts[0].references, HashSet::from([c]));
+    assert_eq!(parts[0].graph.nodes().len(), 4);
+    assert_eq!(parts[0].graph.nodes()[&a].childs(), &HashSet::from([c]));
+    assert!(parts[0].graph.nodes()[&c].childs().is_empty());
+    assert_eq!(parts[1].references, HashSet::from([a]));
+    assert_eq!(parts[1].graph.nodes()[&a].childs(), &HashSet::from([c]));
+    assert_eq!(parts[1].graph.nodes()[&c].childs(), &HashSet::from([d]));
+
+    let parts = graph.split_levels(NonZeroUsize::new(10).unwrap());
+    assert_eq!(parts.len(), 1);
+    assert!(parts[0].references.is_empty());
+    assert_eq!(parts[0].graph.nodes(), graph.nodes());
+  }
+
+  #[test]
+  fn test_split_by_subtree() {
+    // root -> a -> c, root -> b
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let (a, _) = graph.add_node("A");
+    let (b, _) = graph.add_node("B");
+    let (c, _) = graph.add_node("C");
+    for (parent, child) in [(root, a), (root, b), (a, c)] {
+      graph.add_child(parent, child).unwrap();
+    }
+
+    let parts = graph.split_by_subtree();
+    assert_eq!(parts.len(), 3);
+    assert_eq!(parts[0].references, HashSet::from([a, b]));
+    assert_eq!(parts[0].graph.nodes().len(), 3);
+    let subtree = parts
+      .iter()
+      .find(|part| part.graph.nodes().contains_key(&c))
+      .unwrap();
+    assert_eq!(subtree.references, HashSet::from([root]));
+    assert_eq!(subtree.graph.nodes()[&root].childs(), &HashSet::from([a]));
+  }
+}
@@ -0,0 +1,504 @@
+use std::{
+  collections::HashMap,
+  fmt::{
+    self,
+    Display,
+    Formatter,
+  },
+  str::FromStr,
+};
+
+use itertools::Itertools;
+use num_bigint::BigUint;
+use uuid::Uuid;
+
+use super::{
+  AcyclicGraph,
+  NodeData,
+  RenderOptions,
+};
+
+// ColorBrewer Set1 without the yellow, readable on a white background
+const PALETTE: [&str; 8] = [
+  "#e41a1c", "#377eb8", "#4daf4a", "#984ea3", "#ff7f00", "#a65628", "#f781bf", "#999999",
+];
+// ColorBrewer Pastel1, light enough to read a label on it
+const FILLS: [&str; 9] = [
+  "#fbb4ae", "#b3cde3", "#ccebc5", "#decbe4", "#fed9a6", "#ffffcc", "#e5d8bd", "#fddaec", "#f2f2f2",
+];
+const MIN_WIDTH: f64 = 1.0;
+const MAX_WIDTH: f64 = 5.0;
+
+/// Direction of the levels, the DOT `rankdir` and the Mermaid flowchart
+/// direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum RankDir {
+  /// Top to bottom
+  #[default]
+  Tb,
+  /// Left to right, easier to read for wide graphs
+  Lr,
+  /// Bottom to top
+  Bt,
+  /// Right to left
+  Rl,
+}
+
+impl RankDir {
+  pub(super) fn code(self) -> &'static str {
+    match self {
+      RankDir::Tb => "TB",
+      RankDir::Lr => "LR",
+      RankDir::Bt => "BT",
+      RankDir::Rl => "RL",
+    }
+  }
+}
+
+/// Outline of a DOT and Mermaid node
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum NodeShape {
+  #[default]
+  Box,
+  /// A stadium in Mermaid which has no ellipse
+  Ellipse,
+  Circle,
+  Diamond,
+  Hexagon,
+  Parallelogram,
+}
+
+impl NodeShape {
+  const ALL: [NodeShape; 6] = [
+    NodeShape::Box,
+    NodeShape::Ellipse,
+    NodeShape::Circle,
+    NodeShape::Diamond,
+    NodeShape::Hexagon,
+    NodeShape::Parallelogram,
+  ];
+
+  pub(super) fn dot(self) -> &'static str {
+    match self {
+      NodeShape::Box => "box",
+      NodeShape::Ellipse => "ellipse",
+      NodeShape::Circle => "circle",
+      NodeShape::Diamond => "diamond",
+      NodeShape::Hexagon => "hexagon",
+      NodeShape::Parallelogram => "parallelogram",
+    }
+  }
+
+  // the brackets around the text of the node
+  pub(super) fn mermaid(self) -> (&'static str, &'static str) {
+    match self {
+      NodeShape::Box => ("[", "]"),
+      NodeShape::Ellipse => ("([", "])"),
+      NodeShape::Circle => ("((", "))"),
+      NodeShape::Diamond => ("{", "}"),
+      NodeShape::Hexagon => ("{{", "}}"),
+      NodeShape::Parallelogram => ("[/", "/]"),
+    }
+  }
+}
+
+/// Shape of the nodes by variant of their data, parsed from a list like
+/// `text=ellipse,json=hexagon` where the variants are `number`, `text`,
+/// `json` and `none`, the others stay boxes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NodeShapes {
+  pub number: NodeShape,
+  pub text: NodeShape,
+  pub json: NodeShape,
+  pub none: NodeShape,
+}
+
+impl NodeShapes {
+  pub fn get(&self, data: &NodeData) -> NodeShape {
+    match data {
+      NodeData::Number(_) => self.number,
+      NodeData::Text(_) => self.text,
+      NodeData::Json(_) => self.json,
+      NodeData::None => self.none,
+    }
+  }
+}
+
+impl FromStr for NodeShapes {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let invalid = || {
+      format!(
+        "invalid node shapes {s:?}, expected variant=shape separated by commas with number, text, \
+         json or none and {}",
+        NodeShape::ALL.iter().map(|shape| shape.dot()).join(", ")
+      )
+    };
+    let mut shapes = NodeShapes::default();
+    for rule in s.split(',').map(str::trim).filter(|rule| !rule.is_empty()) {
+      let (variant, shape) = rule.split_once('=').ok_or_else(invalid)?;
+      let shape = NodeShape::ALL
+        .into_iter()
+        .find(|other| other.dot() == shape.trim())
+        .ok_or_else(invalid)?;
+      match variant.trim() {
+        "number" => shapes.number = shape,
+        "text" => shapes.text = shape,
+        "json" => shapes.json = shape,
+        "none" => shapes.none = shape,
+        _ => return Err(invalid()),
+      }
+    }
+    Ok(shapes)
+  }
+}
+
+/// What the exported edges show, as a DOT `color` or `penwidth` and a Mermaid
+/// `linkStyle`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum EdgeStyle {
+  #[default]
+  None,
+  /// Color of the depth of the parent, the palette cycles after 8 levels
+  Depth,
+  /// Width growing with the logarithm of the number of root to leaf paths
+  /// going through the edge, see [`AcyclicGraph::edge_path_counts`]
+  Weight,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Stroke {
+  Color(&'static str),
+  Width(f64),
+}
+
+impl Stroke {
+  pub(super) fn dot(self) -> DotStroke {
+    DotStroke(self)
+  }
+
+  pub(super) fn mermaid(self) -> MermaidStroke {
+    MermaidStroke(self)
+  }
+}
+
+pub(super) struct DotStroke(Stroke);
+
+impl Display for DotStroke {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self.0 {
+      Stroke::Color(color) => write!(f, "color = \"{color}\""),
+      Stroke::Width(width) => write!(f, "penwidth = {width:.1}"),
+    }
+  }
+}
+
+pub(super) struct MermaidStroke(Stroke);
+
+impl Display for MermaidStroke {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self.0 {
+      Stroke::Color(color) => write!(f, "stroke:{color}"),
+      Stroke::Width(width) => write!(f, "stroke-width:{width:.1}px"),
+    }
+  }
+}
+
+/// Stroke of each edge and the legend explaining them
+pub(super) struct Strokes {
+  /// Keyed by parent and child
+  pub(super) edges: HashMap<(Uuid, Uuid), Stroke>,
+  /// Each used stroke with its meaning
+  pub(super) legend: Vec<(Stroke, String)>,
+}
+
+impl Strokes {
+  // a cluster with one labeled edge per legend entry
+  pub(super) fn write_dot_legend(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    if self.legend.is_empty() {
+      return Ok(());
+    }
+    writeln!(f, "  subgraph \"cluster_legend\" {{")?;
+    writeln!(f, "    label = \"Legend\"")?;
+    for (i, (stroke, meaning)) in self.legend.iter().enumerate() {
+      writeln!(
+        f,
+        "    \"legend_{i}\" [shape = plaintext, label = \"{meaning}\"];"
+      )?;
+      writeln!(f, "    \"legend_{i}_end\" [shape = point, style = invis];")?;
+      writeln!(
+        f,
+        "    \"legend_{i}\" -> \"legend_{i}_end\" [{}];",
+        stroke.dot()
+      )?;
+    }
+    writeln!(f, "  }}")
+  }
+
+  // a subgraph with one node per legend entry, its border has the stroke
+  pub(super) fn write_mermaid_legend(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    if self.legend.is_empty() {
+      return Ok(());
+    }
+    writeln!(f, "  subgraph legend [Legend]")?;
+    for (i, (_, meaning)) in self.legend.iter().enumerate() {
+      writeln!(f, "    legend{i}[{meaning}]")?;
+    }
+    writeln!(f, "  end")?;
+    for (i, (stroke, _)) in self.legend.iter().enumerate() {
+      writeln!(f, "  style legend{i} {}", stroke.mermaid())?;
+    }
+    Ok(())
+  }
+}
+
+impl RenderOptions {
+  // the fill color of a depth with --color-by-depth, the palette cycles
+  pub(super) fn fill(&self, depth: Option<usize>) -> Option<&'static str> {
+    match self.color_by_depth {
+      true => depth.map(|depth| FILLS[depth % FILLS.len()]),
+      false => None,
+    }
+  }
+
+  // None without edge style
+  pub(super) fn strokes(&self, graph: &AcyclicGraph) -> Option<Strokes> {
+    let edges = graph
+      .nodes
+      .iter()
+      .flat_map(|(&parent, node)| node.childs.iter().map(move |&child| (parent, child)));
+    match self.edge_style {
+      EdgeStyle::None => None,
+      EdgeStyle::Depth => {
+        let depths = graph.depths();
+        let edges: HashMap<(Uuid, Uuid), Stroke> = edges
+          .map(|(parent, child)| {
+            let color = PALETTE[depths[&parent] % PALETTE.len()];
+            ((parent, child), Stroke::Color(color))
+          })
+          .collect();
+        // depths sharing a color once the palette cycles share an entry
+        let mut levels = vec![Vec::new(); PALETTE.len()];
+        for depth in edges
+          .keys()
+          .map(|(parent, _)| depths[parent])
+          .sorted()
+          .dedup()
+        {
+          levels[depth % PALETTE.len()].push(depth);
+        }
+        let legend = PALETTE
+          .iter()
+          .zip(levels)
+          .filter(|(_, levels)| !levels.is_empty())
+          .map(|(color, levels)| {
+            let plural = if levels.len() > 1 { "s" } else { "" };
+            let levels = levels.iter().join(", ");
+            (
+              Stroke::Color(color),
+              format!("parent depth{plural} {levels}"),
+            )
+          })
+          .collect();
+        Some(Strokes { edges, legend })
+      }
+      EdgeStyle::Weight => {
+        let counts = graph.edge_path_counts();
+        let (Some(min), Some(max)) = (counts.values().min(), counts.values().max()) else {
+          return Some(Strokes {
+            edges: HashMap::new(),
+            legend: Vec::new(),
+          });
+        };
+        // the counts grow exponentially, their bit length is their logarithm
+        let (min_bits, max_bits) = (min.bits(), max.bits());
+        let width = |bits: u64| {
+          let ratio = if max_bits > min_bits {
+            (bits - min_bits) as f64 / (max_bits - min_bits) as f64
+          } else {
+            0.0
+          };
+          MIN_WIDTH + (MAX_WIDTH - MIN_WIDTH) * ratio
+        };
+        let path = |count| {
+          let plural = if count == &BigUint::from(1u8) {
+            ""
+          } else {
+            "s"
+          };
+          format!("{count} root to leaf path{plural}")
+        };
+        let mut legend = vec![(Stroke::Width(width(min_bits)), path(min))];
+        if max_bits > min_bits {
+          legend.push((Stroke::Width(width(max_bits)), path(max)));
+        }
+        let edges = counts
+          .iter()
+          .map(|(&edge, count)| (edge, Stroke::Width(width(count.bits()))))
+          .collect();
+        Some(Strokes { edges, legend })
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{
+    SeedableRng,
+    rngs::StdRng,
+  };
+  use short_uuid::ShortUuid;
+
+  use super::*;
+
+  fn diamond() -> AcyclicGraph {
+    // root -> a -> c, root -> b -> c, a -> d
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node_with_rng("Root", &mut rng);
+    let (a, _) = graph.add_node_with_rng("A", &mut rng);
+    let (b, _) = graph.add_node_with_rng("B", &mut rng);
+    let (c, _) = graph.add_node_with_rng("C", &mut rng);
+    let (d, _) = graph.add_node_with_rng("D", &mut rng);
+    for (parent, child) in [(root, a), (root, b), (a, c), (b, c), (a, d)] {
+      graph.add_child(parent, child).unwrap();
+    }
+    graph
+  }
+
+  #[test]
+  fn test_depth() {
+    let graph = diamond();
+    let options = RenderOptions {
+      edge_style: EdgeStyle::Depth,
+      ..Default::default()
+    };
+    let dot_output = graph.dot_with(options.clone()).to_string();
+    dot_parser::ast::Graph::try_from(dot_output.as_str()).expect("DOT format is invalid");
+    let edges: Vec<&str> = dot_output
+      .lines()
+      .filter(|line| line.contains("->"))
+      .collect();
+    // and one per legend entry
+    assert_eq!(edges.len(), 7);
+    assert!(dot_output.contains(
+      "    \"legend_0\" [shape = plaintext, label = \"parent depth 0\"];\n    \"legend_0_end\" \
+       [shape = point, style = invis];\n    \"legend_0\" -> \"legend_0_end\" [color = \
+       \"#e41a1c\"];\n"
+    ));
+    assert_eq!(
+      edges
+        .iter()
+        .filter(|line| line.ends_with("[color = \"#e41a1c\"];"))
+        .count(),
+      3
+    );
+    assert_eq!(
+      edges
+        .iter()
+        .filter(|line| line.ends_with("[color = \"#377eb8\"];"))
+        .count(),
+      4
+    );
+
+    let mermaid_output = graph.mermaid_with(options).to_string();
+    let styles: Vec<&str> = mermaid_output
+      .lines()
+      .filter(|line| line.starts_with("  linkStyle"))
+      .collect();
+    assert_eq!(styles.len(), 2);
+    assert!(styles.iter().any(|line| line.ends_with(" stroke:#e41a1c")));
+    assert!(mermaid_output.ends_with(
+      "  subgraph legend [Legend]\n    legend0[parent depth 0]\n    legend1[parent depth 1]\n  \
+       end\n  style legend0 stroke:#e41a1c\n  style legend1 stroke:#377eb8\n"
+    ));
+    let links: usize = styles
+      .iter()
+      .map(|line| line.split(' ').nth(3).unwrap().split(',').count())
+      .sum();
+    assert_eq!(links, 5);
+  }
+
+  #[test]
+  fn test_weight() {
+    let graph = diamond();
+    let options = RenderOptions {
+      edge_style: EdgeStyle::Weight,
+      ..Default::default()
+    };
+    let strokes = options.strokes(&graph).unwrap();
+    assert_eq!(
+      strokes.legend,
+      vec![
+        (Stroke::Width(MIN_WIDTH), "1 root to leaf path".to_string()),
+        (Stroke::Width(MAX_WIDTH), "2 root to leaf paths".to_string()),
+      ]
+    );
+    let widths: Vec<f64> = strokes
+      .edges
+      .values()
+      .map(|stroke| match stroke {
+        Stroke::Width(width) => *width,
+        Stroke::Color(_) => unreachable!(),
+      })
+      .collect();
+    // 3 paths: root -> a carries 2 of them, the other edges 1
+    assert_eq!(
+      widths.iter().filter(|&&width| width == MAX_WIDTH).count(),
+      1
+    );
+    assert_eq!(
+      widths.iter().filter(|&&width| width == MIN_WIDTH).count(),
+      4
+    );
+  }
+
+  #[test]
+  fn test_node_style() {
+    let mut graph = diamond();
+    let (number, _) = graph.add_node(7u64);
+    let root = graph
+      .nodes()
+      .iter()
+      .find(|(_, node)| node.data() == &NodeData::from("Root"))
+      .map(|(&uuid, _)| uuid)
+      .unwrap();
+    graph.add_child(root, number).unwrap();
+    let options = RenderOptions {
+      rankdir: RankDir::Lr,
+      shapes: "text=ellipse, number=hexagon".parse().unwrap(),
+      color_by_depth: true,
+      show_uuid: true,
+      ..Default::default()
+    };
+    let dot_output = graph.dot_with(options.clone()).to_string();
+    dot_parser::ast::Graph::try_from(dot_output.as_str()).expect("DOT format is invalid");
+    assert!(dot_output.contains("  graph [rankdir = LR]\n"));
+    let short = ShortUuid::from_uuid(&root);
+    assert!(dot_output.contains(&format!(
+      "  \"{short}\" [shape = ellipse, label = \"Root\\n{short}\", style = filled, fillcolor = \
+       \"#fbb4ae\"];\n"
+    )));
+    assert!(dot_output.contains("[shape = hexagon, label = \"7\\n"));
+    assert_eq!(dot_output.matches("fillcolor = \"#b3cde3\"").count(), 3);
+
+    let mermaid_output = graph.mermaid_with(options).to_string();
+    assert!(mermaid_output.contains("flowchart LR\n"));
+    assert!(mermaid_output.contains(&format!("  {short}([\"Root<br>{short}\"])")));
+    assert!(mermaid_output.contains("{{\"7<br>"));
+    assert_eq!(mermaid_output.matches("  classDef fill").count(), 3);
+    assert!(mermaid_output.contains(&format!("  class {short} fill")));
+
+    // the default is unchanged
+    assert!(graph.dot().to_string().contains("  graph [rankdir = TB]\n"));
+    assert!(!graph.mermaid().to_string().contains("(["));
+    assert!("text=star".parse::<NodeShapes>().is_err());
+    assert!("edge=box".parse::<NodeShapes>().is_err());
+    assert_eq!("".parse(), Ok(NodeShapes::default()));
+  }
+}
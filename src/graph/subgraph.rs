@@ -0,0 +1,97 @@
+use std::collections::HashSet;
+
+use uuid::Uuid;
+
+use super::{
+  AcyclicGraph,
+  Error,
+};
+
+impl AcyclicGraph {
+  /// The branch of `root`, the node and its descendants with the edges and
+  /// edge data between them, to render a part of a graph too big to render
+  /// whole
+  pub fn subgraph(&self, root: Uuid) -> Result<AcyclicGraph, Error> {
+    let mut nodes = self.descendants(root)?;
+    nodes.insert(root);
+    Ok(self.induced_subgraph(&nodes))
+  }
+
+  /// The nodes of `nodes` with only the edges between them, the other UUIDs
+  /// are ignored. The nodes keep their UUID, data, level and timestamp.
+  pub fn induced_subgraph(&self, nodes: &HashSet<Uuid>) -> AcyclicGraph {
+    let mut graph = AcyclicGraph::new(self.name.clone());
+    graph.uuids = self.uuids.clone();
+    for (&uuid, node) in &self.nodes {
+      if !nodes.contains(&uuid) {
+        continue;
+      }
+      let mut node = node.clone();
+      node.childs.retain(|child| nodes.contains(child));
+      graph.nodes.insert(uuid, node);
+    }
+    graph.edge_data = self
+      .edge_data
+      .iter()
+      .filter(|((parent, child), _)| nodes.contains(parent) && nodes.contains(child))
+      .map(|(&edge, data)| (edge, data.clone()))
+      .collect();
+    if self.unique_names() {
+      graph
+        .enable_unique_names()
+        .expect("a subset of unique names is unique");
+    }
+    // the nodes were inserted with their edges
+    graph.rebuild_order();
+    graph
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_subgraph() {
+    // root -> a -> c, root -> b -> c, b -> d
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let (a, _) = graph.add_node("A");
+    let (b, _) = graph.add_node("B");
+    let (c, _) = graph.add_node("C");
+    let (d, _) = graph.add_node("D");
+    for (parent, child) in [(root, a), (root, b), (a, c), (b, c), (b, d)] {
+      graph.add_child(parent, child).unwrap();
+    }
+    graph
+      .set_edge_data(
+        b,
+        c,
+        serde_json::Map::from_iter([("weight".to_string(), 2.into())]),
+      )
+      .unwrap();
+
+    let branch = graph.subgraph(b).unwrap();
+    assert_eq!(branch.name(), "Test Graph");
+    assert_eq!(
+      branch.nodes().keys().copied().collect::<HashSet<_>>(),
+      HashSet::from([b, c, d])
+    );
+    assert_eq!(branch.nodes()[&b].childs(), &HashSet::from([c, d]));
+    assert_eq!(branch.nodes()[&c].data(), graph.nodes()[&c].data());
+    assert_eq!(branch.edge_data(b, c), graph.edge_data(b, c));
+    assert_eq!(branch.topological_sort().first(), Some(&b));
+    assert_eq!(graph.subgraph(root).unwrap().nodes(), graph.nodes());
+    assert_eq!(
+      graph.subgraph(Uuid::nil()),
+      Err(Error::UuidNotFound { uuid: Uuid::nil() })
+    );
+
+    // a and b lose their edges to c and d, an unknown UUID is ignored
+    let induced = graph.induced_subgraph(&HashSet::from([a, b, Uuid::nil()]));
+    assert_eq!(induced.nodes().len(), 2);
+    assert!(induced.nodes()[&a].childs().is_empty());
+    assert!(induced.nodes()[&b].childs().is_empty());
+    assert_eq!(induced.edge_data(b, c), None);
+  }
+}
@@ -0,0 +1,144 @@
+use std::sync::{
+  PoisonError,
+  RwLock,
+  RwLockReadGuard,
+  RwLockWriteGuard,
+};
+
+use rand::RngCore;
+use uuid::Uuid;
+
+use super::{
+  AcyclicGraph,
+  Error,
+  NodeData,
+};
+
+/// An [`AcyclicGraph`] shared between threads, any number of readers can run
+/// queries on [`SyncAcyclicGraph::read`] while writers append nodes and edges
+/// through `&self`
+#[derive(Debug)]
+pub struct SyncAcyclicGraph {
+  graph: RwLock<AcyclicGraph>,
+}
+
+// a write never leave the graph half modified, a panic of another thread
+// doesn't make it unusable
+impl SyncAcyclicGraph {
+  pub fn new(graph: AcyclicGraph) -> Self {
+    Self {
+      graph: RwLock::new(graph),
+    }
+  }
+
+  /// Shared access for queries, writers wait until the guard is dropped
+  pub fn read(&self) -> RwLockReadGuard<'_, AcyclicGraph> {
+    self.graph.read().unwrap_or_else(PoisonError::into_inner)
+  }
+
+  /// See [`AcyclicGraph::try_add_node_with_rng`], the UUID follows the UUID
+  /// version of the graph
+  pub fn add_node_with_rng(
+    &self, data: impl Into<NodeData>, rng: &mut impl RngCore,
+  ) -> Result<Uuid, Error> {
+    let mut graph = self.write();
+    graph.try_add_node_with_rng(data, rng).map(|(uuid, _)| uuid)
+  }
+
+  /// See [`AcyclicGraph::try_add_node`], a name already used in unique names
+  /// mode is an error instead of a panic
+  pub fn add_node(&self, data: impl Into<NodeData>) -> Result<Uuid, Error> {
+    let mut graph = self.write();
+    graph.try_add_node(data).map(|(uuid, _)| uuid)
+  }
+
+  pub fn add_child(&self, parent: Uuid, child: Uuid) -> Result<(), Error> {
+    self.write().add_child(parent, child)
+  }
+
+  pub fn remove_child(&self, parent: Uuid, child: Uuid) -> Result<(), Error> {
+    self.write().remove_child(parent, child)
+  }
+
+  /// A copy of the current graph, later writes don't change it
+  pub fn to_graph(&self) -> AcyclicGraph {
+    self.read().clone()
+  }
+
+  pub fn into_inner(self) -> AcyclicGraph {
+    self
+      .graph
+      .into_inner()
+      .unwrap_or_else(PoisonError::into_inner)
+  }
+
+  fn write(&self) -> RwLockWriteGuard<'_, AcyclicGraph> {
+    self.graph.write().unwrap_or_else(PoisonError::into_inner)
+  }
+}
+
+impl From<AcyclicGraph> for SyncAcyclicGraph {
+  fn from(graph: AcyclicGraph) -> Self {
+    Self::new(graph)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::thread;
+
+  use super::*;
+  use crate::graph::UuidVersion;
+
+  #[test]
+  fn test_concurrent_reads() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let graph = SyncAcyclicGraph::new(graph);
+
+    thread::scope(|scope| {
+      scope.spawn(|| {
+        let mut parent = root;
+        for i in 0..100u64 {
+          let child = graph.add_node(i).unwrap();
+          graph.add_child(parent, child).unwrap();
+          parent = child;
+        }
+      });
+      for _ in 0..4 {
+        scope.spawn(|| {
+          for _ in 0..100 {
+            let graph = graph.read();
+            // a reader never see a half written edge
+            assert_eq!(graph.check_invariants(), Ok(()));
+            let sorted = graph.topological_generations().flatten().count();
+            assert_eq!(sorted, graph.nodes().len());
+          }
+        });
+      }
+    });
+
+    let graph = graph.into_inner();
+    assert_eq!(graph.nodes().len(), 101);
+    assert_eq!(graph.depths().values().max(), Some(&100));
+  }
+
+  #[test]
+  fn test_add_node() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    graph.set_uuid_version(UuidVersion::V7);
+    graph.enable_unique_names().unwrap();
+    let graph = SyncAcyclicGraph::new(graph);
+
+    let a = graph.add_node("A").unwrap();
+    let b = graph.add_node("B").unwrap();
+    assert!(a < b);
+    assert_eq!(a.get_version(), Some(uuid::Version::SortRand));
+    // the lock is released by the error
+    assert!(matches!(
+      graph.add_node("A"),
+      Err(Error::NameAlreadyExists { .. })
+    ));
+    assert_eq!(graph.read().nodes().len(), 2);
+  }
+}
@@ -0,0 +1,72 @@
+use std::fmt::{
+  self,
+  Display,
+  Formatter,
+};
+
+use super::{
+  AcyclicGraph,
+  RenderOptions,
+  sanitize::LineEscape,
+};
+
+impl AcyclicGraph {
+  /// One node per line in the order of [`AcyclicGraph::topological_sort`],
+  /// its short UUID then its label
+  pub fn topo_with(&self, options: RenderOptions) -> Topo<'_> {
+    Topo {
+      graph: self,
+      options,
+    }
+  }
+}
+
+pub struct Topo<'a> {
+  graph: &'a AcyclicGraph,
+  options: RenderOptions,
+}
+
+impl Display for Topo<'_> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let depths = self.options.depths(self.graph);
+    for uuid in self.graph.topological_sort() {
+      let node = &self.graph.nodes[&uuid];
+      write!(f, "{}", self.options.id(&uuid))?;
+      if let Some(label) = self.options.label(uuid, node, depths.as_ref()) {
+        write!(f, " {}", LineEscape(&label))?;
+      }
+      writeln!(f)?;
+    }
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{
+    SeedableRng,
+    rngs::StdRng,
+  };
+
+  use super::*;
+
+  #[test]
+  fn test_topo() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (parent_uuid, _) = graph.add_node_with_rng("Parent", &mut rng);
+    let (child_uuid, _) = graph.add_node_with_rng("Child", &mut rng);
+    let (other_uuid, _) = graph.add_node_with_rng(42, &mut rng);
+    graph.add_child(parent_uuid, child_uuid).unwrap();
+    graph.add_child(parent_uuid, other_uuid).unwrap();
+
+    let expected_output = r###"m43pF1xXxnZvhCY1VeAnMV Parent
+cDe6M3HmMtiJnhL4ihtnyx Child
+r1eLoQWPBenPRfbYKAqer2 42
+"###;
+    pretty_assertions::assert_eq!(
+      graph.topo_with(RenderOptions::default()).to_string(),
+      expected_output
+    );
+  }
+}
@@ -0,0 +1,451 @@
+use std::collections::{
+  HashMap,
+  HashSet,
+};
+
+use uuid::Uuid;
+
+use super::{
+  AcyclicGraph,
+  Error,
+  NodeMap,
+};
+
+// a removed node or position
+const NONE: u32 = u32::MAX;
+
+/// A topological order of the nodes kept up to date by the edge insertions,
+/// the algorithm of Pearce and Kelly. An edge that already goes forward is
+/// accepted in constant time, otherwise only the descendants of the child
+/// and the ancestors of the parent placed between them are searched and
+/// swapped, where a search from the child would go through all its
+/// descendants.
+///
+/// The nodes are known by their ID in the [`NodeMap`], so the positions are
+/// vectors of u32 rather than maps keyed by UUID and the parents are linked
+/// lists in a single vector rather than a vector per node, which matters for
+/// graphs of millions of nodes.
+///
+/// The removals keep it valid, the other changes made without
+/// [`AcyclicGraph::add_child`], like an undo or an import, invalidate it and
+/// it's rebuilt at the next insertion.
+#[derive(Debug, Clone, Default)]
+pub(super) struct TopoOrder {
+  // the position of each ID
+  positions: Vec<u32>,
+  // the ID at each position, a removed node leaves a hole until a compaction
+  slots: Vec<u32>,
+  // the nodes in the order
+  len: usize,
+  // the first link of the parents of each ID, for the search of the
+  // ancestors
+  first: Vec<u32>,
+  links: Vec<Link>,
+  // the links still used, a removed edge keeps its link until a compaction
+  edges: usize,
+  valid: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Link {
+  parent: u32,
+  next: u32,
+}
+
+impl TopoOrder {
+  /// From the nodes in a topological order
+  pub(super) fn new(nodes: &NodeMap, order: Vec<Uuid>) -> Self {
+    let order = order.iter().map(|uuid| nodes.id(uuid).unwrap()).collect();
+    let edges = nodes.iter().flat_map(|(uuid, node)| {
+      let parent = nodes.id(uuid).unwrap();
+      node
+        .childs
+        .iter()
+        .map(move |child| (parent, nodes.id(child).unwrap()))
+    });
+    TopoOrder::from_ids(nodes.id_bound(), order, edges)
+  }
+
+  // `bound` is above every ID
+  fn from_ids(bound: usize, order: Vec<u32>, edges: impl Iterator<Item = (u32, u32)>) -> Self {
+    let mut positions = vec![NONE; bound];
+    for (position, &id) in order.iter().enumerate() {
+      positions[id as usize] = position as u32;
+    }
+    let mut topo = TopoOrder {
+      positions,
+      len: order.len(),
+      slots: order,
+      first: vec![NONE; bound],
+      links: Vec::new(),
+      edges: 0,
+      valid: true,
+    };
+    for (parent, child) in edges {
+      topo.link(parent, child);
+    }
+    topo
+  }
+
+  pub(super) fn is_valid(&self) -> bool {
+    self.valid
+  }
+
+  pub(super) fn invalidate(&mut self) {
+    *self = TopoOrder::default();
+  }
+
+  /// A new node goes last, it has no edge yet
+  pub(super) fn push(&mut self, nodes: &NodeMap, uuid: Uuid) {
+    if self.valid {
+      let id = nodes.id(&uuid).unwrap() as usize;
+      if self.positions.len() <= id {
+        self.positions.resize(id + 1, NONE);
+        self.first.resize(id + 1, NONE);
+      }
+      self.positions[id] = self.slots.len() as u32;
+      self.slots.push(id as u32);
+      self.len += 1;
+    }
+  }
+
+  /// The edges from the node are removed before, the node is still in
+  /// `nodes`
+  pub(super) fn remove(&mut self, nodes: &NodeMap, uuid: Uuid) {
+    if self.valid
+      && let Some(id) = nodes.id(&uuid)
+    {
+      let id = id as usize;
+      self.slots[self.positions[id] as usize] = NONE;
+      self.positions[id] = NONE;
+      self.len -= 1;
+      let mut link = std::mem::replace(&mut self.first[id], NONE);
+      while link != NONE {
+        self.edges -= 1;
+        link = self.links[link as usize].next;
+      }
+      self.compact();
+    }
+  }
+
+  pub(super) fn remove_edge(&mut self, nodes: &NodeMap, parent: Uuid, child: Uuid) {
+    if !self.valid {
+      return;
+    }
+    let (Some(parent), Some(child)) = (nodes.id(&parent), nodes.id(&child)) else {
+      return;
+    };
+    let mut previous = None;
+    let mut link = self.first[child as usize];
+    while link != NONE {
+      let Link {
+        parent: other,
+        next,
+      } = self.links[link as usize];
+      if other == parent {
+        match previous {
+          Some(previous) => self.links[previous as usize].next = next,
+          None => self.first[child as usize] = next,
+        }
+        self.edges -= 1;
+        self.compact();
+        return;
+      }
+      previous = Some(link);
+      link = next;
+    }
+  }
+
+  // the holes and the removed edges are dropped once they are the majority
+  fn compact(&mut self) {
+    if self.slots.len() > 2 * self.len + 64 || self.links.len() > 2 * self.edges + 64 {
+      let order: Vec<u32> = self
+        .slots
+        .iter()
+        .copied()
+        .filter(|&id| id != NONE)
+        .collect();
+      let edges: Vec<(u32, u32)> = order
+        .iter()
+        .flat_map(|&child| self.parent_ids(child).map(move |parent| (parent, child)))
+        .collect();
+      *self = TopoOrder::from_ids(self.positions.len(), order, edges.into_iter());
+    }
+  }
+
+  fn link(&mut self, parent: u32, child: u32) {
+    let next = std::mem::replace(&mut self.first[child as usize], self.links.len() as u32);
+    self.links.push(Link { parent, next });
+    self.edges += 1;
+  }
+
+  fn parent_ids(&self, id: u32) -> impl Iterator<Item = u32> + '_ {
+    let mut link = self.first[id as usize];
+    std::iter::from_fn(move || {
+      let current = self.links.get(link as usize)?;
+      link = current.next;
+      Some(current.parent)
+    })
+  }
+
+  /// Position of a node, None while the order is invalid
+  pub(super) fn position(&self, nodes: &NodeMap, uuid: Uuid) -> Option<usize> {
+    if !self.valid {
+      return None;
+    }
+    let id = nodes.id(&uuid)?;
+    Some(self.positions[id as usize] as usize)
+  }
+
+  /// Parents of a node, none while the order is invalid
+  pub(super) fn parents_of<'a>(
+    &'a self, nodes: &'a NodeMap, uuid: Uuid,
+  ) -> impl Iterator<Item = Uuid> + 'a {
+    nodes
+      .id(&uuid)
+      .filter(|_| self.valid)
+      .into_iter()
+      .flat_map(|id| self.parent_ids(id))
+      .map(|parent| nodes.uuid(parent))
+  }
+
+  /// The depth of each node reachable from `root` by its longest path, in
+  /// one pass over the order with a depth per ID rather than maps keyed by
+  /// UUID. The order is valid and the root exists.
+  pub(super) fn longest_paths(&self, nodes: &NodeMap, root: Uuid) -> Vec<(Uuid, usize)> {
+    let mut depths = vec![NONE; self.positions.len()];
+    let root = nodes.id(&root).unwrap();
+    depths[root as usize] = 0;
+    for &id in &self.slots[self.positions[root as usize] as usize..] {
+      if id == NONE || depths[id as usize] == NONE {
+        continue;
+      }
+      let depth = depths[id as usize] + 1;
+      for child in &nodes.node(id).childs {
+        let child = &mut depths[nodes.id(child).unwrap() as usize];
+        *child = match *child {
+          NONE => depth,
+          other => other.max(depth),
+        };
+      }
+    }
+    self
+      .slots
+      .iter()
+      .filter(|&&id| id != NONE && depths[id as usize] != NONE)
+      .map(|&id| (nodes.uuid(id), depths[id as usize] as usize))
+      .collect()
+  }
+
+  /// Move the nodes so `parent` comes before `child`, nothing is changed if
+  /// the edge would close a cycle. Both nodes exist and the order is valid.
+  pub(super) fn add_edge(
+    &mut self, nodes: &NodeMap, parent: Uuid, child: Uuid,
+  ) -> Result<(), Error> {
+    let cycle = Error::Cycle {
+      src: parent,
+      dst: child,
+    };
+    if parent == child {
+      return Err(cycle);
+    }
+    let (parent, child) = (nodes.id(&parent).unwrap(), nodes.id(&child).unwrap());
+    let (lower, upper) = (
+      self.positions[child as usize],
+      self.positions[parent as usize],
+    );
+    if lower < upper {
+      // the descendants of the child placed before the parent, reaching the
+      // parent means a cycle
+      let forward = self
+        .search(
+          child,
+          |current| {
+            nodes
+              .node(current)
+              .childs
+              .iter()
+              .map(|child| nodes.id(child).unwrap())
+          },
+          |position| position < upper,
+          Some(parent),
+        )
+        .ok_or(cycle)?;
+      // the ancestors of the parent placed after the child
+      let backward = self
+        .search(
+          parent,
+          |current| self.parent_ids(current),
+          |position| position > lower,
+          None,
+        )
+        .unwrap_or_default();
+
+      // the ancestors take the first of their positions, the descendants
+      // the last ones, each group keeps its order
+      let mut positions: Vec<u32> = backward
+        .iter()
+        .chain(&forward)
+        .map(|&id| self.positions[id as usize])
+        .collect();
+      positions.sort_unstable();
+      for (id, position) in backward.into_iter().chain(forward).zip(positions) {
+        self.positions[id as usize] = position;
+        self.slots[position as usize] = id;
+      }
+    }
+    self.link(parent, child);
+    Ok(())
+  }
+
+  // the IDs reached from `start` through the positions kept by `within`
+  // sorted by position, None if `stop` is reached
+  fn search<I: Iterator<Item = u32>>(
+    &self, start: u32, next: impl Fn(u32) -> I, within: impl Fn(u32) -> bool, stop: Option<u32>,
+  ) -> Option<Vec<u32>> {
+    let mut reached = vec![start];
+    let mut visited = HashSet::from([start]);
+    let mut stack = vec![start];
+    while let Some(current) = stack.pop() {
+      for other in next(current) {
+        if Some(other) == stop {
+          return None;
+        }
+        if within(self.positions[other as usize]) && visited.insert(other) {
+          reached.push(other);
+          stack.push(other);
+        }
+      }
+    }
+    reached.sort_unstable_by_key(|&id| self.positions[id as usize]);
+    Some(reached)
+  }
+}
+
+/// The parents of each node, see [`AcyclicGraph::parent_index`]
+pub(super) enum ParentIndex<'a> {
+  Order(&'a TopoOrder, &'a NodeMap),
+  Map(HashMap<Uuid, HashSet<Uuid>>),
+}
+
+impl ParentIndex<'_> {
+  pub(super) fn get(&self, uuid: Uuid) -> Vec<Uuid> {
+    match self {
+      ParentIndex::Order(order, nodes) => order.parents_of(nodes, uuid).collect(),
+      ParentIndex::Map(parents) => parents.get(&uuid).into_iter().flatten().copied().collect(),
+    }
+  }
+}
+
+impl AcyclicGraph {
+  // after the nodes were inserted with their edges
+  pub(super) fn rebuild_order(&mut self) {
+    self.order = TopoOrder::new(&self.nodes, self.topological_sort());
+  }
+
+  // the parents of each node, kept by the order while it's valid
+  pub(super) fn parent_index(&self) -> ParentIndex<'_> {
+    match self.order.valid {
+      true => ParentIndex::Order(&self.order, &self.nodes),
+      false => ParentIndex::Map(self.parents()),
+    }
+  }
+
+  // the position of a node in the order while it's valid
+  pub(super) fn order_position(&self, uuid: Uuid) -> Option<usize> {
+    self.order.position(&self.nodes, uuid)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_topo_order() {
+    // each new edge goes backward in the order of addition
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let uuids: Vec<Uuid> = (0..6).map(|i| graph.add_node(i).0).collect();
+    for window in uuids.windows(2) {
+      graph.add_child(window[1], window[0]).unwrap();
+    }
+    assert!(graph.order.is_valid());
+    let order = |graph: &AcyclicGraph| -> Vec<Uuid> {
+      graph
+        .order
+        .slots
+        .iter()
+        .filter(|&&id| id != NONE)
+        .map(|&id| graph.nodes.uuid(id))
+        .collect()
+    };
+    let expected: Vec<Uuid> = uuids.iter().rev().copied().collect();
+    assert_eq!(order(&graph), expected);
+
+    assert!(matches!(
+      graph.add_child(uuids[0], uuids[5]),
+      Err(Error::Cycle { .. })
+    ));
+    assert!(matches!(
+      graph.add_child(uuids[2], uuids[2]),
+      Err(Error::Cycle { .. })
+    ));
+    // unchanged by the refused edges
+    assert_eq!(order(&graph), expected);
+
+    // a removed node leaves a hole, the order stays valid
+    graph.remove_node(uuids[3]).unwrap();
+    graph.add_child(uuids[2], uuids[4]).unwrap();
+    graph.add_child(uuids[1], uuids[5]).unwrap();
+    assert_eq!(graph.check_invariants(), Ok(()));
+    let position = |uuid: &Uuid| graph.order_position(*uuid).unwrap();
+    for (parent, node) in graph.nodes() {
+      for child in node.childs() {
+        assert!(position(parent) < position(child));
+      }
+    }
+
+    // an undo invalidates it, the next insertion rebuilds it
+    let snapshot = graph.snapshot();
+    graph.remove_child(uuids[1], uuids[0]).unwrap();
+    graph.restore(snapshot).unwrap();
+    assert!(!graph.order.is_valid());
+    assert!(matches!(
+      graph.add_child(uuids[0], uuids[1]),
+      Err(Error::Cycle { .. })
+    ));
+    assert!(graph.order.is_valid());
+
+    // the holes are compacted once they are the majority
+    let chain: Vec<Uuid> = (0..200).map(|i| graph.add_node(i).0).collect();
+    for window in chain.windows(2) {
+      graph.add_child(window[0], window[1]).unwrap();
+    }
+    for window in chain[..150].windows(2) {
+      graph.remove_child(window[0], window[1]).unwrap();
+    }
+    for &uuid in &chain[..149] {
+      graph.remove_node(uuid).unwrap();
+    }
+    assert!(graph.order.slots.len() < 2 * graph.nodes().len() + 64);
+    assert!(graph.order.links.len() < 2 * graph.order.edges + 64);
+    assert_eq!(
+      graph
+        .order
+        .parents_of(&graph.nodes, chain[151])
+        .collect::<Vec<_>>(),
+      [chain[150]]
+    );
+    assert!(matches!(
+      graph.add_child(chain[199], chain[150]),
+      Err(Error::Cycle { .. })
+    ));
+    graph.add_child(chain[150], uuids[0]).unwrap();
+    let position = |uuid: &Uuid| graph.order_position(*uuid).unwrap();
+    for (parent, node) in graph.nodes() {
+      for child in node.childs() {
+        assert!(position(parent) < position(child));
+      }
+    }
+  }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use super::AcyclicGraph;
+
+/// Descendants of each node as bitsets, the nodes are indexed by UUID order
+pub(super) struct Reach {
+  pub(super) uuids: Vec<Uuid>,
+  pub(super) indexes: HashMap<Uuid, usize>,
+  pub(super) bits: Vec<Vec<u64>>,
+}
+
+impl Reach {
+  /// Indexes of the descendants of a node in increasing order
+  pub(super) fn descendants(&self, from: usize) -> impl Iterator<Item = usize> + '_ {
+    std::iter::successors(self.next_descendant(from, 0), move |&to| {
+      self.next_descendant(from, to + 1)
+    })
+  }
+
+  /// The first descendant of a node with an index of at least `start`, a word
+  /// of 64 nodes is skipped at once
+  pub(super) fn next_descendant(&self, from: usize, start: usize) -> Option<usize> {
+    let row = &self.bits[from];
+    let mut word = start / 64;
+    let mut bits = row.get(word)? & (u64::MAX << (start % 64));
+    loop {
+      if bits != 0 {
+        return Some(word * 64 + bits.trailing_zeros() as usize);
+      }
+      word += 1;
+      bits = *row.get(word)?;
+    }
+  }
+}
+
+impl AcyclicGraph {
+  // the descendants of the nodes sorted by UUID, computed from the leaves up.
+  // Quadratic memory.
+  pub(super) fn reach(&self) -> Reach {
+    let (uuids, indexes) = self.indexes();
+    let words = uuids.len().div_ceil(64);
+    let mut bits = vec![vec![0u64; words]; uuids.len()];
+    let order: Vec<usize> = self
+      .topological_generations()
+      .flatten()
+      .map(|uuid| indexes[&uuid])
+      .collect();
+    for &u in order.iter().rev() {
+      let mut reach = vec![0u64; words];
+      for child in &self.nodes[&uuids[u]].childs {
+        let c = indexes[child];
+        reach[c / 64] |= 1 << (c % 64);
+        for (bit, child_bit) in reach.iter_mut().zip(&bits[c]) {
+          *bit |= child_bit;
+        }
+      }
+      bits[u] = reach;
+    }
+    Reach {
+      uuids,
+      indexes,
+      bits,
+    }
+  }
+
+  /// Remove every edge implied by a longer path, the graph keeps the same
+  /// reachability with the fewest edges. Return the number of removed edges,
+  /// their data is dropped. Quadratic memory.
+  pub fn transitive_reduction(&mut self) -> usize {
+    let reach = self.reach();
+    let words = reach.uuids.len().div_ceil(64);
+    let mut redundant = Vec::new();
+    for (u, uuid) in reach.uuids.iter().enumerate() {
+      // what the childs reach, a child in it is reached by a longer path
+      let mut below = vec![0u64; words];
+      for child in &self.nodes[uuid].childs {
+        for (bit, child_bit) in below.iter_mut().zip(&reach.bits[reach.indexes[child]]) {
+          *bit |= child_bit;
+        }
+      }
+      for child in &self.nodes[uuid].childs {
+        let c = reach.indexes[child];
+        if below[c / 64] & (1 << (c % 64)) != 0 {
+          redundant.push((u, c));
+        }
+      }
+    }
+    redundant.sort_unstable();
+    for &(parent, child) in &redundant {
+      // the edges come from the graph
+      let _ = self.remove_child(reach.uuids[parent], reach.uuids[child]);
+    }
+    redundant.len()
+  }
+
+  /// Add an edge from every node to each of its descendants, a node can then
+  /// reach another one iff it's one of its childs. Return the number of added
+  /// edges. Quadratic memory.
+  pub fn transitive_closure(&mut self) -> usize {
+    let reach = self.reach();
+    let mut added = 0;
+    for (u, &uuid) in reach.uuids.iter().enumerate() {
+      for d in reach.descendants(u) {
+        // an edge to a descendant can't close a cycle
+        if self.add_child(uuid, reach.uuids[d]).is_ok() {
+          added += 1;
+        }
+      }
+    }
+    added
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_transitive() {
+    // root -> a -> b -> c with the shortcuts root -> b, root -> c and a -> c
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let [root, a, b, c] = ["Root", "A", "B", "C"].map(|name| graph.add_node(name).0);
+    for (parent, child) in [(root, a), (a, b), (b, c), (root, b), (root, c), (a, c)] {
+      graph.add_child(parent, child).unwrap();
+    }
+    let edges = |graph: &AcyclicGraph| -> usize {
+      graph.nodes().values().map(|node| node.childs().len()).sum()
+    };
+
+    let mut closure = graph.clone();
+    assert_eq!(closure.transitive_closure(), 0);
+    assert_eq!(closure, graph);
+
+    assert_eq!(graph.transitive_reduction(), 3);
+    assert_eq!(edges(&graph), 3);
+    for (parent, child) in [(root, a), (a, b), (b, c)] {
+      assert!(graph.nodes()[&parent].childs().contains(&child));
+    }
+    assert_eq!(graph.transitive_reduction(), 0);
+
+    assert_eq!(graph.transitive_closure(), 3);
+    assert_eq!(graph, closure);
+
+    // two paths of the same length aren't redundant
+    let mut diamond = AcyclicGraph::new("Diamond");
+    let [root, a, b, c] = ["Root", "A", "B", "C"].map(|name| diamond.add_node(name).0);
+    for (parent, child) in [(root, a), (root, b), (a, c), (b, c)] {
+      diamond.add_child(parent, child).unwrap();
+    }
+    assert_eq!(diamond.transitive_reduction(), 0);
+    assert_eq!(diamond.transitive_closure(), 1);
+  }
+}
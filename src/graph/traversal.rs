@@ -0,0 +1,378 @@
+use std::{
+  cmp::Reverse,
+  collections::{
+    BinaryHeap,
+    HashMap,
+    HashSet,
+    VecDeque,
+  },
+};
+
+use itertools::Itertools;
+use rand::Rng;
+use uuid::Uuid;
+
+use super::{
+  AcyclicGraph,
+  Error,
+  Node,
+};
+
+/// A node with its depth, as yielded by the traversals of [`AcyclicGraph`]
+pub type Visit<'a> = (Uuid, &'a Node, usize);
+
+/// Iterator over the topological generations of a graph, see
+/// [`AcyclicGraph::topological_generations`]
+#[derive(Debug, Clone)]
+pub struct TopologicalGenerations<'a> {
+  graph: &'a AcyclicGraph,
+  in_degrees: HashMap<Uuid, usize>,
+  current: Vec<Uuid>,
+}
+
+impl<'a> TopologicalGenerations<'a> {
+  pub(super) fn new(graph: &'a AcyclicGraph) -> Self {
+    let in_degrees = graph.in_degrees();
+    let mut current: Vec<Uuid> = in_degrees
+      .iter()
+      .filter(|(_, degree)| **degree == 0)
+      .map(|(&uuid, _)| uuid)
+      .collect();
+    current.sort();
+
+    Self {
+      graph,
+      in_degrees,
+      current,
+    }
+  }
+}
+
+impl Iterator for TopologicalGenerations<'_> {
+  type Item = Vec<Uuid>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    if self.current.is_empty() {
+      return None;
+    }
+
+    let mut next = Vec::new();
+    for uuid in &self.current {
+      for child in &self.graph.nodes[uuid].childs {
+        if let Some(degree) = self.in_degrees.get_mut(child) {
+          *degree -= 1;
+          if *degree == 0 {
+            next.push(*child);
+          }
+        }
+      }
+    }
+    next.sort();
+
+    Some(std::mem::replace(&mut self.current, next))
+  }
+}
+
+/// Breadth first iterator from a node, see [`AcyclicGraph::bfs`]
+#[derive(Debug, Clone)]
+pub struct Bfs<'a> {
+  graph: &'a AcyclicGraph,
+  queue: VecDeque<(Uuid, usize)>,
+  visited: HashSet<Uuid>,
+}
+
+impl<'a> Iterator for Bfs<'a> {
+  type Item = Visit<'a>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    let (uuid, depth) = self.queue.pop_front()?;
+    let node = &self.graph.nodes[&uuid];
+    for &child in node.childs.iter().sorted() {
+      if self.visited.insert(child) {
+        self.queue.push_back((child, depth + 1));
+      }
+    }
+    Some((uuid, node, depth))
+  }
+}
+
+/// Depth first post-order iterator from a node, see
+/// [`AcyclicGraph::dfs_post_order`]
+#[derive(Debug, Clone)]
+pub struct DfsPostOrder<'a> {
+  graph: &'a AcyclicGraph,
+  // each node of the current path with its childs left to visit, in reverse
+  stack: Vec<(Uuid, usize, Vec<Uuid>)>,
+  visited: HashSet<Uuid>,
+}
+
+impl<'a> DfsPostOrder<'a> {
+  fn push(&mut self, uuid: Uuid, depth: usize) {
+    let childs = self.graph.nodes[&uuid]
+      .childs
+      .iter()
+      .copied()
+      .sorted()
+      .rev();
+    self.stack.push((uuid, depth, childs.collect()));
+  }
+}
+
+impl<'a> Iterator for DfsPostOrder<'a> {
+  type Item = Visit<'a>;
+
+  fn next(&mut self) -> Option<Self::Item> {
+    loop {
+      let (uuid, depth, childs) = self.stack.last_mut()?;
+      let (uuid, depth) = (*uuid, *depth);
+      match childs.pop() {
+        Some(child) => {
+          if self.visited.insert(child) {
+            self.push(child, depth + 1);
+          }
+        }
+        None => {
+          self.stack.pop();
+          return Some((uuid, &self.graph.nodes[&uuid], depth));
+        }
+      }
+    }
+  }
+}
+
+impl AcyclicGraph {
+  /// The nodes reachable from `root` breadth first, `root` first, with the
+  /// length of the shortest path from `root`. The childs of a node are
+  /// visited by UUID order.
+  pub fn bfs(&self, root: Uuid) -> Result<Bfs<'_>, Error> {
+    self.get_node(root)?;
+    Ok(Bfs {
+      graph: self,
+      queue: VecDeque::from([(root, 0)]),
+      visited: HashSet::from([root]),
+    })
+  }
+
+  /// The nodes reachable from `root` depth first, each one after all its
+  /// descendants so `root` last, with the length of the path that first
+  /// reached it. The childs of a node are visited by UUID order.
+  pub fn dfs_post_order(&self, root: Uuid) -> Result<DfsPostOrder<'_>, Error> {
+    self.get_node(root)?;
+    let mut dfs = DfsPostOrder {
+      graph: self,
+      stack: Vec::new(),
+      visited: HashSet::from([root]),
+    };
+    dfs.push(root, 0);
+    Ok(dfs)
+  }
+
+  /// Every node after its parents, generation by generation as
+  /// [`AcyclicGraph::topological_generations`], with the index of its
+  /// generation, the length of the longest path from a root
+  pub fn iter_topological(&self) -> impl Iterator<Item = Visit<'_>> {
+    self
+      .topological_generations()
+      .enumerate()
+      .flat_map(move |(depth, generation)| {
+        generation
+          .into_iter()
+          .map(move |uuid| (uuid, &self.nodes[&uuid], depth))
+      })
+  }
+
+  /// The nodes reachable from `root` level by level as
+  /// [`AcyclicGraph::levels`], with their level
+  pub fn iter_levels(&self, root: Uuid) -> Result<impl Iterator<Item = Visit<'_>>, Error> {
+    let levels = self.levels(root)?;
+    Ok(
+      levels
+        .into_iter()
+        .enumerate()
+        .flat_map(move |(depth, level)| {
+          level
+            .into_iter()
+            .map(move |uuid| (uuid, &self.nodes[&uuid], depth))
+        }),
+    )
+  }
+
+  /// Every node after its parents, Kahn's algorithm taking the smallest UUID
+  /// among the nodes whose parents are all taken, so the order only depends
+  /// on the graph
+  pub fn topological_sort(&self) -> Vec<Uuid> {
+    let mut in_degrees = self.in_degrees();
+    let mut available: BinaryHeap<Reverse<Uuid>> = in_degrees
+      .iter()
+      .filter(|(_, degree)| **degree == 0)
+      .map(|(&uuid, _)| Reverse(uuid))
+      .collect();
+    let mut order = Vec::with_capacity(self.nodes.len());
+    while let Some(Reverse(uuid)) = available.pop() {
+      order.push(uuid);
+      for child in &self.nodes[&uuid].childs {
+        if let Some(degree) = in_degrees.get_mut(child) {
+          *degree -= 1;
+          if *degree == 0 {
+            available.push(Reverse(*child));
+          }
+        }
+      }
+    }
+    order
+  }
+
+  /// Up to `k` distinct topological orders, each one is built by picking a
+  /// random node among the nodes whose parents are all already picked. Stop
+  /// early when no new order is found after many attempts, a chain for
+  /// example only have one order.
+  pub fn random_topological_orders(&self, k: usize, rng: &mut impl Rng) -> Vec<Vec<Uuid>> {
+    let in_degrees = self.in_degrees();
+    let mut roots: Vec<Uuid> = in_degrees
+      .iter()
+      .filter(|(_, degree)| **degree == 0)
+      .map(|(&uuid, _)| uuid)
+      .collect();
+    roots.sort();
+
+    let mut orders = Vec::new();
+    let mut seen = HashSet::new();
+    let mut attempts = 0;
+    while orders.len() < k && attempts < k.saturating_mul(10).max(100) {
+      attempts += 1;
+
+      let mut in_degrees = in_degrees.clone();
+      let mut available = roots.clone();
+      let mut order = Vec::with_capacity(self.nodes.len());
+      while !available.is_empty() {
+        let uuid = available.swap_remove(rng.random_range(0..available.len()));
+        order.push(uuid);
+        let mut childs: Vec<Uuid> = self.nodes[&uuid].childs.iter().copied().collect();
+        // keep the result reproducible with a seeded RNG
+        childs.sort();
+        for child in childs {
+          let degree = in_degrees.entry(child).or_default();
+          *degree -= 1;
+          if *degree == 0 {
+            available.push(child);
+          }
+        }
+      }
+
+      if seen.insert(order.clone()) {
+        orders.push(order);
+      }
+    }
+
+    orders
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{
+    SeedableRng,
+    rngs::StdRng,
+  };
+
+  use super::*;
+
+  #[test]
+  fn test_topological_generations() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (a, _) = graph.add_node("A");
+    let (b, _) = graph.add_node("B");
+    let (c, _) = graph.add_node("C");
+    let (d, _) = graph.add_node("D");
+    graph.add_child(a, c).unwrap();
+    graph.add_child(b, c).unwrap();
+    graph.add_child(a, d).unwrap();
+    graph.add_child(c, d).unwrap();
+
+    let generations: Vec<_> = graph.topological_generations().collect();
+    let mut first = vec![a, b];
+    first.sort();
+    assert_eq!(generations, vec![first, vec![c], vec![d]]);
+  }
+
+  #[test]
+  fn test_traversals() {
+    // 1 -> 2 -> 4, 1 -> 3 -> 4 -> 5, 1 -> 5
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let [a, b, c, d, e] = [1, 2, 3, 4, 5].map(|i| {
+      graph
+        .add_node_with_uuid(Uuid::from_u128(i), i as u64)
+        .unwrap()
+        .0
+    });
+    for (parent, child) in [(a, b), (a, c), (b, d), (c, d), (d, e), (a, e)] {
+      graph.add_child(parent, child).unwrap();
+    }
+    let visits = |iter: &mut dyn Iterator<Item = Visit<'_>>| {
+      iter
+        .map(|(uuid, node, depth)| {
+          assert_eq!(node, &graph.nodes()[&uuid]);
+          (uuid, depth)
+        })
+        .collect_vec()
+    };
+
+    assert_eq!(
+      visits(&mut graph.bfs(a).unwrap()),
+      [(a, 0), (b, 1), (c, 1), (e, 1), (d, 2)]
+    );
+    assert_eq!(visits(&mut graph.bfs(d).unwrap()), [(d, 0), (e, 1)]);
+    assert_eq!(
+      visits(&mut graph.dfs_post_order(a).unwrap()),
+      [(e, 3), (d, 2), (b, 1), (c, 1), (a, 0)]
+    );
+    assert_eq!(
+      visits(&mut graph.iter_topological()),
+      [(a, 0), (b, 1), (c, 1), (d, 2), (e, 3)]
+    );
+    assert_eq!(
+      visits(&mut graph.iter_levels(b).unwrap()),
+      [(b, 0), (d, 1), (e, 2)]
+    );
+    let missing = Err(Error::UuidNotFound { uuid: Uuid::nil() });
+    assert_eq!(graph.bfs(Uuid::nil()).map(|_| ()), missing);
+    assert_eq!(graph.dfs_post_order(Uuid::nil()).map(|_| ()), missing);
+  }
+
+  #[test]
+  fn test_topological_sort() {
+    // 1 -> 4, 3 -> 2, 2 -> 4: 1 is taken first but 2 waits for 3
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let [a, b, c, d] = [1, 2, 3, 4].map(|i| {
+      graph
+        .add_node_with_uuid(Uuid::from_u128(i), i as u64)
+        .unwrap()
+        .0
+    });
+    for (parent, child) in [(a, d), (c, b), (b, d)] {
+      graph.add_child(parent, child).unwrap();
+    }
+    assert_eq!(graph.topological_sort(), [a, c, b, d]);
+    assert!(AcyclicGraph::new("Empty").topological_sort().is_empty());
+  }
+
+  #[test]
+  fn test_random_topological_orders() {
+    // a -> c, b -> c, c -> d: a and b can be swapped, so 2 orders
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let [a, b, c, d] = ["A", "B", "C", "D"].map(|name| graph.add_node(name).0);
+    for (parent, child) in [(a, c), (b, c), (c, d)] {
+      graph.add_child(parent, child).unwrap();
+    }
+
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut orders = graph.random_topological_orders(5, &mut rng);
+    orders.sort();
+    let mut expected = vec![vec![a, b, c, d], vec![b, a, c, d]];
+    expected.sort();
+    assert_eq!(orders, expected);
+
+    let mut rng = StdRng::seed_from_u64(42);
+    assert_eq!(graph.random_topological_orders(1, &mut rng).len(), 1);
+  }
+}
@@ -0,0 +1,163 @@
+use rand::{
+  Rng,
+  RngCore,
+};
+use short_uuid::ShortUuid;
+use uuid::Uuid;
+
+use super::AcyclicGraph;
+
+/// An UUID or a short UUID as written by the exports. A short UUID of a
+/// random UUID has 19 to 22 characters, a shorter word would be read as a
+/// tiny UUID so it isn't one.
+pub fn parse_uuid(id: &str) -> Option<Uuid> {
+  if let Ok(uuid) = Uuid::parse_str(id) {
+    return Some(uuid);
+  }
+  if !(19..=22).contains(&id.len()) {
+    return None;
+  }
+  let uuid = ShortUuid::parse_str(id).ok()?.to_uuid();
+  (ShortUuid::from_uuid(&uuid).to_string() == id).then_some(uuid)
+}
+
+/// Version of the UUIDs given to the added nodes
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  Default,
+  serde::Serialize,
+  serde::Deserialize
+)]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum UuidVersion {
+  /// Random
+  #[default]
+  V4,
+  /// Time ordered, the timestamp is the rank of creation in milliseconds so
+  /// seeded graphs stay reproducible and sorting by UUID follows creation
+  V7,
+}
+
+/// Maker of the UUIDs of the added nodes
+#[derive(Debug, Clone, Default)]
+pub(super) struct Uuids {
+  version: UuidVersion,
+  // timestamp of the next version 7 UUID
+  clock: u64,
+}
+
+impl Uuids {
+  pub(super) fn next_with_rng(&mut self, rng: &mut impl RngCore) -> Uuid {
+    match self.version {
+      UuidVersion::V4 => uuid::Builder::from_random_bytes(rng.random()).into_uuid(),
+      UuidVersion::V7 => {
+        let uuid = uuid::Builder::from_unix_timestamp_millis(self.clock, &rng.random());
+        self.clock += 1;
+        uuid.into_uuid()
+      }
+    }
+  }
+
+  pub(super) fn next(&mut self) -> Uuid {
+    match self.version {
+      UuidVersion::V4 => Uuid::new_v4(),
+      UuidVersion::V7 => self.next_with_rng(&mut rand::rng()),
+    }
+  }
+}
+
+impl AcyclicGraph {
+  /// Version of the UUIDs of the nodes added from now on, the existing nodes
+  /// keep theirs. The version 7 UUIDs added to a loaded graph come after its
+  /// newest one.
+  pub fn set_uuid_version(&mut self, version: UuidVersion) {
+    self.uuids.version = version;
+    if version == UuidVersion::V7 {
+      // the clock isn't saved with the graph, it restarts after the newest
+      // timestamp
+      let newest = self
+        .nodes
+        .keys()
+        .filter(|uuid| uuid.get_version() == Some(uuid::Version::SortRand))
+        .filter_map(Uuid::get_timestamp)
+        .map(|timestamp| {
+          let (secs, nanos) = timestamp.to_unix();
+          secs * 1000 + u64::from(nanos) / 1_000_000 + 1
+        })
+        .max();
+      self.uuids.clock = self.uuids.clock.max(newest.unwrap_or(0));
+    }
+  }
+
+  pub fn uuid_version(&self) -> UuidVersion {
+    self.uuids.version
+  }
+
+  // kept by the checkpoints of a generation so the resumed one gives the same
+  // version 7 UUIDs
+  #[cfg(feature = "generator")]
+  pub(crate) fn uuid_clock(&self) -> u64 {
+    self.uuids.clock
+  }
+
+  #[cfg(feature = "generator")]
+  pub(crate) fn set_uuid_clock(&mut self, clock: u64) {
+    self.uuids.clock = clock;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{
+    SeedableRng,
+    rngs::StdRng,
+  };
+
+  use super::*;
+
+  #[test]
+  fn test_v7() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut graph = AcyclicGraph::new("Test Graph");
+    graph.set_uuid_version(UuidVersion::V7);
+    let uuids: Vec<Uuid> = (0..100u64)
+      .map(|i| graph.add_node_with_rng(i, &mut rng).0)
+      .collect();
+    assert!(uuids.is_sorted());
+    assert!(
+      uuids
+        .iter()
+        .all(|uuid| uuid.get_version() == Some(uuid::Version::SortRand))
+    );
+    let (uuid, _) = graph.add_node("last");
+    assert!(uuids.iter().all(|other| other < &uuid));
+
+    let mut other = AcyclicGraph::new("Test Graph");
+    other.set_uuid_version(UuidVersion::V7);
+    let mut rng = StdRng::seed_from_u64(42);
+    assert_eq!(other.add_node_with_rng(0, &mut rng).0, uuids[0]);
+
+    // a loaded graph goes on after its newest UUID
+    let json = serde_json::to_string(&graph).unwrap();
+    let mut loaded: AcyclicGraph = serde_json::from_str(&json).unwrap();
+    loaded.set_uuid_version(UuidVersion::V7);
+    let (next, _) = loaded.add_node("next");
+    assert!(loaded.nodes().keys().all(|other| other <= &next));
+    assert_eq!(next.get_timestamp().unwrap().to_unix(), (0, 101_000_000));
+  }
+
+  #[test]
+  fn test_parse_uuid() {
+    let uuid = Uuid::from_u128(0x9B8F_63F1_4C4E_4A3B_8F0E_2D1C_6A5B_4C3D);
+    let short = ShortUuid::from_uuid(&uuid).to_string();
+    assert_eq!(parse_uuid(&short), Some(uuid));
+    assert_eq!(parse_uuid(&uuid.to_string()), Some(uuid));
+    assert_eq!(parse_uuid("nope"), None);
+    assert_eq!(parse_uuid(&format!("{short}0")), None);
+  }
+}
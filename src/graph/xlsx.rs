@@ -0,0 +1,88 @@
+use itertools::Itertools;
+use rust_xlsxwriter::{
+  Format,
+  Workbook,
+  XlsxError,
+};
+
+use super::{
+  AcyclicGraph,
+  NodeData,
+  RenderOptions,
+};
+
+const NODE_HEADERS: [&str; 6] = ["ID", "UUID", "Label", "Level", "Timestamp", "Data"];
+const EDGE_HEADERS: [&str; 4] = ["Parent ID", "Child ID", "Parent", "Child"];
+
+impl AcyclicGraph {
+  /// Excel workbook with a Nodes sheet and an Edges sheet, rows are sorted
+  /// by UUID and nodes are referenced by short UUID or sequential ID
+  pub fn xlsx_with(&self, options: RenderOptions) -> Result<Vec<u8>, XlsxError> {
+    let depths = options.depths(self);
+    let label = |uuid| {
+      options
+        .label(uuid, &self.nodes[&uuid], depths.as_ref())
+        .unwrap_or_default()
+    };
+    let bold = Format::new().set_bold();
+    let mut workbook = Workbook::new();
+
+    let nodes = workbook.add_worksheet().set_name("Nodes")?;
+    nodes.write_row_with_format(0, 0, NODE_HEADERS, &bold)?;
+    nodes.set_freeze_panes(1, 0)?;
+    for (row, (uuid, node)) in (1..).zip(&self.nodes) {
+      nodes.write_string(row, 0, options.id(uuid).to_string())?;
+      nodes.write_string(row, 1, uuid.to_string())?;
+      nodes.write_string(row, 2, label(*uuid))?;
+      if let Some(level) = node.level {
+        nodes.write_number(row, 3, level as f64)?;
+      }
+      if let Some(timestamp) = node.timestamp {
+        nodes.write_string(row, 4, timestamp.to_string())?;
+      }
+      if !options.omit_data {
+        match &node.data {
+          NodeData::Number(n) => nodes.write_number(row, 5, *n as f64)?,
+          NodeData::None => nodes,
+          data => nodes.write_string(row, 5, data.to_string())?,
+        };
+      }
+    }
+    nodes.autofit();
+
+    let edges = workbook.add_worksheet().set_name("Edges")?;
+    edges.write_row_with_format(0, 0, EDGE_HEADERS, &bold)?;
+    edges.set_freeze_panes(1, 0)?;
+    let pairs = self
+      .nodes
+      .iter()
+      .flat_map(|(parent, node)| node.childs.iter().map(move |child| (*parent, *child)))
+      .sorted();
+    for (row, (parent, child)) in (1..).zip(pairs) {
+      edges.write_string(row, 0, options.id(&parent).to_string())?;
+      edges.write_string(row, 1, options.id(&child).to_string())?;
+      edges.write_string(row, 2, label(parent))?;
+      edges.write_string(row, 3, label(child))?;
+    }
+    edges.autofit();
+
+    workbook.save_to_buffer()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_xlsx() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (parent_uuid, _) = graph.add_node("Parent");
+    let (child_uuid, _) = graph.add_node(42);
+    graph.add_child(parent_uuid, child_uuid).unwrap();
+
+    let xlsx = graph.xlsx_with(RenderOptions::default()).unwrap();
+    // a workbook is a zip archive
+    assert!(xlsx.starts_with(b"PK\x03\x04"));
+  }
+}
@@ -0,0 +1,116 @@
+//! Strings of the help and the reports, each language is a module with a
+//! `text` and a `help` function so adding one is adding a module and a
+//! [`Lang`] variant.
+
+mod en;
+mod fr;
+
+/// Language of the help and the reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum Lang {
+  #[default]
+  En,
+  Fr,
+}
+
+/// A string of the reports
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+  // table headers
+  Check,
+  Value,
+  Expected,
+  Status,
+  Metric,
+  Mean,
+  StdDev,
+  Min,
+  Max,
+  Delta,
+  // validation
+  ValidationResults,
+  Roots,
+  WrongRecordedLevel,
+  ChildrenBeforeParent,
+  OnlyOnePath,
+  ExtraEdgeDensity,
+  Yes,
+  No,
+  AverageChilds,
+  MaxDepth,
+  AverageDepth,
+  AverageWidth,
+  // analysis
+  AnalysisResults,
+  Width,
+  RootToLeafPaths,
+  InDegreeCentrality,
+  OutDegreeCentrality,
+  BetweennessCentrality,
+  PageRank,
+  // simulation
+  SimulationResults,
+  Workers,
+  TotalWork,
+  Makespan,
+  IdleTime,
+  Speedup,
+  CriticalPathLength,
+  CriticalPath,
+  // sweep
+  Nodes,
+  Edges,
+  Depth,
+  MaxWidth,
+  Leaves,
+  MaxOutDegree,
+  MeanOutDegree,
+  // stats
+  LongestPath,
+  InDegrees,
+  OutDegrees,
+  DepthHistogram,
+  LevelWidths,
+}
+
+impl Lang {
+  pub fn text(self, text: Text) -> &'static str {
+    match self {
+      Lang::En => en::text(text),
+      Lang::Fr => fr::text(text),
+    }
+  }
+
+  /// Help of an argument of a command, or of the command itself without
+  /// argument. `None` keeps the English help of the doc comments.
+  pub fn help(self, command: &str, arg: Option<&str>) -> Option<&'static str> {
+    match self {
+      Lang::En => en::help(command, arg),
+      Lang::Fr => fr::help(command, arg),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_text() {
+    assert_eq!(Lang::En.text(Text::Roots), "Roots");
+    assert_eq!(Lang::Fr.text(Text::Roots), "Racines");
+    assert_eq!(Lang::default(), Lang::En);
+  }
+
+  #[test]
+  fn test_help() {
+    assert_eq!(Lang::En.help("dag", Some("depth")), None);
+    assert_eq!(
+      Lang::Fr.help("dag", Some("depth")),
+      Some("Nombre maximal de niveaux, racine comprise")
+    );
+    assert!(Lang::Fr.help("find-seed", None).is_some());
+    assert_eq!(Lang::Fr.help("dag", Some("unknown")), None);
+  }
+}
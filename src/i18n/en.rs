@@ -0,0 +1,60 @@
+use super::Text;
+
+pub(super) fn text(text: Text) -> &'static str {
+  match text {
+    Text::Check => "Check",
+    Text::Value => "Value",
+    Text::Expected => "Expected",
+    Text::Status => "Status",
+    Text::Metric => "Metric",
+    Text::Mean => "Mean",
+    Text::StdDev => "Std dev",
+    Text::Min => "Min",
+    Text::Max => "Max",
+    Text::Delta => "Delta",
+    Text::ValidationResults => "Validation results:",
+    Text::Roots => "Roots",
+    Text::WrongRecordedLevel => "Nodes with a wrong recorded level",
+    Text::ChildrenBeforeParent => "Children not created after their parent",
+    Text::OnlyOnePath => "Only one path to each node",
+    Text::ExtraEdgeDensity => "Extra edges per tree edge",
+    Text::Yes => "yes",
+    Text::No => "no",
+    Text::AverageChilds => "Average childs per node with child",
+    Text::MaxDepth => "Max depth + 1",
+    Text::AverageDepth => "Average depth",
+    Text::AverageWidth => "Average width without root level",
+    Text::AnalysisResults => "Analysis results:",
+    Text::Width => "Width (maximum antichain)",
+    Text::RootToLeafPaths => "Root to leaf paths",
+    Text::InDegreeCentrality => "In-degree centrality",
+    Text::OutDegreeCentrality => "Out-degree centrality",
+    Text::BetweennessCentrality => "Betweenness centrality",
+    Text::PageRank => "PageRank",
+    Text::SimulationResults => "Simulation results:",
+    Text::Workers => "Workers",
+    Text::TotalWork => "Total work",
+    Text::Makespan => "Makespan",
+    Text::IdleTime => "Idle time",
+    Text::Speedup => "Speedup",
+    Text::CriticalPathLength => "Critical path length",
+    Text::CriticalPath => "Critical path",
+    Text::Nodes => "Nodes",
+    Text::Edges => "Edges",
+    Text::Depth => "Depth",
+    Text::MaxWidth => "Max width",
+    Text::Leaves => "Leaves",
+    Text::MaxOutDegree => "Max out-degree",
+    Text::MeanOutDegree => "Mean out-degree",
+    Text::LongestPath => "Longest path",
+    Text::InDegrees => "Nodes per in-degree",
+    Text::OutDegrees => "Nodes per out-degree",
+    Text::DepthHistogram => "Nodes per depth",
+    Text::LevelWidths => "Nodes per level",
+  }
+}
+
+// the doc comments of the arguments are the English help
+pub(super) fn help(_command: &str, _arg: Option<&str>) -> Option<&'static str> {
+  None
+}
@@ -0,0 +1,380 @@
+use super::Text;
+
+pub(super) fn text(text: Text) -> &'static str {
+  match text {
+    Text::Check => "Vérification",
+    Text::Value => "Valeur",
+    Text::Expected => "Attendu",
+    Text::Status => "Statut",
+    Text::Metric => "Métrique",
+    Text::Mean => "Moyenne",
+    Text::StdDev => "Écart type",
+    Text::Min => "Min",
+    Text::Max => "Max",
+    Text::Delta => "Écart",
+    Text::ValidationResults => "Résultats de la validation :",
+    Text::Roots => "Racines",
+    Text::WrongRecordedLevel => "Nœuds avec un mauvais niveau enregistré",
+    Text::ChildrenBeforeParent => "Enfants non créés après leur parent",
+    Text::OnlyOnePath => "Un seul chemin vers chaque nœud",
+    Text::ExtraEdgeDensity => "Arêtes en plus par arête de l'arbre",
+    Text::Yes => "oui",
+    Text::No => "non",
+    Text::AverageChilds => "Moyenne d'enfants par nœud avec enfant",
+    Text::MaxDepth => "Profondeur max + 1",
+    Text::AverageDepth => "Profondeur moyenne",
+    Text::AverageWidth => "Largeur moyenne sans le niveau racine",
+    Text::AnalysisResults => "Résultats de l'analyse :",
+    Text::Width => "Largeur (antichaîne maximale)",
+    Text::RootToLeafPaths => "Chemins de la racine aux feuilles",
+    Text::InDegreeCentrality => "Centralité de degré entrant",
+    Text::OutDegreeCentrality => "Centralité de degré sortant",
+    Text::BetweennessCentrality => "Centralité d'intermédiarité",
+    Text::PageRank => "PageRank",
+    Text::SimulationResults => "Résultats de la simulation :",
+    Text::Workers => "Travailleurs",
+    Text::TotalWork => "Travail total",
+    Text::Makespan => "Durée totale",
+    Text::IdleTime => "Temps d'inactivité",
+    Text::Speedup => "Accélération",
+    Text::CriticalPathLength => "Longueur du chemin critique",
+    Text::CriticalPath => "Chemin critique",
+    Text::Nodes => "Nœuds",
+    Text::Edges => "Arêtes",
+    Text::Depth => "Profondeur",
+    Text::MaxWidth => "Largeur max",
+    Text::Leaves => "Feuilles",
+    Text::MaxOutDegree => "Degré sortant max",
+    Text::MeanOutDegree => "Degré sortant moyen",
+    Text::LongestPath => "Plus long chemin",
+    Text::InDegrees => "Nœuds par degré entrant",
+    Text::OutDegrees => "Nœuds par degré sortant",
+    Text::DepthHistogram => "Nœuds par profondeur",
+    Text::LevelWidths => "Nœuds par niveau",
+  }
+}
+
+pub(super) fn help(command: &str, arg: Option<&str>) -> Option<&'static str> {
+  let help = match (command, arg) {
+    ("dag", Some("depth")) => "Nombre maximal de niveaux, racine comprise",
+    ("dag", Some("width_mean")) => "Largeur moyenne des niveaux",
+    ("dag", Some("width_std_dev")) => "Écart type de la largeur des niveaux",
+    ("dag", Some("child_mean")) => "Nombre moyen d'enfants par nœud",
+    ("dag", Some("child_std_dev")) => "Écart type du nombre d'enfants par nœud",
+    ("dag", Some("width_dist")) => {
+      "Distribution des largeurs des niveaux, tirée avec la moyenne et l'écart type de la largeur"
+    }
+    ("dag", Some("child_dist")) => {
+      "Distribution des enfants de chaque nœud, tirée avec la moyenne et l'écart type des enfants"
+    }
+    ("dag", Some("format")) => "Format de sortie",
+    ("dag", Some("seed")) => "Graine du générateur, aléatoire par défaut",
+    ("dag", Some("name")) => "Nom du graphe",
+    ("dag", Some("uuid_version")) => {
+      "Version des UUID des nœuds, ceux en v7 sont triés par ordre de création donc le tri par \
+       UUID place les parents avant leurs enfants"
+    }
+    ("dag", Some("strategy")) => {
+      "Ajoute les nœuds niveau par niveau ou sous-arbre par sous-arbre, en profondeur les premiers \
+       sous-arbres prennent l'essentiel de la largeur de chaque niveau"
+    }
+    ("dag", Some("shape")) => {
+      "Génère un arbre, ou un DAG où les nœuds peuvent avoir plusieurs parents"
+    }
+    ("dag", Some("extra_edge_density")) => {
+      "Arêtes en plus de la forme dag par arête de l'arbre, entre 0 et 1, chacune d'un nœud au \
+       hasard vers un nœud au hasard d'un niveau plus profond"
+    }
+    ("dag", Some("edge_weight_mean")) => {
+      "Donne à chaque arête un poids tiré d'une loi normale de cette moyenne, les tirages négatifs \
+       deviennent 0"
+    }
+    ("dag", Some("edge_weight_std_dev")) => "Écart type du poids des arêtes",
+    ("dag", Some("label_template")) => {
+      "Étiquette des nœuds, par exemple \"{name} ({data}) d={depth}\", aussi {uuid}, {id} et \
+       {timestamp}"
+    }
+    ("dag", Some("link_template")) => {
+      "URL des nœuds avec les mêmes champs que l'étiquette, le champ `url` des données du nœud est \
+       utilisé sans modèle"
+    }
+    ("dag", Some("sort_by")) => {
+      "Ordre des nœuds DOT et Mermaid, par nom ou ordre topologique pour des diffs lisibles entre \
+       graphes proches"
+    }
+    ("dag", Some("edge_style")) => {
+      "Colore les arêtes DOT et Mermaid selon la profondeur de leur parent ou les élargit selon le \
+       nombre de chemins de la racine aux feuilles qui les traversent"
+    }
+    ("dag", Some("rankdir")) => {
+      "Direction des niveaux DOT et Mermaid, lr est plus lisible pour les graphes larges"
+    }
+    ("dag", Some("node_shapes")) => {
+      "Forme des nœuds DOT et Mermaid selon la variante de leurs données, comme \
+       \"text=ellipse,json=hexagon\" avec number, text, json ou none et box, ellipse, circle, \
+       diamond, hexagon ou parallelogram"
+    }
+    ("dag", Some("sanitize")) => {
+      "Traitement des caractères dangereux dans le nom du graphe et les étiquettes des nœuds"
+    }
+    ("dag", Some("split_levels")) => {
+      "Écrit la sortie DOT ou Mermaid dans un fichier par tranche de ce nombre de niveaux dans \
+       --output-dir, les nœuds liés à d'autres tranches sont répétés en pointillés"
+    }
+    ("dag", Some("split_by_subtree")) => {
+      "Écrit la sortie DOT ou Mermaid dans un fichier pour la racine et un par sous-arbre d'un \
+       enfant de la racine dans --output-dir"
+    }
+    ("dag", Some("output")) => "Écrit la sortie dans ce fichier au lieu de stdout",
+    ("dag", Some("output_dir")) => {
+      "Écrit la sortie dans graph.dot, graph.mmd... de ce dossier au lieu de stdout, --format both \
+       écrit un fichier DOT et un fichier Mermaid. Les fichiers découpés sont nommés part-001.dot, \
+       part-001.mmd..., dans le dossier courant sans lui"
+    }
+    ("dag", Some("mapping")) => {
+      "Avec le format METIS ou les identifiants séquentiels, écrit le numéro ou l'identifiant, \
+       l'UUID et l'étiquette de chaque nœud dans ce fichier"
+    }
+    ("dag", Some("data_out")) => {
+      "Écrit les données de chaque nœud par son identifiant dans ce fichier JSON et les retire des \
+       sorties JGF, XLSX et des tableaux DOT"
+    }
+    ("dag", Some("node_data_schema")) => {
+      "Fichier JSON décrivant les champs à générer dans les données de chaque nœud"
+    }
+    ("dag", Some("attr")) => {
+      "Champ ajouté aux données de chaque nœud, comme \"latency=lognormal(5,2)\", avec int, \
+       uniform, normal ou lognormal et leurs deux paramètres, répétable"
+    }
+    ("dag", Some("edge_attr")) => {
+      "Attribut donné à chaque arc avec la même syntaxe que --attr, répétable"
+    }
+    ("dag", Some("timestamps_from")) => {
+      "Donne une date de création à chaque nœud, la racine est créée à cette date"
+    }
+    ("dag", Some("timestamp_step")) => {
+      "Secondes moyennes entre la création d'un nœud et celle de son dernier parent, cent ans au \
+       plus"
+    }
+    ("dag", Some("dry_run")) => "Affiche la forme attendue du graphe sans le générer",
+    ("dag", Some("exact_nodes")) => {
+      "Génère exactement ce nombre de nœuds, sur --exact-depth niveaux avec --exact-edges arcs, au \
+       lieu de suivre les distributions"
+    }
+    ("dag", Some("exact_depth")) => "Nombre de niveaux de --exact-nodes, racine comprise",
+    ("dag", Some("exact_edges")) => {
+      "Nombre d'arcs de --exact-nodes, au moins le nombre de nœuds moins un"
+    }
+    ("dag", Some("preset")) => {
+      "Construit une forme connue de --depth niveaux et --width-mean nœuds de large au lieu de \
+       suivre les distributions"
+    }
+    ("dag", Some("checkpoint")) => {
+      "Enregistre la génération entre deux niveaux dans ce fichier, au plus toutes les \
+       --checkpoint-interval secondes, pour la terminer avec --resume si elle est interrompue"
+    }
+    ("dag", Some("checkpoint_interval")) => "Secondes minimales entre deux points de reprise",
+    ("dag", Some("resume")) => {
+      "Termine la génération enregistrée dans ce point de reprise, les paramètres doivent être les \
+       mêmes et la graine est celle du point de reprise"
+    }
+    ("dag", Some("size_by_pagerank")) => {
+      "Taille des nœuds DOT selon leur PageRank, gardé dans un attribut `score`"
+    }
+    ("dag", Some("dot_clusters")) => {
+      "Regroupe chaque sous-arbre d'un enfant de la racine dans un cluster DOT"
+    }
+    ("dag", Some("dot_tables")) => {
+      "Dessine chaque nœud DOT comme un tableau avec son étiquette, son UUID court et les champs \
+       de ses données"
+    }
+    ("dag", Some("dot_rank_hints")) => {
+      "Ordonne les nœuds DOT rang par rang pour réduire les croisements d'arcs et garde cet ordre \
+       avec des arcs invisibles, ignoré avec --dot-clusters"
+    }
+    ("dag", Some("color_by_depth")) => {
+      "Remplit les nœuds DOT et Mermaid d'une couleur par profondeur"
+    }
+    ("dag", Some("show_uuid")) => {
+      "Ajoute l'UUID court de chaque nœud DOT et Mermaid sous son étiquette"
+    }
+    ("dag", Some("reduce")) => {
+      "Retire les arêtes impliquées par un chemin plus long avant la sortie, la validation est \
+       sautée si une arête est retirée"
+    }
+    ("dag", Some("sequential_ids")) => {
+      "Identifie les nœuds exportés par n0001, n0002... dans l'ordre des UUID au lieu de leur UUID \
+       court, voir --mapping"
+    }
+    ("dag", Some("summary")) => {
+      "Affiche la graine, le nombre de nœuds, d'arêtes, de niveaux, de racines et de feuilles et \
+       le statut de la validation sur la dernière ligne de stderr, pour les scripts"
+    }
+    ("dag", Some("validate_output")) => {
+      "Format du rapport de validation sur stderr, json l'affiche sur une ligne pour les jobs de \
+       CI qui vérifient les graphes générés"
+    }
+    ("dag", Some("tolerance")) => {
+      "Fait échouer la validation quand le nombre moyen d'enfants ou la largeur moyenne s'écarte \
+       de plus de cette fraction de la moyenne demandée, 0.15 pour 15 %, ils sont seulement \
+       informatifs sans elle"
+    }
+    ("dag", Some("analyze")) => "Affiche les métriques structurelles du graphe après la validation",
+    ("dag", Some("color")) => {
+      "Couleur du rapport de validation, NO_COLOR est respecté en mode auto"
+    }
+    ("dag", Some("lang")) => "Langue de l'aide et des rapports",
+    ("generate", None) => "Génère un graphe, comme sans commande",
+    ("validate", None) => {
+      "Vérifie un digraphe DOT ou un graphe JSON avec les paramètres de génération au lieu d'en \
+       générer un, échoue si une vérification échoue"
+    }
+    ("validate", Some("input")) => {
+      "Fichier DOT, ou fichier JSON de --format json avec l'extension .json"
+    }
+    ("validate", Some("root")) => {
+      "Garde seulement ce nœud et ses descendants, par UUID court ou UUID"
+    }
+    ("mutate", None) => {
+      "Applique des mutations aléatoires qui gardent le graphe généré, ou un digraphe DOT ou un \
+       graphe JSON, acyclique"
+    }
+    ("mutate", Some("input")) => {
+      "Fichier DOT, ou fichier JSON de --format json avec l'extension .json, le graphe généré sans \
+       lui"
+    }
+    ("mutate", Some("add_edges")) => "Nombre d'arêtes ajoutées",
+    ("mutate", Some("remove_edges")) => "Nombre d'arêtes supprimées",
+    ("mutate", Some("remove_nodes")) => {
+      "Nombre de nœuds supprimés, leurs arêtes sont supprimées aussi"
+    }
+    ("mutate", Some("mutation_seed")) => {
+      "Graine des mutations, le graphe utilise la graine principale"
+    }
+    ("grow", None) => {
+      "Ajoute des niveaux au graphe généré, ou à un digraphe DOT ou un graphe JSON, avec les mêmes \
+       distributions"
+    }
+    ("grow", Some("input")) => {
+      "Fichier DOT, ou fichier JSON de --format json avec l'extension .json, le graphe généré sans \
+       lui"
+    }
+    ("grow", Some("levels")) => "Nombre de niveaux ajoutés",
+    ("grow", Some("growth_seed")) => {
+      "Graine des nouveaux niveaux, le graphe utilise la graine principale"
+    }
+    ("prune", None) => "Supprime des sous-arbres aléatoires du graphe généré",
+    ("prune", Some("fraction")) => {
+      "Fraction des nœuds non racines supprimés avec leurs descendants"
+    }
+    ("prune", Some("max_depth")) => {
+      "Supprime chaque nœud plus profond que cette profondeur, la racine est à la profondeur 0"
+    }
+    ("prune", Some("prune_seed")) => {
+      "Graine des sous-arbres supprimés, le graphe utilise la graine principale"
+    }
+    ("rename", None) => "Donne de nouveaux noms à des nœuds aléatoires du graphe généré",
+    ("rename", Some("fraction")) => "Fraction des nœuds renommés, UUID et arêtes sont gardés",
+    ("rename", Some("rename_seed")) => {
+      "Graine des nouveaux noms, le graphe utilise la graine principale"
+    }
+    ("simulate", None) => {
+      "Planifie les nœuds du graphe généré sur des travailleurs et rapporte la durée totale"
+    }
+    ("simulate", Some("workers")) => "Nombre de travailleurs",
+    ("simulate", Some("duration_mean")) => {
+      "Durée des nœuds sans champ entier `duration` dans leurs données"
+    }
+    ("simulate", Some("duration_std_dev")) => "Écart type de la durée des nœuds",
+    ("simulate", Some("simulation_seed")) => {
+      "Graine des durées, le graphe utilise la graine principale"
+    }
+    ("sweep", None) => {
+      "Affiche les statistiques des métriques des graphes générés à partir de graines consécutives \
+       au lieu d'un graphe"
+    }
+    ("sweep", Some("runs")) => "Nombre de graphes générés, à partir de la graine principale",
+    ("compare", None) => {
+      "Affiche côte à côte les métriques des graphes générés avec deux fichiers de configuration \
+       au lieu d'un graphe"
+    }
+    ("compare", Some("configs")) => {
+      "Fichier TOML de paramètres de génération, donné deux fois, les paramètres absents gardent \
+       les valeurs de la ligne de commande"
+    }
+    ("compare", Some("runs")) => {
+      "Nombre de graphes générés avec chaque configuration, à partir de sa graine"
+    }
+    ("find-seed", None) => {
+      "Affiche la première graine depuis la graine principale dont le graphe respecte les \
+       contraintes au lieu d'un graphe"
+    }
+    ("find-seed", Some("nodes")) => {
+      "Nombre de nœuds accepté, par exemple 100..120, 100..=120, 100.. ou 100"
+    }
+    ("find-seed", Some("levels")) => "Nombre de niveaux accepté, niveau racine compris",
+    ("find-seed", Some("leaves")) => "Nombre de feuilles accepté",
+    ("find-seed", Some("max_width")) => "Largeur maximale acceptée",
+    ("find-seed", Some("tries")) => {
+      "Nombre de graines essayées depuis la graine principale avant d'abandonner"
+    }
+    ("self-test", None) => {
+      "Vérifie que les graines connues génèrent et exportent les sorties de référence embarquées \
+       sur cette plateforme au lieu d'un graphe"
+    }
+    ("self-test", Some("bless")) => {
+      "Écrit plutôt les sorties dans ce dossier, pour mettre à jour les sorties de référence de \
+       src/self_test"
+    }
+    ("watch", None) => {
+      "Réécrit le graphe d'un fichier de configuration dans le fichier de --output à chaque \
+       modification du fichier de configuration"
+    }
+    ("watch", Some("config")) => {
+      "Fichier TOML de paramètres de génération comme pour compare, les paramètres absents gardent \
+       les valeurs de la ligne de commande"
+    }
+    ("watch", Some("interval")) => {
+      "Millisecondes entre deux vérifications du fichier de configuration"
+    }
+    ("convert", None) => {
+      "Écrit un digraphe DOT ou un graphe JSON dans le format principal au lieu d'un graphe généré"
+    }
+    ("convert", Some("input")) => {
+      "Fichier DOT, ou fichier JSON de --format json avec l'extension .json"
+    }
+    ("convert", Some("root")) => {
+      "Garde seulement ce nœud et ses descendants, par UUID court ou UUID"
+    }
+    ("convert", Some("anonymize")) => {
+      "Remplace le nom du graphe, les UUID et les données des nœuds par de nouveaux petnames et \
+       des UUID aléatoires et retire les horodatages, les arêtes et les niveaux sont gardés"
+    }
+    ("convert", Some("anonymize_seed")) => "Graine des nouveaux noms et UUID de --anonymize",
+    ("diff", None) => {
+      "Affiche les nœuds, arêtes et données de nœuds qui diffèrent entre deux digraphes DOT ou \
+       graphes JSON au lieu d'un graphe généré"
+    }
+    ("diff", Some("before")) => {
+      "Fichier DOT, ou fichier JSON de --format json avec l'extension .json"
+    }
+    ("diff", Some("after")) => "Le graphe comparé au premier, associé par UUID",
+    ("diff", Some("diff_format")) => {
+      "Affiche une ligne par changement, ou les deux graphes en DOT avec les changements colorés"
+    }
+    ("stats", None) => {
+      "Affiche les nombres, distributions de degrés, profondeurs et largeurs des niveaux d'un \
+       digraphe DOT, d'un graphe JSON ou du graphe généré au lieu du graphe"
+    }
+    ("stats", Some("input")) => {
+      "Fichier DOT, ou fichier JSON de --format json avec l'extension .json, le graphe généré sans \
+       lui"
+    }
+    ("stats", Some("stats_format")) => {
+      "Affiche un tableau, ou les statistiques en objet JSON sur une ligne"
+    }
+    ("explore", None) => "Parcourt le graphe généré dans le terminal au lieu de l'écrire",
+    _ => return None,
+  };
+  Some(help)
+}
@@ -0,0 +1,39 @@
+//! Generate random directed acyclic graphs and export them. The
+//! [`AcyclicGraph`] model is always available, the generator and the command
+//! line interface are behind the `generator` and `cli` features. The main
+//! items are at the root: [`generate`] a graph from a [`Config`] then
+//! [`validate`] it against the same configuration.
+
+pub mod analyze;
+pub mod explore;
+#[cfg(feature = "generator")]
+pub mod generator;
+pub mod graph;
+pub mod i18n;
+pub mod report;
+pub mod schema;
+#[cfg(feature = "generator")]
+pub mod self_test;
+#[cfg(feature = "generator")]
+pub mod simulate;
+pub mod stats;
+#[cfg(feature = "generator")]
+pub mod sweep;
+pub mod template;
+#[cfg(feature = "generator")]
+pub mod transform;
+#[cfg(feature = "generator")]
+pub mod validator;
+
+#[cfg(feature = "generator")]
+pub use self::generator::{
+  Config,
+  generate,
+};
+pub use self::graph::{
+  AcyclicGraph,
+  Node,
+  NodeData,
+};
+#[cfg(feature = "generator")]
+pub use self::validator::validate;
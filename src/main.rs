@@ -1,41 +1,687 @@
-mod generator;
-mod graph;
-mod validator;
+use std::{
+  fs::File,
+  io::{
+    BufReader,
+    BufWriter,
+    IsTerminal,
+    Write,
+  },
+  num::NonZeroUsize,
+  path::{
+    Path,
+    PathBuf,
+  },
+  time::Duration,
+};
 
-use std::num::NonZeroUsize;
-
-use clap::Parser;
-use rand::Rng;
+use clap::{
+  CommandFactory,
+  FromArgMatches,
+  Parser,
+  Subcommand,
+  ValueEnum,
+  builder::BoolishValueParser,
+};
+use dag::{
+  analyze,
+  generator,
+  graph,
+  i18n::{
+    Lang,
+    Text,
+  },
+  report,
+  schema,
+  self_test,
+  simulate,
+  stats::Stats,
+  sweep,
+  template,
+  transform,
+  validator,
+};
+use itertools::Itertools;
+use rand::{
+  Rng,
+  SeedableRng,
+  rngs::StdRng,
+};
+use snafu::{
+  OptionExt,
+  ResultExt,
+  Snafu,
+  ensure,
+};
+use uuid::Uuid;
 
 #[derive(Parser, Debug)]
 pub struct Args {
-  #[arg(long, default_value_t = NonZeroUsize::new(5).unwrap())]
+  #[arg(long, global = true, env = "DAG_DEPTH", default_value_t = NonZeroUsize::new(5).unwrap())]
   #[arg(alias = "profondeur_max")]
   depth: NonZeroUsize,
 
-  #[arg(long, default_value_t = 10.0)]
+  #[arg(long, global = true, env = "DAG_WIDTH_MEAN", default_value_t = 10.0)]
   #[arg(alias = "largeur_moyenne")]
   width_mean: f64,
 
-  #[arg(long, default_value_t = 0.5)]
+  #[arg(long, global = true, env = "DAG_WIDTH_STD_DEV", default_value_t = 0.5)]
   width_std_dev: f64,
 
-  #[arg(long, default_value_t = 3.0)]
+  #[arg(long, global = true, env = "DAG_CHILD_MEAN", default_value_t = 3.0)]
   #[arg(alias = "connexions_moyennes")]
   child_mean: f64,
 
-  #[arg(long, default_value_t = 1.0)]
+  #[arg(long, global = true, env = "DAG_CHILD_STD_DEV", default_value_t = 1.0)]
   #[arg(alias = "ecart_type_connexions")]
   child_std_dev: f64,
 
-  #[arg(long, default_value = "mermaid")]
+  /// Distribution of the widths of the levels, drawn with the width mean and
+  /// standard deviation
+  #[arg(long, global = true, env = "DAG_WIDTH_DIST", default_value = "normal")]
+  width_dist: generator::Dist,
+
+  /// Distribution of the childs of each node, drawn with the child mean and
+  /// standard deviation
+  #[arg(long, global = true, env = "DAG_CHILD_DIST", default_value = "normal")]
+  child_dist: generator::Dist,
+
+  #[arg(long, global = true, env = "DAG_FORMAT", default_value = "mermaid")]
   format: Format,
 
-  #[arg(long)]
+  #[arg(long, global = true, env = "DAG_SEED")]
   seed: Option<u64>,
 
-  #[arg(long)]
+  #[arg(long, global = true, env = "DAG_NAME")]
   name: Option<String>,
+
+  /// Version of the node UUIDs, v7 ones sort in creation order so sorting by
+  /// UUID puts the parents before their childs
+  #[arg(long, global = true, env = "DAG_UUID_VERSION", default_value = "v4")]
+  uuid_version: graph::UuidVersion,
+
+  /// Add the nodes level by level or subtree by subtree, depth-first gives
+  /// the first subtrees most of the width of each level
+  #[arg(
+    long,
+    global = true,
+    env = "DAG_STRATEGY",
+    default_value = "breadth-first"
+  )]
+  strategy: generator::Strategy,
+
+  /// Generate a tree, or a dag where nodes can have several parents
+  #[arg(long, global = true, env = "DAG_SHAPE", default_value = "tree")]
+  shape: generator::Shape,
+
+  /// Extra edges of the dag shape per edge of the tree, between 0 and 1, each
+  /// from a random node to a random node of a deeper level
+  #[arg(
+    long,
+    global = true,
+    env = "DAG_EXTRA_EDGE_DENSITY",
+    default_value_t = 0.2,
+    value_parser = fraction
+  )]
+  extra_edge_density: f64,
+
+  /// Give each edge a weight drawn from a normal distribution of this mean,
+  /// negative draws become 0
+  #[arg(long, global = true, env = "DAG_EDGE_WEIGHT_MEAN")]
+  edge_weight_mean: Option<f64>,
+
+  /// Standard deviation of the edge weights
+  #[arg(
+    long,
+    global = true,
+    env = "DAG_EDGE_WEIGHT_STD_DEV",
+    default_value_t = 1.0
+  )]
+  #[arg(alias = "edge-weight-std")]
+  edge_weight_std_dev: f64,
+
+  /// Node label, e.g. "{name} ({data}) d={depth}", also {uuid}, {id} and
+  /// {timestamp}
+  #[arg(long, global = true, env = "DAG_LABEL_TEMPLATE")]
+  label_template: Option<template::Template>,
+
+  /// Node URL with the same placeholders as the label, the `url` field of the
+  /// node data is used without template
+  #[arg(long, global = true, env = "DAG_LINK_TEMPLATE")]
+  link_template: Option<template::Template>,
+
+  /// Order of the DOT and Mermaid nodes, by name or topological order give
+  /// readable diffs between close graphs
+  #[arg(long, global = true, env = "DAG_SORT_BY", default_value = "uuid")]
+  sort_by: graph::SortBy,
+
+  /// Color the DOT and Mermaid edges by the depth of their parent or widen
+  /// them with the number of root to leaf paths going through them
+  #[arg(long, global = true, env = "DAG_EDGE_STYLE", default_value = "none")]
+  edge_style: graph::EdgeStyle,
+
+  /// Direction of the DOT and Mermaid levels, lr is easier to read for wide
+  /// graphs
+  #[arg(long, global = true, env = "DAG_RANKDIR", default_value = "tb")]
+  rankdir: graph::RankDir,
+
+  /// Shape of the DOT and Mermaid nodes by variant of their data, like
+  /// "text=ellipse,json=hexagon" with number, text, json or none and box,
+  /// ellipse, circle, diamond, hexagon or parallelogram
+  #[arg(long, global = true, env = "DAG_NODE_SHAPES")]
+  node_shapes: Option<graph::NodeShapes>,
+
+  /// Handling of unsafe characters in the graph name and the node labels
+  #[arg(long, global = true, env = "DAG_SANITIZE", default_value = "escape")]
+  sanitize: graph::Sanitize,
+
+  /// Write the DOT or Mermaid output to one file per slice of this number of
+  /// levels in --output-dir, nodes linked to other slices are repeated dashed
+  #[arg(
+    long,
+    global = true,
+    env = "DAG_SPLIT_LEVELS",
+    conflicts_with_all = ["split_by_subtree", "output"]
+  )]
+  split_levels: Option<NonZeroUsize>,
+
+  /// Write the DOT or Mermaid output to one file for the root and one per
+  /// subtree of a child of the root in --output-dir
+  #[arg(
+    long,
+    global = true,
+    env = "DAG_SPLIT_BY_SUBTREE",
+    value_parser = BoolishValueParser::new(),
+    conflicts_with = "output"
+  )]
+  split_by_subtree: bool,
+
+  /// Write the output to this file instead of stdout
+  #[arg(long, global = true, env = "DAG_OUTPUT")]
+  output: Option<PathBuf>,
+
+  /// Write the output to graph.dot, graph.mmd... in this directory instead
+  /// of stdout, --format both writes a DOT file and a Mermaid file. The split
+  /// files are named part-001.dot, part-001.mmd..., in the current directory
+  /// without it.
+  #[arg(long, global = true, env = "DAG_OUTPUT_DIR", conflicts_with = "output")]
+  output_dir: Option<PathBuf>,
+
+  /// With the METIS format or sequential IDs, write the number or ID, UUID
+  /// and label of each node to this file
+  #[arg(long, global = true, env = "DAG_MAPPING")]
+  mapping: Option<PathBuf>,
+
+  /// Write the data of each node keyed by its ID to this JSON file and leave
+  /// it out of the JGF, DOT tables and XLSX outputs
+  #[arg(long, global = true, env = "DAG_DATA_OUT")]
+  data_out: Option<PathBuf>,
+
+  /// JSON file describing the fields to generate in each node data
+  #[arg(long, global = true, env = "DAG_NODE_DATA_SCHEMA")]
+  node_data_schema: Option<PathBuf>,
+
+  /// Field added to each node data, like "latency=lognormal(5,2)", with int,
+  /// uniform, normal or lognormal and their two parameters, repeatable
+  #[arg(long, global = true, env = "DAG_ATTR", value_delimiter = ';')]
+  attr: Vec<schema::Attribute>,
+
+  /// Attribute given to each edge with the same syntax as --attr, repeatable
+  #[arg(long, global = true, env = "DAG_EDGE_ATTR", value_delimiter = ';')]
+  edge_attr: Vec<schema::Attribute>,
+
+  /// Give each node a creation time, the root is created at this date
+  #[arg(long, global = true, env = "DAG_TIMESTAMPS_FROM")]
+  timestamps_from: Option<schema::Date>,
+
+  /// Average seconds between the creation of a node and its last parent, a
+  /// hundred years at most
+  #[arg(
+    long,
+    global = true,
+    env = "DAG_TIMESTAMP_STEP",
+    default_value_t = 3600,
+    value_parser = clap::value_parser!(u64).range(..=transform::MAX_TIMESTAMP_STEP)
+  )]
+  timestamp_step: u64,
+
+  /// Print the expected shape of the graph without generating it
+  #[arg(long, global = true, env = "DAG_DRY_RUN", value_parser = BoolishValueParser::new())]
+  dry_run: bool,
+
+  /// Generate exactly this number of nodes, with --exact-depth levels and
+  /// --exact-edges edges, instead of following the distributions
+  #[arg(long, global = true, env = "DAG_EXACT_NODES")]
+  #[arg(conflicts_with_all = ["checkpoint", "resume"])]
+  #[arg(requires_all = ["exact_depth", "exact_edges"])]
+  exact_nodes: Option<usize>,
+
+  /// Number of levels of --exact-nodes, root level included
+  #[arg(long, global = true, env = "DAG_EXACT_DEPTH", requires = "exact_nodes")]
+  exact_depth: Option<usize>,
+
+  /// Number of edges of --exact-nodes, at least the number of nodes minus one
+  #[arg(long, global = true, env = "DAG_EXACT_EDGES", requires = "exact_nodes")]
+  exact_edges: Option<usize>,
+
+  /// Build a well-known shape of --depth levels and --width-mean nodes wide
+  /// instead of following the distributions
+  #[arg(
+    long,
+    global = true,
+    env = "DAG_PRESET",
+    conflicts_with_all = ["exact_nodes", "checkpoint", "resume"]
+  )]
+  preset: Option<generator::Preset>,
+
+  /// Save the generation between two levels to this file, at most every
+  /// --checkpoint-interval seconds, to finish it with --resume if interrupted
+  #[arg(long, global = true, env = "DAG_CHECKPOINT")]
+  checkpoint: Option<PathBuf>,
+
+  /// Minimum seconds between two checkpoints
+  #[arg(
+    long,
+    global = true,
+    env = "DAG_CHECKPOINT_INTERVAL",
+    default_value_t = 60
+  )]
+  checkpoint_interval: u64,
+
+  /// Finish the generation saved in this checkpoint, the parameters must be
+  /// the same and the seed is taken from the checkpoint
+  #[arg(long, global = true, env = "DAG_RESUME")]
+  resume: Option<PathBuf>,
+
+  /// Scale the DOT nodes by their PageRank, the score is kept in a `score`
+  /// attribute
+  #[arg(long, global = true, env = "DAG_SIZE_BY_PAGERANK", value_parser = BoolishValueParser::new())]
+  size_by_pagerank: bool,
+
+  /// Wrap each subtree of a child of the root in a DOT cluster
+  #[arg(long, global = true, env = "DAG_DOT_CLUSTERS", value_parser = BoolishValueParser::new())]
+  dot_clusters: bool,
+
+  /// Draw each DOT node as a table with its label, short UUID and data fields
+  #[arg(long, global = true, env = "DAG_DOT_TABLES", value_parser = BoolishValueParser::new())]
+  dot_tables: bool,
+
+  /// Order the DOT nodes rank by rank to reduce the edge crossings and keep
+  /// that order with invisible edges, ignored with --dot-clusters
+  #[arg(long, global = true, env = "DAG_DOT_RANK_HINTS", value_parser = BoolishValueParser::new())]
+  dot_rank_hints: bool,
+
+  /// Fill the DOT and Mermaid nodes with a color per depth
+  #[arg(long, global = true, env = "DAG_COLOR_BY_DEPTH", value_parser = BoolishValueParser::new())]
+  color_by_depth: bool,
+
+  /// Add the short UUID of each DOT and Mermaid node under its label
+  #[arg(long, global = true, env = "DAG_SHOW_UUID", value_parser = BoolishValueParser::new())]
+  show_uuid: bool,
+
+  /// Remove the edges implied by a longer path before the output, the
+  /// validation is skipped if one is removed
+  #[arg(long, global = true, env = "DAG_REDUCE", value_parser = BoolishValueParser::new())]
+  reduce: bool,
+
+  /// Identify the exported nodes by n0001, n0002... in UUID order instead of
+  /// their short UUID, see --mapping
+  #[arg(long, global = true, env = "DAG_SEQUENTIAL_IDS", value_parser = BoolishValueParser::new())]
+  sequential_ids: bool,
+
+  /// Print the seed, the counts of nodes, edges, levels, roots and leaves and
+  /// the validation status on the last line of stderr, for scripts
+  #[arg(long, global = true, env = "DAG_SUMMARY")]
+  summary: Option<report::SummaryFormat>,
+
+  /// Format of the validation report on stderr, json prints it on one line
+  /// for the CI jobs checking the generated graphs
+  #[arg(
+    long,
+    global = true,
+    env = "DAG_VALIDATE_OUTPUT",
+    default_value = "text"
+  )]
+  validate_output: ValidateOutput,
+
+  /// Fail the validation when the average childs or width is further than
+  /// this fraction from the requested mean, 0.15 for 15%, they are only
+  /// informative without it
+  #[arg(long, global = true, env = "DAG_TOLERANCE", value_parser = tolerance)]
+  tolerance: Option<f64>,
+
+  /// Print structural metrics of the graph after the validation
+  #[arg(long, global = true, env = "DAG_ANALYZE", value_parser = BoolishValueParser::new())]
+  analyze: bool,
+
+  /// Color of the validation report, NO_COLOR is respected in auto mode
+  #[arg(long, global = true, env = "DAG_COLOR", default_value = "auto")]
+  color: ColorWhen,
+
+  /// Language of the help and the reports
+  #[arg(long, global = true, env = "DAG_LANG", default_value = "en")]
+  lang: Lang,
+
+  /// Generate the graph when omitted
+  #[command(subcommand)]
+  command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+  /// Generate a graph, the same as without a command
+  Generate,
+  /// Check a DOT digraph or a JSON graph against the generation parameters
+  /// instead of generating one, fail if a check fails
+  Validate(ValidateArgs),
+  /// Apply random mutations that keep the generated graph, or a DOT digraph
+  /// or a JSON graph, acyclic
+  Mutate(MutateArgs),
+  /// Append levels to the generated graph, or a DOT digraph or a JSON graph,
+  /// with the same distributions
+  Grow(GrowArgs),
+  /// Remove random subtrees of the generated graph
+  Prune(PruneArgs),
+  /// Give fresh names to random nodes of the generated graph
+  Rename(RenameArgs),
+  /// Schedule the nodes of the generated graph on workers and report the
+  /// makespan
+  Simulate(SimulateArgs),
+  /// Print statistics of the metrics of graphs generated from consecutive
+  /// seeds instead of a graph
+  Sweep(SweepArgs),
+  /// Print the first seed from the main seed whose graph match the
+  /// constraints instead of a graph
+  FindSeed(FindSeedArgs),
+  /// Print the metrics of graphs generated with two configuration files side
+  /// by side instead of a graph
+  Compare(CompareArgs),
+  /// Check that the known seeds generate and render the embedded golden
+  /// outputs on this platform instead of a graph
+  SelfTest(SelfTestArgs),
+  /// Write the graph of a configuration file to --output again each time the
+  /// configuration file changes
+  Watch(WatchArgs),
+  /// Write a DOT digraph or a JSON graph in the main format instead of a
+  /// generated graph
+  Convert(ConvertArgs),
+  /// Print the nodes, edges and node data that differ between two DOT
+  /// digraphs or JSON graphs instead of a generated graph
+  Diff(DiffArgs),
+  /// Print the counts, degree distributions, depths and level widths of a
+  /// DOT digraph, a JSON graph or the generated graph instead of the graph
+  Stats(StatsArgs),
+  /// Navigate the generated graph in the terminal instead of writing it
+  #[cfg(feature = "explore")]
+  Explore,
+}
+
+#[derive(clap::Args, Debug)]
+struct WatchArgs {
+  /// TOML file of generation parameters like for compare, missing parameters
+  /// keep the command line values
+  #[arg(long, env = "DAG_WATCH_CONFIG")]
+  config: PathBuf,
+
+  /// Milliseconds between two checks of the configuration file
+  #[arg(long, env = "DAG_WATCH_INTERVAL", default_value_t = 500)]
+  interval: u64,
+}
+
+#[derive(clap::Args, Debug)]
+struct ConvertArgs {
+  /// DOT file, or JSON file of --format json with the .json extension
+  #[arg(env = "DAG_CONVERT_INPUT")]
+  input: PathBuf,
+
+  /// Keep only this node and its descendants, by short UUID or UUID
+  #[arg(long, env = "DAG_CONVERT_ROOT", value_parser = node_uuid)]
+  root: Option<Uuid>,
+
+  /// Replace the graph name, the UUIDs and the node data by fresh petnames
+  /// and random UUIDs and drop the timestamps, edges and levels are kept
+  #[arg(long, env = "DAG_CONVERT_ANONYMIZE", value_parser = BoolishValueParser::new())]
+  anonymize: bool,
+
+  /// Seed of the fresh names and UUIDs of --anonymize
+  #[arg(long, env = "DAG_CONVERT_ANONYMIZE_SEED", requires = "anonymize")]
+  anonymize_seed: Option<u64>,
+}
+
+#[derive(clap::Args, Debug)]
+struct DiffArgs {
+  /// DOT file, or JSON file of --format json with the .json extension
+  #[arg(env = "DAG_DIFF_BEFORE")]
+  before: PathBuf,
+
+  /// The graph compared to the first one, matched by UUID
+  #[arg(env = "DAG_DIFF_AFTER")]
+  after: PathBuf,
+
+  /// Print a line per change, or both graphs in DOT with the changes colored
+  #[arg(long, env = "DAG_DIFF_FORMAT", default_value = "text")]
+  diff_format: DiffFormat,
+}
+
+#[derive(clap::Args, Debug)]
+struct StatsArgs {
+  /// DOT file, or JSON file of --format json with the .json extension, the
+  /// generated graph without it
+  #[arg(env = "DAG_STATS_INPUT")]
+  input: Option<PathBuf>,
+
+  /// Print a table, or the statistics as a JSON object on one line
+  #[arg(long, env = "DAG_STATS_FORMAT", default_value = "text")]
+  stats_format: StatsFormat,
+}
+
+#[derive(clap::Args, Debug)]
+struct ValidateArgs {
+  /// DOT file, or JSON file of --format json with the .json extension
+  #[arg(env = "DAG_VALIDATE_INPUT")]
+  input: PathBuf,
+
+  /// Keep only this node and its descendants, by short UUID or UUID
+  #[arg(long, env = "DAG_VALIDATE_ROOT", value_parser = node_uuid)]
+  root: Option<Uuid>,
+}
+
+// a short UUID as in the DOT and Mermaid outputs or a full UUID
+fn node_uuid(s: &str) -> Result<Uuid, String> {
+  graph::parse_uuid(s).ok_or_else(|| "must be a short UUID or an UUID".to_string())
+}
+
+#[derive(clap::Args, Debug)]
+struct SelfTestArgs {
+  /// Write the outputs to this directory instead, to update the golden
+  /// outputs of src/self_test
+  #[arg(long, env = "DAG_SELF_TEST_BLESS")]
+  bless: Option<PathBuf>,
+}
+
+#[derive(clap::Args, Debug)]
+struct CompareArgs {
+  /// TOML file of generation parameters, given twice, missing parameters keep
+  /// the command line values
+  #[arg(
+    long = "config",
+    env = "DAG_COMPARE_CONFIG",
+    value_delimiter = ',',
+    required = true
+  )]
+  configs: Vec<PathBuf>,
+
+  /// Number of graphs generated with each configuration, from its seed
+  #[arg(long, env = "DAG_COMPARE_RUNS", default_value_t = NonZeroUsize::new(100).unwrap())]
+  runs: NonZeroUsize,
+}
+
+#[derive(clap::Args, Debug)]
+struct FindSeedArgs {
+  /// Accepted number of nodes, e.g. 100..120, 100..=120, 100.. or 100
+  #[arg(long, env = "DAG_FIND_SEED_NODES")]
+  nodes: Option<sweep::Interval>,
+
+  /// Accepted number of levels, root level included
+  #[arg(long, env = "DAG_FIND_SEED_LEVELS")]
+  levels: Option<sweep::Interval>,
+
+  #[arg(long, env = "DAG_FIND_SEED_LEAVES")]
+  leaves: Option<sweep::Interval>,
+
+  #[arg(long, env = "DAG_FIND_SEED_MAX_WIDTH")]
+  max_width: Option<sweep::Interval>,
+
+  /// Number of seeds tried from the main seed before giving up
+  #[arg(long, env = "DAG_FIND_SEED_TRIES", default_value_t = NonZeroUsize::new(10000).unwrap())]
+  tries: NonZeroUsize,
+}
+
+#[derive(clap::Args, Debug)]
+struct SweepArgs {
+  /// Number of graphs generated, from the main seed
+  #[arg(long, env = "DAG_SWEEP_RUNS", default_value_t = NonZeroUsize::new(100).unwrap())]
+  runs: NonZeroUsize,
+}
+
+#[derive(clap::Args, Debug)]
+struct SimulateArgs {
+  #[arg(long, env = "DAG_SIMULATE_WORKERS", default_value_t = NonZeroUsize::new(4).unwrap())]
+  workers: NonZeroUsize,
+
+  /// Duration of the nodes without an integer `duration` field in their data
+  #[arg(long, env = "DAG_SIMULATE_DURATION_MEAN", default_value_t = 10.0)]
+  duration_mean: f64,
+
+  #[arg(long, env = "DAG_SIMULATE_DURATION_STD_DEV", default_value_t = 3.0)]
+  duration_std_dev: f64,
+
+  /// Seed of the durations, the graph use the main seed
+  #[arg(long, env = "DAG_SIMULATE_SEED")]
+  simulation_seed: Option<u64>,
+}
+
+#[derive(clap::Args, Debug)]
+struct PruneArgs {
+  /// Fraction of the non root nodes removed with their descendants
+  #[arg(long, env = "DAG_PRUNE_FRACTION", default_value_t = 0.0, value_parser = fraction)]
+  fraction: f64,
+
+  /// Remove every node deeper than this depth, the root is at depth 0
+  #[arg(long, env = "DAG_PRUNE_MAX_DEPTH")]
+  max_depth: Option<usize>,
+
+  /// Seed of the removed subtrees, the graph use the main seed
+  #[arg(long, env = "DAG_PRUNE_SEED")]
+  prune_seed: Option<u64>,
+}
+
+#[derive(clap::Args, Debug)]
+struct RenameArgs {
+  /// Fraction of the nodes renamed, UUIDs and edges are kept
+  #[arg(long, env = "DAG_RENAME_FRACTION", default_value_t = 0.1, value_parser = fraction)]
+  fraction: f64,
+
+  /// Seed of the new names, the graph use the main seed
+  #[arg(long, env = "DAG_RENAME_SEED")]
+  rename_seed: Option<u64>,
+}
+
+fn fraction(s: &str) -> Result<f64, String> {
+  match s.parse::<f64>() {
+    Ok(fraction) if (0.0..=1.0).contains(&fraction) => Ok(fraction),
+    Ok(_) => Err("must be between 0 and 1".to_string()),
+    Err(e) => Err(e.to_string()),
+  }
+}
+
+// a fraction of the mean that can be above 1, NaN would pass every check
+fn tolerance(s: &str) -> Result<f64, String> {
+  match s.parse::<f64>() {
+    Ok(tolerance) if tolerance.is_finite() && tolerance >= 0.0 => Ok(tolerance),
+    Ok(_) => Err("must be a finite number of at least 0".to_string()),
+    Err(e) => Err(e.to_string()),
+  }
+}
+
+#[derive(clap::Args, Debug)]
+struct GrowArgs {
+  /// DOT file, or JSON file of --format json with the .json extension, the
+  /// generated graph without it
+  #[arg(env = "DAG_GROW_INPUT")]
+  input: Option<PathBuf>,
+
+  #[arg(long, env = "DAG_GROW_LEVELS", default_value_t = 1)]
+  levels: usize,
+
+  /// Seed of the new levels, the graph use the main seed
+  #[arg(long, env = "DAG_GROW_SEED")]
+  growth_seed: Option<u64>,
+}
+
+#[derive(clap::Args, Debug)]
+struct MutateArgs {
+  /// DOT file, or JSON file of --format json with the .json extension, the
+  /// generated graph without it
+  #[arg(env = "DAG_MUTATE_INPUT")]
+  input: Option<PathBuf>,
+
+  #[arg(long, env = "DAG_MUTATE_ADD_EDGES", default_value_t = 0)]
+  add_edges: usize,
+
+  #[arg(long, env = "DAG_MUTATE_REMOVE_EDGES", default_value_t = 0)]
+  remove_edges: usize,
+
+  /// Edges to and from the removed nodes are removed too
+  #[arg(long, env = "DAG_MUTATE_REMOVE_NODES", default_value_t = 0)]
+  remove_nodes: usize,
+
+  /// Seed of the mutations, the graph use the main seed
+  #[arg(long, env = "DAG_MUTATE_SEED")]
+  mutation_seed: Option<u64>,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum ColorWhen {
+  Auto,
+  Always,
+  Never,
+}
+
+impl ColorWhen {
+  fn enabled(self) -> bool {
+    match self {
+      ColorWhen::Always => true,
+      ColorWhen::Never => false,
+      ColorWhen::Auto => {
+        std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+          && std::io::stderr().is_terminal()
+      }
+    }
+  }
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum ValidateOutput {
+  /// A table of the checks followed by the status
+  Text,
+  /// The report as a JSON object
+  Json,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum DiffFormat {
+  /// `+`, `-` or `~` followed by the node or the edge
+  Text,
+  /// Added in green, removed dashed in red and changed data in orange
+  Dot,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+enum StatsFormat {
+  /// A table of the metrics
+  Text,
+  /// An object with the counts and the distributions as arrays
+  Json,
 }
 
 #[derive(clap::ValueEnum, Clone, Debug)]
@@ -43,39 +689,1045 @@ enum Format {
   Dot,
   Mermaid,
   Both,
+  /// A self-contained page with an SVG drawing of the graph
+  Html,
+  /// JSON Graph Format
+  Jgf,
+  /// DIMACS edge format with a legend of the node numbers
+  Dimacs,
+  /// METIS graph format, see --mapping for the node numbers
+  Metis,
+  /// Excel workbook with a Nodes sheet and an Edges sheet
+  #[cfg(feature = "xlsx")]
+  Xlsx,
+  /// Lossless JSON of the graph that can be loaded back
+  Json,
+  /// One node per line in topological order, its short UUID then its label
+  Topo,
+  /// A tree drawn with box-drawing characters for the terminal
+  Ascii,
+  /// GraphML for yEd and Gephi
+  Graphml,
+  /// GEXF for Gephi
+  Gexf,
 }
 
-fn main() {
-  let args = Args::parse();
-  let seed = args.seed.unwrap_or_else(|| rand::rng().random());
+impl Format {
+  // of the files written in --output-dir
+  fn extensions(&self) -> &'static [&'static str] {
+    match self {
+      Format::Dot => &["dot"],
+      Format::Mermaid => &["mmd"],
+      Format::Both => &["dot", "mmd"],
+      Format::Html => &["html"],
+      Format::Jgf => &["jgf"],
+      Format::Dimacs => &["dimacs"],
+      Format::Metis => &["metis"],
+      #[cfg(feature = "xlsx")]
+      Format::Xlsx => &["xlsx"],
+      Format::Json => &["json"],
+      Format::Topo => &["txt"],
+      Format::Ascii => &["txt"],
+      Format::Graphml => &["graphml"],
+      Format::Gexf => &["gexf"],
+    }
+  }
+}
+
+#[derive(Debug, Snafu)]
+enum Error {
+  #[snafu(display("Can't open {}", path.display()))]
+  Open {
+    source: std::io::Error,
+    path: PathBuf,
+  },
+  #[snafu(display("Can't write {}", path.display()))]
+  Write {
+    source: std::io::Error,
+    path: PathBuf,
+  },
+  #[snafu(display("Can't load {}", path.display()))]
+  Config {
+    source: toml::de::Error,
+    path: PathBuf,
+  },
+  #[snafu(display("Can't load {}", path.display()))]
+  Checkpoint {
+    source: generator::Error,
+    path: PathBuf,
+  },
+  #[snafu(display("Can't load {}", path.display()))]
+  Schema {
+    source: schema::Error,
+    path: PathBuf,
+  },
+  Generate {
+    source: generator::Error,
+  },
+  Simulate {
+    source: simulate::Error,
+  },
+  Timestamps {
+    source: transform::Error,
+  },
+  Rename {
+    source: transform::Error,
+  },
+  TimestampsFrom {
+    source: schema::Error,
+  },
+  #[snafu(display("Can't export the graph"))]
+  Export {
+    source: graph::Error,
+  },
+  #[cfg(feature = "xlsx")]
+  Xlsx {
+    source: rust_xlsxwriter::XlsxError,
+  },
+  Json {
+    source: serde_json::Error,
+  },
+  #[snafu(display("Can't keep the branch of --root"))]
+  Root {
+    source: graph::Error,
+  },
+  #[snafu(display("Can't import {}", path.display()))]
+  Import {
+    source: graph::Error,
+    path: PathBuf,
+  },
+  #[snafu(display("Can't import {}", path.display()))]
+  ImportJson {
+    source: serde_json::Error,
+    path: PathBuf,
+  },
+  #[snafu(display("Can't write the output"))]
+  Output {
+    source: std::io::Error,
+  },
+  #[cfg(feature = "explore")]
+  #[snafu(display("Can't use the terminal"))]
+  Terminal {
+    source: std::io::Error,
+  },
+  #[snafu(display("Only DOT and Mermaid can be split, not {format:?}"))]
+  SplitFormat {
+    format: Format,
+  },
+  #[snafu(display("No seed match the constraints in {tries} tries from {seed}"))]
+  SeedNotFound {
+    seed: u64,
+    tries: usize,
+  },
+  #[snafu(display("--format both writes two files, it needs --output-dir"))]
+  BothOutput,
+  #[snafu(display("--summary describes a single graph, {command} doesn't make one"))]
+  SummaryCommand {
+    command: &'static str,
+  },
+  #[snafu(display("{} doesn't follow the generation parameters", path.display()))]
+  Invalid {
+    path: PathBuf,
+  },
+  #[snafu(display("{count} outputs differ from the golden outputs"))]
+  SelfTest {
+    count: usize,
+  },
+}
+
+#[snafu::report]
+fn main() -> Result<(), Error> {
+  let command = localize(Args::command(), lang());
+  let args = Args::from_arg_matches(&command.get_matches()).unwrap_or_else(|e| e.exit());
+  let lang = args.lang;
+  let resume = args
+    .resume
+    .as_ref()
+    .map(|path| {
+      let file = File::open(path).context(OpenSnafu { path })?;
+      generator::Checkpoint::from_reader(BufReader::new(file)).context(CheckpointSnafu { path })
+    })
+    .transpose()?;
+  let seed = args
+    .seed
+    .or(resume.as_ref().map(generator::Checkpoint::seed))
+    .unwrap_or_else(|| rand::rng().random());
+
+  // the summary is about a single graph
+  let without_graph = match &args.command {
+    Some(Command::Validate(_)) => None,
+    Some(Command::Diff(_)) => Some("diff"),
+    Some(Command::Sweep(_)) => Some("sweep"),
+    Some(Command::Compare(_)) => Some("compare"),
+    Some(Command::Watch(_)) => Some("watch"),
+    Some(Command::FindSeed(_)) => Some("find-seed"),
+    Some(Command::SelfTest(_)) => Some("self-test"),
+    _ if args.dry_run => Some("--dry-run"),
+    _ => None,
+  };
+  if let (Some(_), Some(command)) = (args.summary, without_graph) {
+    return SummaryCommandSnafu { command }.fail();
+  }
+
+  if let Some(Command::SelfTest(self_test)) = &args.command {
+    return run_self_test(self_test.bless.as_deref());
+  }
+  // refused before the graph is made, the split parts go to files anyway
+  ensure!(
+    !matches!(args.format, Format::Both)
+      || args.output_dir.is_some()
+      || args.split_levels.is_some()
+      || args.split_by_subtree,
+    BothOutputSnafu
+  );
+
+  let mut schema = args
+    .node_data_schema
+    .as_ref()
+    .map(|path| {
+      let file = File::open(path).context(OpenSnafu { path })?;
+      schema::Schema::from_reader(file).context(SchemaSnafu { path })
+    })
+    .transpose()?;
+  if !args.attr.is_empty() {
+    // an attribute replaces the schema field of the same name
+    schema
+      .get_or_insert_default()
+      .extend(args.attr.iter().cloned());
+  }
 
-  let config = generator::Config {
+  let mut config = generator::Config {
     depth: args.depth.into(),
     width_mean: args.width_mean,
     width_std_dev: args.width_std_dev,
     child_mean: args.child_mean,
     child_std_dev: args.child_std_dev,
+    width_dist: args.width_dist,
+    child_dist: args.child_dist,
     seed,
-    name: args.name,
+    name: args.name.clone(),
+    schema,
+    edge_schema: (!args.edge_attr.is_empty()).then(|| args.edge_attr.iter().cloned().collect()),
+    uuid_version: args.uuid_version,
+    strategy: args.strategy,
+    shape: args.shape,
+    extra_edge_density: args.extra_edge_density,
+    edge_weight_mean: args.edge_weight_mean,
+    edge_weight_std_dev: args.edge_weight_std_dev,
   };
 
-  let graph = generator::generate(&config).unwrap();
+  if let Some(Command::Diff(diff)) = &args.command {
+    let before = import(&diff.before)?;
+    let after = import(&diff.after)?;
+    eprintln!("done import");
+    let changes = before.diff(&after);
+    match diff.diff_format {
+      DiffFormat::Text => print!("{changes}"),
+      DiffFormat::Dot => print!("{}", before.diff_dot(&after)),
+    }
+    eprintln!(
+      "{} added and {} removed nodes, {} added and {} removed edges, {} changed nodes",
+      changes.added_nodes.len(),
+      changes.removed_nodes.len(),
+      changes.added_edges.len(),
+      changes.removed_edges.len(),
+      changes.changed_nodes.len()
+    );
+    return Ok(());
+  }
 
-  eprintln!("done generate");
+  if let Some(Command::Validate(validate)) = &args.command {
+    let graph = import_branch(&validate.input, validate.root)?;
+    eprintln!("done import");
+    let status = print_validation(&args, &graph, &config);
+    print_summary(&args, &graph, seed, Some(status == report::Status::Ok));
+    ensure!(
+      status == report::Status::Ok,
+      InvalidSnafu {
+        path: &validate.input
+      }
+    );
+    return Ok(());
+  }
 
-  match args.format {
-    Format::Dot => print!("{}", graph.dot()),
-    Format::Mermaid => print!("{}", graph.mermaid()),
-    Format::Both => {
-      print!("{}", graph.dot());
-      print!("{}", graph.mermaid());
+  let exact = args
+    .exact_nodes
+    .zip(args.exact_depth)
+    .zip(args.exact_edges)
+    .map(|((nodes, depth), edges)| generator::Exact {
+      nodes,
+      depth,
+      edges,
+    });
+
+  if args.dry_run {
+    // the same generation as below
+    let plan = match (&exact, args.preset) {
+      (Some(exact), _) => generator::plan_exact(&config, exact),
+      (None, Some(preset)) => generator::plan_preset(&config, preset),
+      (None, None) => generator::plan(&config),
     }
+    .context(GenerateSnafu)?;
+    println!("Dry run with seed {seed}:");
+    println!(" - Level widths: {}", plan.widths.iter().join(", "));
+    println!(" - Expected nodes: {}", plan.nodes());
+    println!(" - Expected edges: {}", plan.edges());
+    println!(" - Estimated memory: {}", human_size(plan.memory()));
+    let output = match args.format {
+      Format::Dot => plan.dot_size(),
+      Format::Mermaid => plan.mermaid_size(),
+      Format::Both => plan.dot_size() + plan.mermaid_size(),
+      Format::Html => plan.html_size(),
+      Format::Jgf => plan.jgf_size(),
+      Format::Dimacs => plan.dimacs_size(),
+      Format::Metis => plan.metis_size(),
+      Format::Json => plan.json_size(),
+      Format::Topo => plan.topo_size(),
+      Format::Ascii => plan.ascii_size(),
+      Format::Graphml => plan.graphml_size(),
+      Format::Gexf => plan.gexf_size(),
+      #[cfg(feature = "xlsx")]
+      Format::Xlsx => plan.xlsx_size(),
+    };
+    println!(" - Estimated output: {}", human_size(output));
+    return Ok(());
+  }
+
+  if let Some(Command::Sweep(sweep)) = &args.command {
+    let sweep = sweep::sweep(&config, sweep.runs).context(GenerateSnafu)?;
+    println!(
+      "Sweep of {} runs from seed {seed} to {}:",
+      sweep.runs,
+      seed.wrapping_add(sweep.runs as u64 - 1)
+    );
+    // nothing to do if stdout is closed
+    let _ = sweep.table(lang).write(&mut std::io::stdout(), false);
+    return Ok(());
+  }
+
+  if let Some(Command::Compare(compare)) = &args.command {
+    let [a, b] = compare.configs.as_slice() else {
+      clap::Error::raw(
+        clap::error::ErrorKind::WrongNumberOfValues,
+        "--config must be given twice\n",
+      )
+      .exit()
+    };
+    let cfg_a = parameters(a)?.apply(&config);
+    let cfg_b = parameters(b)?.apply(&config);
+    let comparison = sweep::compare(&cfg_a, &cfg_b, compare.runs).context(GenerateSnafu)?;
+    println!(
+      "Comparison of {} runs, A: {} from seed {}, B: {} from seed {}:",
+      compare.runs,
+      a.display(),
+      cfg_a.seed,
+      b.display(),
+      cfg_b.seed
+    );
+    // nothing to do if stdout is closed
+    let _ = comparison.table(lang).write(&mut std::io::stdout(), false);
+    return Ok(());
+  }
+
+  if let Some(Command::Watch(watch)) = &args.command {
+    let Some(output) = &args.output else {
+      clap::Error::raw(
+        clap::error::ErrorKind::MissingRequiredArgument,
+        "watch rewrites the file of --output\n",
+      )
+      .exit()
+    };
+    return run_watch(&args, watch, output, &config);
   }
 
-  match validator::validator(&graph, &config) {
-    Ok(_) => eprintln!("OK"),
-    Err(_) => eprintln!("FAIL"),
+  if let Some(Command::FindSeed(find)) = &args.command {
+    let constraints = sweep::Constraints {
+      nodes: find.nodes.clone(),
+      depth: find.levels.clone(),
+      leaves: find.leaves.clone(),
+      max_width: find.max_width.clone(),
+    };
+    let found = sweep::find_seed(&config, &constraints, find.tries).context(GenerateSnafu)?;
+    let found = found.context(SeedNotFoundSnafu {
+      seed,
+      tries: find.tries.get(),
+    })?;
+    eprintln!(
+      "Found after {} tries",
+      found.wrapping_sub(seed).wrapping_add(1)
+    );
+    println!("{found}");
+    return Ok(());
+  }
+
+  let input = match &args.command {
+    Some(Command::Convert(convert)) => Some((&convert.input, convert.root)),
+    Some(Command::Stats(stats)) => stats.input.as_ref().map(|input| (input, None)),
+    Some(Command::Mutate(mutate)) => mutate.input.as_ref().map(|input| (input, None)),
+    Some(Command::Grow(grow)) => grow.input.as_ref().map(|input| (input, None)),
+    _ => None,
+  };
+  let mut graph = if let Some((input, root)) = input {
+    let graph = import_branch(input, root)?;
+    eprintln!("done import");
+    graph
+  } else {
+    let graph = match (&exact, args.preset) {
+      (Some(exact), _) => {
+        config.depth = exact.depth;
+        generator::generate_exact(&config, exact)
+      }
+      (None, Some(preset)) => generator::generate_preset(&config, preset),
+      (None, None) if args.checkpoint.is_some() || resume.is_some() => {
+        let interval = Duration::from_secs(args.checkpoint_interval);
+        generator::generate_checkpointed(&config, resume, interval, |checkpoint| {
+          match &args.checkpoint {
+            Some(path) => save_checkpoint(path, checkpoint),
+            None => Ok(()),
+          }
+        })
+      }
+      (None, None) => generator::generate(&config),
+    }
+    .context(GenerateSnafu)?;
+    eprintln!("done generate");
+    graph
+  };
+
+  // the distributions are not used by the exact generation and the presets
+  let mut validate = exact.is_none() && args.preset.is_none();
+  let mut schedule = None;
+  let command_seed = match &args.command {
+    Some(Command::Mutate(mutate)) => {
+      let seed = mutate.mutation_seed.unwrap_or_else(|| rand::rng().random());
+      let mutation = transform::Mutation {
+        remove_nodes: mutate.remove_nodes,
+        remove_edges: mutate.remove_edges,
+        add_edges: mutate.add_edges,
+      };
+      let original = graph.clone();
+      let applied = transform::mutate(&mut graph, mutation, &mut StdRng::seed_from_u64(seed));
+      eprintln!(
+        "done mutate: {} nodes removed, {} edges removed, {} edges added, similarity {:.3}",
+        applied.remove_nodes,
+        applied.remove_edges,
+        applied.add_edges,
+        original.similarity(&graph).score
+      );
+      // a mutated graph no longer follow the generation parameters
+      validate = false;
+      Some(("Mutation", seed))
+    }
+    Some(Command::Grow(grow)) => {
+      let seed = grow.growth_seed.unwrap_or_else(|| rand::rng().random());
+      generator::grow(&mut graph, &config, grow.levels, seed).context(GenerateSnafu)?;
+      eprintln!("done grow");
+      config.depth += grow.levels;
+      // only the new levels follow the generation parameters
+      if grow.input.is_some() {
+        validate = false;
+      }
+      Some(("Growth", seed))
+    }
+    Some(Command::Prune(prune)) => {
+      let seed = prune.prune_seed.unwrap_or_else(|| rand::rng().random());
+      let prune = transform::Prune {
+        fraction: prune.fraction,
+        max_depth: prune.max_depth,
+      };
+      let removed = transform::prune(&mut graph, prune, &mut StdRng::seed_from_u64(seed));
+      eprintln!("done prune: {removed} nodes removed");
+      Some(("Prune", seed))
+    }
+    Some(Command::Rename(rename)) => {
+      let seed = rename.rename_seed.unwrap_or_else(|| rand::rng().random());
+      let renamed = transform::rename(
+        &mut graph,
+        rename.fraction,
+        &mut StdRng::seed_from_u64(seed),
+      )
+      .context(RenameSnafu)?;
+      eprintln!("done rename: {renamed} nodes renamed");
+      Some(("Rename", seed))
+    }
+    Some(Command::Simulate(simulate)) => {
+      let seed = simulate
+        .simulation_seed
+        .unwrap_or_else(|| rand::rng().random());
+      let durations = simulate::durations(
+        &graph,
+        simulate.duration_mean,
+        simulate.duration_std_dev,
+        &mut StdRng::seed_from_u64(seed),
+      )
+      .context(SimulateSnafu)?;
+      schedule =
+        Some(simulate::simulate(&graph, &durations, simulate.workers).context(SimulateSnafu)?);
+      Some(("Simulation", seed))
+    }
+    Some(
+      Command::Sweep(_)
+      | Command::Validate(_)
+      | Command::Diff(_)
+      | Command::FindSeed(_)
+      | Command::Compare(_)
+      | Command::SelfTest(_)
+      | Command::Watch(_),
+    ) => {
+      unreachable!(
+        "sweep, validate, diff, find-seed, compare, self-test and watch don't generate a single \
+         graph"
+      )
+    }
+    Some(Command::Convert(convert)) => {
+      // an imported graph has no generation parameters
+      validate = false;
+      convert.anonymize.then(|| {
+        let seed = convert
+          .anonymize_seed
+          .unwrap_or_else(|| rand::rng().random());
+        graph = transform::anonymize(&graph, &mut StdRng::seed_from_u64(seed));
+        eprintln!("done anonymize");
+        ("Anonymization", seed)
+      })
+    }
+    // printed before the validation
+    Some(Command::Stats(_)) => None,
+    #[cfg(feature = "explore")]
+    Some(Command::Explore) => None,
+    Some(Command::Generate) | None => None,
+  };
+
+  // after the subcommand so added edges also go forward in time
+  if let Some(from) = args.timestamps_from {
+    transform::timestamps(
+      &mut graph,
+      schema::Timestamp::try_from(from).context(TimestampsFromSnafu)?,
+      args.timestamp_step,
+      &mut StdRng::seed_from_u64(seed),
+    )
+    .context(TimestampsSnafu)?;
+  }
+
+  if args.reduce {
+    let removed = graph.transitive_reduction();
+    eprintln!("done reduce: {removed} edges removed");
+    // the shortcuts drawn by the generator are gone
+    if removed > 0 {
+      validate = false;
+    }
+  }
+
+  #[cfg(feature = "explore")]
+  if let Some(Command::Explore) = &args.command {
+    dag::explore::run(&graph).context(TerminalSnafu)?;
+    print_summary(&args, &graph, seed, None);
+    return Ok(());
+  }
+
+  if let Some(Command::Stats(stats)) = &args.command {
+    let graph_stats = Stats::new(&graph);
+    match stats.stats_format {
+      StatsFormat::Text => graph_stats
+        .table(lang)
+        .write(&mut std::io::stdout(), args.color.enabled())
+        .context(OutputSnafu)?,
+      StatsFormat::Json => println!("{}", graph_stats.json()),
+    }
+    print_summary(&args, &graph, seed, None);
+    return Ok(());
+  }
+
+  let options = render_options(&args, &graph)?;
+
+  // the METIS mapping is written with the METIS output
+  if options.ids.is_some()
+    && !matches!(args.format, Format::Metis)
+    && let Some(path) = &args.mapping
+  {
+    let mapping = graph.id_mapping(options.clone()).to_string();
+    std::fs::write(path, mapping).context(WriteSnafu { path })?;
+  }
+
+  if let Some(path) = &args.data_out {
+    let data = graph.data_file(options.clone()).to_string();
+    std::fs::write(path, data).context(WriteSnafu { path })?;
+  }
+
+  let parts = match args.split_levels {
+    Some(levels) => Some(graph.split_levels(levels)),
+    None => args.split_by_subtree.then(|| graph.split_by_subtree()),
+  };
+  if let Some(parts) = parts {
+    let dir = args.output_dir.as_deref().unwrap_or(Path::new("."));
+    write_parts(&parts, &args.format, &options, dir)?;
+    eprintln!("done split: {} parts", parts.len());
+  } else {
+    write_output(&args, &graph, options.clone())?;
+    if let (Format::Metis, Some(path)) = (&args.format, &args.mapping) {
+      let mapping = graph.metis_with(options).mapping().to_string();
+      std::fs::write(path, mapping).context(WriteSnafu { path })?;
+    }
+  }
+
+  let color = args.color.enabled();
+  let mut valid = None;
+  if validate {
+    valid = Some(print_validation(&args, &graph, &config) == report::Status::Ok);
+  }
+
+  if args.analyze {
+    eprintln!("{}", lang.text(Text::AnalysisResults));
+    let _ = analyze::analyze(&graph)
+      .table(lang)
+      .write(&mut std::io::stderr(), color);
+  }
+
+  if let Some(schedule) = schedule {
+    eprintln!("{}", lang.text(Text::SimulationResults));
+    let _ = schedule.table(lang).write(&mut std::io::stderr(), color);
   }
 
   eprintln!("Seed used: {seed}");
+  if let Some((kind, seed)) = command_seed {
+    eprintln!("{kind} seed used: {seed}");
+  }
+
+  print_summary(&args, &graph, seed, valid);
+  Ok(())
+}
+
+// the --summary line, last on stderr
+fn print_summary(args: &Args, graph: &graph::AcyclicGraph, seed: u64, valid: Option<bool>) {
+  if let Some(format) = args.summary {
+    eprintln!("{}", report::Summary::new(graph, seed, valid).line(format));
+  }
+}
+
+// the report in the format of --validate-output on stderr
+fn print_validation(
+  args: &Args, graph: &graph::AcyclicGraph, config: &generator::Config,
+) -> report::Status {
+  let color = args.color.enabled();
+  match validator::report(graph, config, args.tolerance) {
+    Ok(report) => {
+      match args.validate_output {
+        ValidateOutput::Text => {
+          eprintln!("{}", args.lang.text(Text::ValidationResults));
+          // nothing to do if stderr is closed
+          let _ = report.table(args.lang).write(&mut std::io::stderr(), color);
+          eprintln!("{}", report.status.paint(color));
+        }
+        ValidateOutput::Json => eprintln!("{}", report.json()),
+      }
+      report.status
+    }
+    Err(e) => {
+      eprintln!("{e}");
+      report::Status::Fail
+    }
+  }
+}
+
+fn run_self_test(bless: Option<&Path>) -> Result<(), Error> {
+  if let Some(dir) = bless {
+    let outputs = self_test::render().context(GenerateSnafu)?;
+    for (file, output) in &outputs {
+      let path = dir.join(file);
+      std::fs::write(&path, output).context(WriteSnafu { path })?;
+    }
+    println!(
+      "{} golden outputs written to {}",
+      outputs.len(),
+      dir.display()
+    );
+    return Ok(());
+  }
+
+  let mismatches = self_test::check().context(GenerateSnafu)?;
+  for mismatch in &mismatches {
+    eprintln!("{mismatch}");
+  }
+  ensure!(
+    mismatches.is_empty(),
+    SelfTestSnafu {
+      count: mismatches.len()
+    }
+  );
+  println!(
+    "{} outputs of seeds {} match the golden outputs",
+    self_test::SEEDS.len() * self_test::FORMATS.len(),
+    self_test::SEEDS.iter().join(", ")
+  );
+  Ok(())
+}
+
+// written beside then renamed so an interruption while writing keeps the
+// previous checkpoint
+fn save_checkpoint(path: &Path, checkpoint: &generator::Checkpoint) -> std::io::Result<()> {
+  let mut partial = path.as_os_str().to_owned();
+  partial.push(".partial");
+  let mut file = std::io::BufWriter::new(File::create(&partial)?);
+  checkpoint.write(&mut file)?;
+  file.flush()?;
+  std::fs::rename(&partial, path)?;
+  eprintln!("checkpoint at level {}", checkpoint.level());
+  Ok(())
+}
+
+// the options of the command line, checked in sanitize error mode
+fn render_options(args: &Args, graph: &graph::AcyclicGraph) -> Result<graph::RenderOptions, Error> {
+  let options = graph::RenderOptions {
+    label: args.label_template.clone(),
+    scores: args.size_by_pagerank.then(|| graph.pagerank(0.85)),
+    clusters: args.dot_clusters,
+    tables: args.dot_tables,
+    link: args.link_template.clone(),
+    sort_by: args.sort_by,
+    edge_style: args.edge_style,
+    sanitize: args.sanitize,
+    references: Default::default(),
+    ids: args.sequential_ids.then(|| graph.sequential_ids()),
+    rank_hints: args.dot_rank_hints,
+    omit_data: args.data_out.is_some(),
+    rankdir: args.rankdir,
+    shapes: args.node_shapes.unwrap_or_default(),
+    color_by_depth: args.color_by_depth,
+    show_uuid: args.show_uuid,
+  };
+  if options.sanitize == graph::Sanitize::Error {
+    graph.check_names(&options).context(ExportSnafu)?;
+  }
+  Ok(options)
+}
+
+// a JSON graph of --format json or a DOT digraph
+fn import(path: &Path) -> Result<graph::AcyclicGraph, Error> {
+  let input = std::fs::read_to_string(path).context(OpenSnafu { path })?;
+  if path
+    .extension()
+    .is_some_and(|extension| extension == "json")
+  {
+    graph::AcyclicGraph::from_json_reader(input.as_bytes()).context(ImportJsonSnafu { path })
+  } else {
+    graph::AcyclicGraph::from_dot(&input).context(ImportSnafu { path })
+  }
+}
+
+// the imported graph or the branch of --root
+fn import_branch(path: &Path, root: Option<Uuid>) -> Result<graph::AcyclicGraph, Error> {
+  let graph = import(path)?;
+  match root {
+    Some(root) => graph.subgraph(root).context(RootSnafu),
+    None => Ok(graph),
+  }
+}
+
+// the whole output of the graph in a format, written as it's rendered so a
+// large graph isn't held in memory twice. The errors of `w` are reported for
+// `path`, stdout without path.
+fn render(
+  graph: &graph::AcyclicGraph, format: &Format, options: graph::RenderOptions, w: &mut impl Write,
+  path: Option<&Path>,
+) -> Result<(), Error> {
+  let written = match format {
+    Format::Dot => graph.dot_with(options).write(w),
+    Format::Mermaid => graph.mermaid_with(options).write(w),
+    Format::Html => write!(w, "{}", graph.html_with(options)),
+    Format::Jgf => write!(w, "{}", graph.jgf_with(options)),
+    Format::Dimacs => write!(w, "{}", graph.dimacs_with(options)),
+    Format::Metis => write!(w, "{}", graph.metis_with(options)),
+    Format::Topo => write!(w, "{}", graph.topo_with(options)),
+    Format::Ascii => write!(w, "{}", graph.ascii_with(options)),
+    Format::Graphml => write!(w, "{}", graph.graphml_with(options)),
+    Format::Gexf => write!(w, "{}", graph.gexf_with(options)),
+    #[cfg(feature = "xlsx")]
+    Format::Xlsx => w.write_all(&graph.xlsx_with(options).context(XlsxSnafu)?),
+    // the render options would make it lossy
+    Format::Json => {
+      graph.to_json_writer(&mut *w).context(JsonSnafu)?;
+      Ok(())
+    }
+    // a single output never gets both graphs back to back
+    Format::Both => return BothOutputSnafu.fail(),
+  };
+  written
+    .and_then(|()| w.flush())
+    .map_err(|source| match path {
+      Some(path) => Error::Write {
+        source,
+        path: path.to_path_buf(),
+      },
+      None => Error::Output { source },
+    })
+}
+
+fn render_file(
+  graph: &graph::AcyclicGraph, format: &Format, options: graph::RenderOptions, path: &Path,
+) -> Result<(), Error> {
+  let file = File::create(path).context(WriteSnafu { path })?;
+  render(
+    graph,
+    format,
+    options,
+    &mut BufWriter::new(file),
+    Some(path),
+  )
+}
+
+// to stdout, --output or one file per format of --format both in
+// --output-dir
+fn write_output(
+  args: &Args, graph: &graph::AcyclicGraph, options: graph::RenderOptions,
+) -> Result<(), Error> {
+  if let Some(dir) = &args.output_dir {
+    std::fs::create_dir_all(dir).context(WriteSnafu { path: dir })?;
+    for extension in args.format.extensions() {
+      let format = match *extension {
+        "dot" => &Format::Dot,
+        "mmd" => &Format::Mermaid,
+        _ => &args.format,
+      };
+      let path = dir.join(format!("graph.{extension}"));
+      render_file(graph, format, options.clone(), &path)?;
+    }
+    return Ok(());
+  }
+  match &args.output {
+    Some(path) => render_file(graph, &args.format, options, path),
+    None => {
+      let stdout = BufWriter::new(std::io::stdout().lock());
+      render(graph, &args.format, options, &mut { stdout }, None)
+    }
+  }
+}
+
+// polls the modification time of the configuration file, an invalid file is
+// reported and the previous output is kept until the next change
+fn run_watch(
+  args: &Args, watch: &WatchArgs, output: &Path, base: &generator::Config,
+) -> Result<(), Error> {
+  let mut last = None;
+  loop {
+    let modified = Some(
+      std::fs::metadata(&watch.config)
+        .and_then(|metadata| metadata.modified())
+        .ok(),
+    );
+    if modified != last {
+      last = modified;
+      match regenerate(args, watch, output, base) {
+        Ok(nodes) => eprintln!(
+          "done watch: {} written with {nodes} nodes",
+          output.display()
+        ),
+        Err(e) => eprintln!("Error: {}", snafu::Report::from_error(e)),
+      }
+    }
+    std::thread::sleep(Duration::from_millis(watch.interval));
+  }
+}
+
+fn regenerate(
+  args: &Args, watch: &WatchArgs, output: &Path, base: &generator::Config,
+) -> Result<usize, Error> {
+  let config = parameters(&watch.config)?.apply(base);
+  let graph = generator::generate(&config).context(GenerateSnafu)?;
+  let options = render_options(args, &graph)?;
+  render_file(&graph, &args.format, options, output)?;
+  Ok(graph.nodes().len())
+}
+
+fn parameters(path: &Path) -> Result<generator::Parameters, Error> {
+  let toml = std::fs::read_to_string(path).context(OpenSnafu { path })?;
+  toml::from_str(&toml).context(ConfigSnafu { path })
+}
+
+// one file per part and format, the references of the part are dashed
+fn write_parts(
+  parts: &[graph::Part], format: &Format, options: &graph::RenderOptions, dir: &Path,
+) -> Result<(), Error> {
+  let extensions: &[&str] = match format {
+    Format::Dot => &["dot"],
+    Format::Mermaid => &["mmd"],
+    Format::Both => &["dot", "mmd"],
+    format => {
+      return SplitFormatSnafu {
+        format: format.clone(),
+      }
+      .fail();
+    }
+  };
+  std::fs::create_dir_all(dir).context(WriteSnafu { path: dir })?;
+  for (i, part) in parts.iter().enumerate() {
+    let options = graph::RenderOptions {
+      references: part.references.clone(),
+      ..options.clone()
+    };
+    for extension in extensions {
+      let output = match *extension {
+        "dot" => part.graph.dot_with(options.clone()).to_string(),
+        _ => part.graph.mermaid_with(options.clone()).to_string(),
+      };
+      let path = dir.join(format!("part-{:03}.{extension}", i + 1));
+      std::fs::write(&path, output).context(WriteSnafu { path })?;
+    }
+  }
+  Ok(())
+}
+
+// the help is built before parsing, so --lang and DAG_LANG are read first, an
+// invalid value is reported by the parser
+fn lang() -> Lang {
+  let mut args = std::env::args().skip(1);
+  let mut lang = None;
+  while let Some(arg) = args.next() {
+    if arg == "--lang" {
+      lang = args.next();
+    } else if let Some(value) = arg.strip_prefix("--lang=") {
+      lang = Some(value.to_string());
+    }
+  }
+  lang
+    .or_else(|| std::env::var("DAG_LANG").ok())
+    .and_then(|lang| Lang::from_str(&lang, true).ok())
+    .unwrap_or_default()
+}
+
+fn localize(command: clap::Command, lang: Lang) -> clap::Command {
+  let name = command.get_name().to_string();
+  let mut command = command.mut_args(|arg| match lang.help(&name, Some(arg.get_id().as_str())) {
+    Some(help) => arg.help(help),
+    None => arg,
+  });
+  if let Some(about) = lang.help(&name, None) {
+    command = command.about(about);
+  }
+  command.mut_subcommands(|subcommand| localize(subcommand, lang))
+}
+
+fn human_size(bytes: usize) -> String {
+  const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+  let mut size = bytes as f64;
+  let mut unit = 0;
+  while size >= 1024.0 && unit < UNITS.len() - 1 {
+    size /= 1024.0;
+    unit += 1;
+  }
+  if unit == 0 {
+    format!("{bytes} B")
+  } else {
+    format!("{size:.1} {}", UNITS[unit])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(args: &[&str]) -> Args {
+    Args::try_parse_from(args).unwrap()
+  }
+
+  #[test]
+  fn test_global_options() {
+    Args::command().debug_assert();
+
+    let args = parse(&[
+      "dag", "convert", "g.dot", "--format", "json", "--output", "g.json",
+    ]);
+    assert!(matches!(args.format, Format::Json));
+    assert_eq!(args.output, Some(PathBuf::from("g.json")));
+    let args = parse(&["dag", "validate", "g.json", "--depth", "3"]);
+    assert_eq!(args.depth.get(), 3);
+
+    // the seed of the graph and the seed of the command are apart
+    let args = parse(&["dag", "mutate", "--seed", "1", "--mutation-seed", "2"]);
+    assert_eq!(args.seed, Some(1));
+    let Some(Command::Mutate(mutate)) = args.command else {
+      panic!("not the mutate command")
+    };
+    assert_eq!(mutate.mutation_seed, Some(2));
+  }
+
+  #[test]
+  fn test_preset_conflicts() {
+    for other in [&["--checkpoint", "gen.ckpt"][..], &["--resume", "gen.ckpt"]] {
+      let args = [&["dag", "--preset", "diamond"], other].concat();
+      let error = Args::try_parse_from(args).unwrap_err();
+      assert_eq!(error.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+  }
+
+  #[test]
+  fn test_anonymize() {
+    let args = parse(&[
+      "dag",
+      "convert",
+      "g.json",
+      "--anonymize",
+      "--anonymize-seed",
+      "3",
+    ]);
+    let Some(Command::Convert(convert)) = args.command else {
+      panic!("not the convert command")
+    };
+    assert!(convert.anonymize);
+    assert_eq!(convert.anonymize_seed, Some(3));
+    // the seed alone would do nothing
+    let error =
+      Args::try_parse_from(["dag", "convert", "g.json", "--anonymize-seed", "3"]).unwrap_err();
+    assert_eq!(
+      error.kind(),
+      clap::error::ErrorKind::MissingRequiredArgument
+    );
+  }
+
+  #[test]
+  fn test_tolerance() {
+    assert_eq!(parse(&["dag", "--tolerance", "1.5"]).tolerance, Some(1.5));
+    for value in ["-0.1", "NaN", "inf"] {
+      let error = Args::try_parse_from(["dag", &format!("--tolerance={value}")]).unwrap_err();
+      assert_eq!(error.kind(), clap::error::ErrorKind::ValueValidation);
+    }
+  }
+
+  #[test]
+  fn test_extra_edge_density() {
+    assert_eq!(
+      parse(&["dag", "--extra-edge-density", "1"]).extra_edge_density,
+      1.0
+    );
+    for value in ["-0.1", "1.5", "NaN", "inf"] {
+      let error =
+        Args::try_parse_from(["dag", &format!("--extra-edge-density={value}")]).unwrap_err();
+      assert_eq!(error.kind(), clap::error::ErrorKind::ValueValidation);
+    }
+  }
+
+  #[test]
+  fn test_split_output() {
+    let args = parse(&["dag", "--split-levels", "2", "--output-dir", "parts"]);
+    assert_eq!(args.output_dir, Some(PathBuf::from("parts")));
+    for split in [&["--split-levels", "2"][..], &["--split-by-subtree"]] {
+      let args = [&["dag"], split, &["--output", "g.dot"]].concat();
+      let error = Args::try_parse_from(args).unwrap_err();
+      assert_eq!(error.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+  }
+
+  #[test]
+  fn test_both_output() {
+    let graph = generator::generate(&generator::Config::default()).unwrap();
+    let mut output = Vec::new();
+    let rendered = render(
+      &graph,
+      &Format::Both,
+      graph::RenderOptions::default(),
+      &mut output,
+      None,
+    );
+    assert!(matches!(rendered, Err(Error::BothOutput)));
+    assert!(output.is_empty());
+  }
 }
@@ -0,0 +1,355 @@
+use std::{
+  fmt::{
+    self,
+    Display,
+    Formatter,
+  },
+  io::{
+    self,
+    Write,
+  },
+};
+
+use crate::{
+  graph::AcyclicGraph,
+  i18n::{
+    Lang,
+    Text,
+  },
+};
+
+const GREEN: &str = "\x1b[32m";
+const RED: &str = "\x1b[31m";
+const DIM: &str = "\x1b[2m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+  Ok,
+  Fail,
+  /// Only informative, can't fail
+  Info,
+}
+
+impl Status {
+  fn color(self) -> &'static str {
+    match self {
+      Status::Ok => GREEN,
+      Status::Fail => RED,
+      Status::Info => DIM,
+    }
+  }
+
+  pub fn paint(self, color: bool) -> Painted<Self> {
+    Painted {
+      value: self,
+      color: color.then_some(self.color()),
+    }
+  }
+}
+
+impl Display for Status {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    f.pad(match self {
+      Status::Ok => "OK",
+      Status::Fail => "FAIL",
+      Status::Info => "-",
+    })
+  }
+}
+
+/// Wrap a value in ANSI color codes, padding is applied to the value only
+pub struct Painted<T> {
+  value: T,
+  color: Option<&'static str>,
+}
+
+impl<T: Display> Display for Painted<T> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    match self.color {
+      Some(color) => {
+        f.write_str(color)?;
+        self.value.fmt(f)?;
+        f.write_str(RESET)
+      }
+      None => self.value.fmt(f),
+    }
+  }
+}
+
+/// A table with aligned columns, either checks with a status or metrics
+#[derive(Debug, Clone)]
+pub struct Table {
+  headers: Vec<&'static str>,
+  rows: Vec<(Vec<String>, Option<Status>)>,
+}
+
+impl Table {
+  /// Check, value, expected value and status columns
+  pub fn checks(lang: Lang) -> Self {
+    Self::new(
+      lang,
+      &[Text::Check, Text::Value, Text::Expected, Text::Status],
+    )
+  }
+
+  /// Metric and value columns
+  pub fn metrics(lang: Lang) -> Self {
+    Self::new(lang, &[Text::Metric, Text::Value])
+  }
+
+  /// Metric followed by the statistics of its values over several runs
+  pub fn statistics(lang: Lang) -> Self {
+    let mut table = Self::new(lang, &[Text::Metric, Text::Mean, Text::StdDev, Text::Min]);
+    // percentiles are not translated
+    table
+      .headers
+      .extend(["P50", "P90", "P99", lang.text(Text::Max)]);
+    table
+  }
+
+  /// Metric followed by its value for configs A and B and their difference
+  pub fn comparison(lang: Lang) -> Self {
+    let mut table = Self::new(lang, &[Text::Metric]);
+    table
+      .headers
+      .extend(["A", "B", lang.text(Text::Delta), "%"]);
+    table
+  }
+
+  fn new(lang: Lang, headers: &[Text]) -> Self {
+    Self {
+      headers: headers.iter().map(|&header| lang.text(header)).collect(),
+      rows: Vec::new(),
+    }
+  }
+
+  /// A row without status, for tables other than checks
+  pub fn cells(&mut self, cells: impl IntoIterator<Item = impl Display>) {
+    self.rows.push((
+      cells.into_iter().map(|cell| cell.to_string()).collect(),
+      None,
+    ));
+  }
+
+  pub fn row(
+    &mut self, check: impl Into<String>, value: impl Display, expected: impl Display,
+    status: Status,
+  ) {
+    self.rows.push((
+      vec![check.into(), value.to_string(), expected.to_string()],
+      Some(status),
+    ));
+  }
+
+  pub fn metric(&mut self, metric: impl Into<String>, value: impl Display) {
+    self
+      .rows
+      .push((vec![metric.into(), value.to_string()], None));
+  }
+
+  pub fn status(&self) -> Status {
+    if self
+      .rows
+      .iter()
+      .any(|(_, status)| *status == Some(Status::Fail))
+    {
+      Status::Fail
+    } else {
+      Status::Ok
+    }
+  }
+
+  /// Names of the failed checks
+  pub fn failed(&self) -> impl Iterator<Item = &str> {
+    self
+      .rows
+      .iter()
+      .filter(|(_, status)| *status == Some(Status::Fail))
+      .map(|(cells, _)| cells[0].as_str())
+  }
+
+  pub fn write(&self, w: &mut impl Write, color: bool) -> io::Result<()> {
+    let mut widths: Vec<usize> = self
+      .headers
+      .iter()
+      .map(|header| header.chars().count())
+      .collect();
+    for (cells, _) in &self.rows {
+      for (width, cell) in widths.iter_mut().zip(cells) {
+        *width = (*width).max(cell.chars().count());
+      }
+    }
+    // the last column is not padded to avoid trailing spaces
+    if let Some(last) = widths.last_mut() {
+      *last = 0;
+    }
+
+    let bold = |header| Painted {
+      value: header,
+      color: color.then_some(BOLD),
+    };
+    write!(w, " ")?;
+    for (header, width) in self.headers.iter().zip(&widths) {
+      write!(w, " {:width$}", bold(header))?;
+    }
+    writeln!(w)?;
+
+    for (cells, status) in &self.rows {
+      write!(w, " ")?;
+      for (cell, width) in cells.iter().zip(&widths) {
+        write!(w, " {cell:width$}")?;
+      }
+      if let Some(status) = status {
+        write!(w, " {}", status.paint(color))?;
+      }
+      writeln!(w)?;
+    }
+
+    Ok(())
+  }
+}
+
+/// Line format of a [`Summary`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+pub enum SummaryFormat {
+  /// Tab separated seed, nodes, edges, depth, roots, leaves and status
+  Tsv,
+  /// An object with the same fields
+  Json,
+}
+
+/// Counts of a generated graph and its validation status, printed on one
+/// line for the scripts generating many graphs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct Summary {
+  pub seed: u64,
+  pub nodes: usize,
+  pub edges: usize,
+  /// Number of levels, root level included
+  pub depth: usize,
+  pub roots: usize,
+  pub leaves: usize,
+  /// None when the graph isn't validated, like a mutated one
+  #[serde(rename = "status", serialize_with = "serialize_status")]
+  pub valid: Option<bool>,
+}
+
+fn status(valid: Option<bool>) -> &'static str {
+  match valid {
+    Some(true) => "ok",
+    Some(false) => "fail",
+    None => "skipped",
+  }
+}
+
+fn serialize_status<S: serde::Serializer>(
+  valid: &Option<bool>, serializer: S,
+) -> Result<S::Ok, S::Error> {
+  serializer.serialize_str(status(*valid))
+}
+
+impl Summary {
+  pub fn new(graph: &AcyclicGraph, seed: u64, valid: Option<bool>) -> Self {
+    let parents = graph.parents();
+    Self {
+      seed,
+      nodes: graph.nodes().len(),
+      edges: graph.nodes().values().map(|node| node.childs().len()).sum(),
+      depth: graph
+        .depths()
+        .into_values()
+        .max()
+        .map_or(0, |depth| depth + 1),
+      roots: graph
+        .nodes()
+        .keys()
+        .filter(|uuid| !parents.contains_key(uuid))
+        .count(),
+      leaves: graph
+        .nodes()
+        .values()
+        .filter(|node| node.childs().is_empty())
+        .count(),
+      valid,
+    }
+  }
+
+  /// `ok`, `fail` or `skipped`
+  pub fn status(&self) -> &'static str {
+    status(self.valid)
+  }
+
+  /// Without line break
+  pub fn line(&self, format: SummaryFormat) -> String {
+    match format {
+      SummaryFormat::Tsv => format!(
+        "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+        self.seed,
+        self.nodes,
+        self.edges,
+        self.depth,
+        self.roots,
+        self.leaves,
+        self.status()
+      ),
+      // only numbers and a known string, can't fail
+      SummaryFormat::Json => serde_json::to_string(self).unwrap(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_summary() {
+    // root -> a -> c, root -> b
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("Root");
+    let (a, _) = graph.add_node("A");
+    let (b, _) = graph.add_node("B");
+    let (c, _) = graph.add_node("C");
+    for (parent, child) in [(root, a), (root, b), (a, c)] {
+      graph.add_child(parent, child).unwrap();
+    }
+
+    let summary = Summary::new(&graph, 42, Some(true));
+    assert_eq!(summary.line(SummaryFormat::Tsv), "42\t4\t3\t3\t1\t2\tok");
+    assert_eq!(
+      summary.line(SummaryFormat::Json),
+      r#"{"seed":42,"nodes":4,"edges":3,"depth":3,"roots":1,"leaves":2,"status":"ok"}"#
+    );
+    assert_eq!(Summary::new(&graph, 42, None).status(), "skipped");
+  }
+
+  #[test]
+  fn test_table() {
+    let mut table = Table::checks(Lang::En);
+    table.row("Roots", 1, 1, Status::Ok);
+    table.row("Average width", "8.75", "10.00", Status::Info);
+    assert_eq!(table.status(), Status::Ok);
+
+    let mut output = Vec::new();
+    table.write(&mut output, false).unwrap();
+    pretty_assertions::assert_eq!(
+      String::from_utf8(output).unwrap(),
+      "  Check         Value Expected Status\n  Roots         1     1        OK\n  Average width \
+       8.75  10.00    -\n"
+    );
+
+    table.row("Single path", "no", "yes", Status::Fail);
+    assert_eq!(table.status(), Status::Fail);
+    let mut output = Vec::new();
+    table.write(&mut output, true).unwrap();
+    assert!(
+      String::from_utf8(output)
+        .unwrap()
+        .contains("\x1b[31mFAIL\x1b[0m")
+    );
+  }
+}
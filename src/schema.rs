@@ -0,0 +1,535 @@
+use std::{
+  collections::BTreeMap,
+  fmt::{
+    self,
+    Display,
+    Formatter,
+  },
+  io::Read,
+  str::FromStr,
+};
+
+use itertools::Itertools;
+use rand::Rng;
+use serde::{
+  Deserialize,
+  Serialize,
+};
+use serde_json::{
+  Map,
+  Value,
+};
+use snafu::{
+  OptionExt,
+  ResultExt,
+  Snafu,
+  ensure,
+};
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+  #[snafu(display("Invalid schema"))]
+  Json { source: serde_json::Error },
+  #[snafu(display("Field {field}: {min} is greater than {max}"))]
+  InvalidRange {
+    field: String,
+    min: String,
+    max: String,
+  },
+  #[snafu(display("Field {field}: choice list is empty"))]
+  EmptyChoice { field: String },
+  #[snafu(display("Field {field}: {deviation} is not a valid standard deviation"))]
+  InvalidDeviation { field: String, deviation: f64 },
+  #[snafu(display("The date {date} is out of the timestamp range"))]
+  TimestampRange { date: Date },
+  #[snafu(display("Field {field}: {parameter} {value} is not finite"))]
+  NotFinite {
+    field: String,
+    parameter: &'static str,
+    value: f64,
+  },
+}
+
+/// Map a field name to its generator, fields are generated in name order to
+/// stay deterministic
+///
+/// ```json
+/// {
+///   "age": { "int": { "min": 18, "max": 99 } },
+///   "team": { "choice": ["red", "blue"] },
+///   "id": "uuid",
+///   "hired": { "date": { "from": "2010-01-01", "to": "2024-12-31" } }
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Schema(BTreeMap<String, Field>);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Field {
+  /// Uniform integer in the inclusive range
+  Int { min: i64, max: i64 },
+  /// Uniform number in the inclusive range
+  Uniform { min: f64, max: f64 },
+  /// Normal number
+  Normal { mean: f64, std_dev: f64 },
+  /// Positive number whose logarithm is normal with these parameters
+  #[serde(rename = "lognormal")]
+  LogNormal { mu: f64, sigma: f64 },
+  /// One of the values
+  Choice(Vec<Value>),
+  /// A random UUID
+  Uuid,
+  /// Uniform date in the inclusive range
+  Date { from: Date, to: Date },
+}
+
+/// A `YYYY-MM-DD` date stored as days since 1970-01-01, the year is between
+/// [`Date::MIN_YEAR`] and [`Date::MAX_YEAR`]
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  PartialOrd,
+  Ord,
+  Serialize,
+  Deserialize
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct Date(i64);
+
+/// A schema field written `name=distribution(a,b)` on the command line, like
+/// `latency=lognormal(5,2)` or `size=uniform(1,100)`. The distributions are
+/// `int(min,max)`, `uniform(min,max)`, `normal(mean,std_dev)` and
+/// `lognormal(mu,sigma)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute {
+  pub name: String,
+  pub field: Field,
+}
+
+impl FromStr for Attribute {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let invalid = || {
+      format!(
+        "invalid attribute {s:?}, expected name=distribution(a,b) with int, uniform, normal or \
+         lognormal"
+      )
+    };
+    let (name, spec) = s.split_once('=').ok_or_else(invalid)?;
+    let (kind, args) = spec
+      .trim()
+      .strip_suffix(')')
+      .and_then(|spec| spec.split_once('('))
+      .ok_or_else(invalid)?;
+    let Some((a, b)) = args.split(',').map(str::trim).collect_tuple() else {
+      return Err(invalid());
+    };
+    let float = |arg: &str| arg.parse::<f64>().map_err(|_| invalid());
+    let field = match kind.trim() {
+      "int" => Field::Int {
+        min: a.parse().map_err(|_| invalid())?,
+        max: b.parse().map_err(|_| invalid())?,
+      },
+      "uniform" => Field::Uniform {
+        min: float(a)?,
+        max: float(b)?,
+      },
+      "normal" => Field::Normal {
+        mean: float(a)?,
+        std_dev: float(b)?,
+      },
+      "lognormal" => Field::LogNormal {
+        mu: float(a)?,
+        sigma: float(b)?,
+      },
+      _ => return Err(invalid()),
+    };
+    let name = name.trim();
+    if name.is_empty() {
+      return Err(invalid());
+    }
+    let attribute = Attribute {
+      name: name.to_string(),
+      field,
+    };
+    Schema::from_iter([attribute.clone()])
+      .check()
+      .map_err(|e| e.to_string())?;
+    Ok(attribute)
+  }
+}
+
+impl Date {
+  pub const MAX_YEAR: i64 = 9999;
+  pub const MIN_YEAR: i64 = 0;
+}
+
+impl TryFrom<String> for Date {
+  type Error = String;
+
+  fn try_from(s: String) -> Result<Self, Self::Error> {
+    let invalid = || {
+      format!(
+        "invalid date {s:?}, expected YYYY-MM-DD with a year from {} to {}",
+        Date::MIN_YEAR,
+        Date::MAX_YEAR
+      )
+    };
+    let mut parts = s.splitn(3, '-');
+    let mut next = || parts.next().and_then(|part| part.parse::<i64>().ok());
+    let (Some(year), Some(month), Some(day)) = (next(), next(), next()) else {
+      return Err(invalid());
+    };
+    if !(Date::MIN_YEAR..=Date::MAX_YEAR).contains(&year)
+      || !(1..=12).contains(&month)
+      || !(1..=days_in_month(year, month)).contains(&day)
+    {
+      return Err(invalid());
+    }
+    Ok(Self(days_from_civil(year, month, day)))
+  }
+}
+
+impl FromStr for Date {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Self::try_from(s.to_string())
+  }
+}
+
+impl From<Date> for String {
+  fn from(date: Date) -> Self {
+    date.to_string()
+  }
+}
+
+impl Display for Date {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let (year, month, day) = civil_from_days(self.0);
+    write!(f, "{year:04}-{month:02}-{day:02}")
+  }
+}
+
+/// Seconds since 1970-01-01T00:00:00Z, displayed as RFC 3339
+#[derive(
+  Debug,
+  Clone,
+  Copy,
+  PartialEq,
+  Eq,
+  PartialOrd,
+  Ord,
+  Hash,
+  Serialize,
+  Deserialize
+)]
+pub struct Timestamp(pub i64);
+
+/// Midnight of the date, fail if the seconds overflow
+impl TryFrom<Date> for Timestamp {
+  type Error = Error;
+
+  fn try_from(date: Date) -> Result<Self, Error> {
+    let seconds = date.0.checked_mul(86400);
+    Ok(Self(seconds.context(TimestampRangeSnafu { date })?))
+  }
+}
+
+impl Display for Timestamp {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let seconds = self.0.rem_euclid(86400);
+    write!(
+      f,
+      "{}T{:02}:{:02}:{:02}Z",
+      Date(self.0.div_euclid(86400)),
+      seconds / 3600,
+      seconds / 60 % 60,
+      seconds % 60
+    )
+  }
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+  match month {
+    2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+    2 => 28,
+    4 | 6 | 9 | 11 => 30,
+    _ => 31,
+  }
+}
+
+// http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = year.div_euclid(400);
+  let yoe = year - era * 400;
+  let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+  let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+  era * 146097 + doe - 719468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+  let days = days + 719468;
+  let era = days.div_euclid(146097);
+  let doe = days - era * 146097;
+  let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + i64::from(month <= 2);
+  (year, month, day)
+}
+
+impl Schema {
+  pub fn from_reader(reader: impl Read) -> Result<Self, Error> {
+    let schema: Self = serde_json::from_reader(reader).context(JsonSnafu)?;
+    schema.check()?;
+    Ok(schema)
+  }
+
+  fn check(&self) -> Result<(), Error> {
+    for (field, generator) in &self.0 {
+      match generator {
+        Field::Int { min, max } => ensure!(
+          min <= max,
+          InvalidRangeSnafu {
+            field,
+            min: min.to_string(),
+            max: max.to_string()
+          }
+        ),
+        Field::Date { from, to } => ensure!(
+          from <= to,
+          InvalidRangeSnafu {
+            field,
+            min: from.to_string(),
+            max: to.to_string()
+          }
+        ),
+        Field::Uniform { min, max } => {
+          finite(field, "min", *min)?;
+          finite(field, "max", *max)?;
+          ensure!(
+            min <= max,
+            InvalidRangeSnafu {
+              field,
+              min: min.to_string(),
+              max: max.to_string()
+            }
+          );
+          // the width of the range is sampled
+          finite(field, "max - min", max - min)?;
+        }
+        Field::Normal { mean, std_dev } => {
+          finite(field, "mean", *mean)?;
+          check_deviation(field, *std_dev)?;
+        }
+        Field::LogNormal { mu, sigma } => {
+          finite(field, "mu", *mu)?;
+          check_deviation(field, *sigma)?;
+        }
+        Field::Choice(choices) => ensure!(!choices.is_empty(), EmptyChoiceSnafu { field }),
+        Field::Uuid => {}
+      }
+    }
+    Ok(())
+  }
+
+  /// Generate a JSON object with a value for every field
+  pub fn sample(&self, rng: &mut impl Rng) -> Map<String, Value> {
+    self
+      .0
+      .iter()
+      .map(|(name, field)| (name.clone(), field.sample(rng)))
+      .collect()
+  }
+}
+
+fn finite(field: &str, parameter: &'static str, value: f64) -> Result<(), Error> {
+  ensure!(
+    value.is_finite(),
+    NotFiniteSnafu {
+      field,
+      parameter,
+      value
+    }
+  );
+  Ok(())
+}
+
+fn check_deviation(field: &str, deviation: f64) -> Result<(), Error> {
+  ensure!(
+    deviation.is_finite() && deviation >= 0.0,
+    InvalidDeviationSnafu { field, deviation }
+  );
+  Ok(())
+}
+
+impl FromIterator<Attribute> for Schema {
+  fn from_iter<I: IntoIterator<Item = Attribute>>(attributes: I) -> Self {
+    let mut schema = Schema::default();
+    schema.extend(attributes);
+    schema
+  }
+}
+
+/// A field already in the schema is replaced
+impl Extend<Attribute> for Schema {
+  fn extend<I: IntoIterator<Item = Attribute>>(&mut self, attributes: I) {
+    self.0.extend(
+      attributes
+        .into_iter()
+        .map(|attribute| (attribute.name, attribute.field)),
+    );
+  }
+}
+
+impl Field {
+  fn sample(&self, rng: &mut impl Rng) -> Value {
+    match self {
+      Field::Int { min, max } => rng.random_range(*min..=*max).into(),
+      Field::Uniform { min, max } => rng.random_range(*min..=*max).into(),
+      Field::Normal { mean, std_dev } => (mean + std_dev * standard_normal(rng)).into(),
+      Field::LogNormal { mu, sigma } => (mu + sigma * standard_normal(rng)).exp().into(),
+      Field::Choice(choices) => choices[rng.random_range(0..choices.len())].clone(),
+      Field::Uuid => uuid::Builder::from_random_bytes(rng.random())
+        .into_uuid()
+        .to_string()
+        .into(),
+      Field::Date { from, to } => Date(rng.random_range(from.0..=to.0)).to_string().into(),
+    }
+  }
+}
+
+// Box-Muller transform, rand_distr is only a dependency of the generator
+fn standard_normal(rng: &mut impl Rng) -> f64 {
+  // in (0, 1] so the logarithm is finite
+  let u = 1.0 - rng.random::<f64>();
+  let v: f64 = rng.random();
+  (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos()
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{
+    SeedableRng,
+    rngs::StdRng,
+  };
+
+  use super::*;
+
+  #[test]
+  fn test_date() {
+    for s in ["1970-01-01", "2000-02-29", "2024-12-31", "1969-07-20"] {
+      assert_eq!(Date::try_from(s.to_string()).unwrap().to_string(), s);
+    }
+    assert_eq!(Date::try_from("1970-01-01".to_string()), Ok(Date(0)));
+    assert!(Date::try_from("2023-02-29".to_string()).is_err());
+    assert!(Date::try_from("2023-13-01".to_string()).is_err());
+    assert!(Date::try_from("yesterday".to_string()).is_err());
+    assert!(Date::try_from("9223372036854775807-01-01".to_string()).is_err());
+    assert!(Date::try_from("10000-01-01".to_string()).is_err());
+    assert_eq!(
+      Date::try_from("0000-03-01".to_string())
+        .unwrap()
+        .to_string(),
+      "0000-03-01"
+    );
+  }
+
+  #[test]
+  fn test_timestamp() {
+    let date: Date = "2024-02-29".parse().unwrap();
+    assert_eq!(
+      Timestamp::try_from(date).unwrap().to_string(),
+      "2024-02-29T00:00:00Z"
+    );
+    assert!(matches!(
+      Timestamp::try_from(Date(i64::MAX / 1000)),
+      Err(Error::TimestampRange { .. })
+    ));
+    assert_eq!(Timestamp(-1).to_string(), "1969-12-31T23:59:59Z");
+    assert_eq!(Timestamp(90061).to_string(), "1970-01-02T01:01:01Z");
+  }
+
+  #[test]
+  fn test_sample() {
+    let schema = Schema::from_reader(
+      r#"{
+        "age": { "int": { "min": 18, "max": 20 } },
+        "team": { "choice": ["red", "blue"] },
+        "id": "uuid",
+        "hired": { "date": { "from": "2020-01-01", "to": "2020-01-31" } }
+      }"#
+        .as_bytes(),
+    )
+    .unwrap();
+    let mut rng = StdRng::seed_from_u64(42);
+    let object = schema.sample(&mut rng);
+
+    assert!((18..=20).contains(&object["age"].as_i64().unwrap()));
+    assert!(["red", "blue"].contains(&object["team"].as_str().unwrap()));
+    assert!(uuid::Uuid::parse_str(object["id"].as_str().unwrap()).is_ok());
+    assert!(object["hired"].as_str().unwrap().starts_with("2020-01-"));
+  }
+
+  #[test]
+  fn test_attribute() {
+    let attribute: Attribute = "latency=lognormal(5, 2)".parse().unwrap();
+    assert_eq!(attribute.name, "latency");
+    assert_eq!(
+      attribute.field,
+      Field::LogNormal {
+        mu: 5.0,
+        sigma: 2.0
+      }
+    );
+    let schema: Schema = ["size=uniform(1,100)", "count=int(1,3)", "x=normal(0,1)"]
+      .into_iter()
+      .map(|s| s.parse::<Attribute>().unwrap())
+      .chain([attribute])
+      .collect();
+    let mut rng = StdRng::seed_from_u64(42);
+    for _ in 0..100 {
+      let object = schema.sample(&mut rng);
+      assert!((1.0..=100.0).contains(&object["size"].as_f64().unwrap()));
+      assert!((1..=3).contains(&object["count"].as_i64().unwrap()));
+      assert!(object["latency"].as_f64().unwrap() > 0.0);
+      assert!(object["x"].is_f64());
+    }
+
+    for s in [
+      "size",
+      "size=uniform(1)",
+      "size=uniform(a,b)",
+      "size=poisson(1,2)",
+      "=int(1,2)",
+      "size=uniform(2,1)",
+      "x=normal(0,-1)",
+      "x=uniform(0,inf)",
+      "x=uniform(-1e308,1e308)",
+      "x=normal(nan,1)",
+      "x=lognormal(inf,1)",
+      "x=lognormal(0,inf)",
+    ] {
+      assert!(s.parse::<Attribute>().is_err(), "{s}");
+    }
+  }
+
+  #[test]
+  fn test_invalid() {
+    let result = Schema::from_reader(r#"{ "age": { "int": { "min": 2, "max": 1 } } }"#.as_bytes());
+    assert!(matches!(result, Err(Error::InvalidRange { .. })));
+    let result = Schema::from_reader(r#"{ "team": { "choice": [] } }"#.as_bytes());
+    assert!(matches!(result, Err(Error::EmptyChoice { .. })));
+    let result = Schema::from_reader(r#"{ "age": "float" }"#.as_bytes());
+    assert!(matches!(result, Err(Error::Json { .. })));
+  }
+}
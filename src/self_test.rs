@@ -0,0 +1,197 @@
+use std::fmt::{
+  self,
+  Display,
+  Formatter,
+};
+
+use itertools::{
+  EitherOrBoth,
+  Itertools,
+};
+use uuid::Uuid;
+
+use crate::{
+  generator::{
+    self,
+    Config,
+  },
+  graph::{
+    AcyclicGraph,
+    RenderOptions,
+  },
+};
+
+/// Seeds of the generated graphs
+pub const SEEDS: [u64; 3] = [0, 1, 2];
+
+/// Extension of the golden file of each format
+pub const FORMATS: [&str; 6] = ["dot", "mmd", "html", "jgf", "dimacs", "metis"];
+
+// file names are `seed-{seed}.{format}`, regenerated by `dag self-test --bless
+// src/self_test`
+const GOLDEN: [(&str, &str); 18] = [
+  ("seed-0.dot", include_str!("self_test/seed-0.dot")),
+  ("seed-0.mmd", include_str!("self_test/seed-0.mmd")),
+  ("seed-0.html", include_str!("self_test/seed-0.html")),
+  ("seed-0.jgf", include_str!("self_test/seed-0.jgf")),
+  ("seed-0.dimacs", include_str!("self_test/seed-0.dimacs")),
+  ("seed-0.metis", include_str!("self_test/seed-0.metis")),
+  ("seed-1.dot", include_str!("self_test/seed-1.dot")),
+  ("seed-1.mmd", include_str!("self_test/seed-1.mmd")),
+  ("seed-1.html", include_str!("self_test/seed-1.html")),
+  ("seed-1.jgf", include_str!("self_test/seed-1.jgf")),
+  ("seed-1.dimacs", include_str!("self_test/seed-1.dimacs")),
+  ("seed-1.metis", include_str!("self_test/seed-1.metis")),
+  ("seed-2.dot", include_str!("self_test/seed-2.dot")),
+  ("seed-2.mmd", include_str!("self_test/seed-2.mmd")),
+  ("seed-2.html", include_str!("self_test/seed-2.html")),
+  ("seed-2.jgf", include_str!("self_test/seed-2.jgf")),
+  ("seed-2.dimacs", include_str!("self_test/seed-2.dimacs")),
+  ("seed-2.metis", include_str!("self_test/seed-2.metis")),
+];
+
+/// First line of an output that differs from its golden output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+  pub file: String,
+  /// Starting at 1
+  pub line: usize,
+  /// None when the output is longer than the golden output
+  pub expected: Option<String>,
+  /// None when the output is shorter than the golden output
+  pub actual: Option<String>,
+}
+
+impl Display for Mismatch {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let or_end = |line: &Option<String>| match line {
+      Some(line) => format!("{line:?}"),
+      None => "the end".to_string(),
+    };
+    write!(
+      f,
+      "{}:{}: expected {}, got {}",
+      self.file,
+      self.line,
+      or_end(&self.expected),
+      or_end(&self.actual)
+    )
+  }
+}
+
+/// The graph of a seed, small enough to keep the golden outputs readable
+pub fn graph(seed: u64) -> Result<AcyclicGraph, generator::Error> {
+  let cfg = Config {
+    depth: 4,
+    width_mean: 4.0,
+    width_std_dev: 1.0,
+    child_mean: 2.0,
+    seed,
+    name: Some(format!("self-test {seed}")),
+    ..Default::default()
+  };
+  let mut graph = generator::generate(&cfg)?;
+  // the petname word list is often patched by distributions, the names are
+  // replaced by their rank by UUID, their draws still move the UUIDs
+  let uuids: Vec<Uuid> = graph.nodes().keys().copied().collect();
+  for (i, uuid) in uuids.into_iter().enumerate() {
+    // taken from the graph
+    graph
+      .get_node_mut(uuid)
+      .unwrap()
+      .set_name(format!("node {i}"));
+  }
+  Ok(graph)
+}
+
+/// Every output keyed by the name of its golden file, in the order of
+/// [`SEEDS`] then [`FORMATS`]
+pub fn render() -> Result<Vec<(String, String)>, generator::Error> {
+  let mut outputs = Vec::with_capacity(SEEDS.len() * FORMATS.len());
+  for seed in SEEDS {
+    let graph = graph(seed)?;
+    let options = RenderOptions::default();
+    for format in FORMATS {
+      let output = match format {
+        "dot" => graph.dot_with(options.clone()).to_string(),
+        "mmd" => graph.mermaid_with(options.clone()).to_string(),
+        "html" => graph.html_with(options.clone()).to_string(),
+        "jgf" => graph.jgf_with(options.clone()).to_string(),
+        "dimacs" => graph.dimacs_with(options.clone()).to_string(),
+        "metis" => graph.metis_with(options.clone()).to_string(),
+        _ => unreachable!("unknown self-test format {format}"),
+      };
+      outputs.push((format!("seed-{seed}.{format}"), output));
+    }
+  }
+  Ok(outputs)
+}
+
+/// Compare every output to its embedded golden output, empty when this
+/// platform generates and renders like the one that wrote them
+pub fn check() -> Result<Vec<Mismatch>, generator::Error> {
+  Ok(
+    render()?
+      .into_iter()
+      .zip(GOLDEN)
+      .filter_map(|((file, output), (_, golden))| compare(file, golden, &output))
+      .collect(),
+  )
+}
+
+fn compare(file: String, expected: &str, actual: &str) -> Option<Mismatch> {
+  expected
+    .lines()
+    .zip_longest(actual.lines())
+    .enumerate()
+    .find_map(|(i, lines)| {
+      let (expected, actual) = match lines {
+        EitherOrBoth::Both(expected, actual) if expected == actual => return None,
+        EitherOrBoth::Both(expected, actual) => (Some(expected), Some(actual)),
+        EitherOrBoth::Left(expected) => (Some(expected), None),
+        EitherOrBoth::Right(actual) => (None, Some(actual)),
+      };
+      Some(Mismatch {
+        file: file.clone(),
+        line: i + 1,
+        expected: expected.map(str::to_string),
+        actual: actual.map(str::to_string),
+      })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_golden_names() {
+    let names: Vec<String> = SEEDS
+      .iter()
+      .cartesian_product(FORMATS)
+      .map(|(seed, format)| format!("seed-{seed}.{format}"))
+      .collect();
+    assert_eq!(names, GOLDEN.map(|(name, _)| name.to_string()));
+  }
+
+  #[test]
+  fn test_check() {
+    assert_eq!(check().unwrap(), Vec::new());
+  }
+
+  #[test]
+  fn test_compare() {
+    assert_eq!(compare("a".to_string(), "x\ny\n", "x\ny\n"), None);
+    assert_eq!(
+      compare("a".to_string(), "x\ny\n", "x\nz\n"),
+      Some(Mismatch {
+        file: "a".to_string(),
+        line: 2,
+        expected: Some("y".to_string()),
+        actual: Some("z".to_string()),
+      })
+    );
+    let mismatch = compare("a".to_string(), "x\n", "x\ny\n").unwrap();
+    assert_eq!(mismatch.to_string(), "a:2: expected the end, got \"y\"");
+  }
+}
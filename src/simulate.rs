@@ -0,0 +1,323 @@
+use std::{
+  cmp::Reverse,
+  collections::{
+    BinaryHeap,
+    HashMap,
+  },
+  num::NonZeroUsize,
+};
+
+use itertools::Itertools;
+use rand::Rng;
+use rand_distr::{
+  Distribution,
+  Normal,
+};
+use short_uuid::ShortUuid;
+use snafu::{
+  OptionExt,
+  ResultExt,
+  Snafu,
+  ensure,
+};
+use uuid::Uuid;
+
+use crate::{
+  graph::{
+    AcyclicGraph,
+    NodeData,
+  },
+  i18n::{
+    Lang,
+    Text,
+  },
+  report::Table,
+};
+
+/// Largest mean and standard deviation of the sampled durations, a trillion
+/// time units
+pub const MAX_DURATION: f64 = 1e12;
+
+#[derive(Snafu, Debug)]
+pub enum Error {
+  RandNormalDistribution {
+    source: rand_distr::NormalError,
+  },
+  #[snafu(display("The duration mean {mean} isn't between 0 and {MAX_DURATION}"))]
+  DurationMean {
+    mean: f64,
+  },
+  #[snafu(display("The duration standard deviation {std_dev} isn't between 0 and {MAX_DURATION}"))]
+  DurationStdDev {
+    std_dev: f64,
+  },
+  #[snafu(display(
+    "The durations of a chain or of all the nodes add up beyond {}",
+    u64::MAX
+  ))]
+  DurationOverflow,
+}
+
+/// Duration of each node, the `duration` field of JSON data if it's an
+/// integer, like one generated by an `int` schema field, otherwise sampled
+/// from a normal distribution with a minimum of 1. The mean and the standard
+/// deviation are at most [`MAX_DURATION`].
+pub fn durations(
+  graph: &AcyclicGraph, mean: f64, std_dev: f64, rng: &mut impl Rng,
+) -> Result<HashMap<Uuid, u64>, Error> {
+  ensure!(
+    (0.0..=MAX_DURATION).contains(&mean),
+    DurationMeanSnafu { mean }
+  );
+  ensure!(
+    (0.0..=MAX_DURATION).contains(&std_dev),
+    DurationStdDevSnafu { std_dev }
+  );
+  let dist = Normal::new(mean, std_dev).context(RandNormalDistributionSnafu)?;
+  Ok(
+    graph
+      .nodes()
+      .iter()
+      .map(|(&uuid, node)| {
+        let field = match node.data() {
+          NodeData::Json(json) => json.get("duration").and_then(|duration| duration.as_u64()),
+          _ => None,
+        };
+        let duration = field.unwrap_or_else(|| dist.sample(rng).round().max(1.0) as u64);
+        (uuid, duration)
+      })
+      .collect(),
+  )
+}
+
+/// Result of a list scheduling
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+  pub workers: usize,
+  /// Start time and worker of each node
+  pub starts: HashMap<Uuid, (u64, usize)>,
+  /// Time when the last node finish
+  pub makespan: u64,
+  /// Sum of the durations, the time a single worker would need
+  pub work: u64,
+  /// Longest chain of nodes by duration, no schedule can be shorter
+  pub critical_path: Vec<Uuid>,
+  pub critical_path_length: u64,
+}
+
+/// List scheduling where a ready node with the longest remaining chain is
+/// started first as soon as a worker is free, ties are broken by UUID. Fail
+/// if a chain or the total work takes more than `u64::MAX`.
+pub fn simulate(
+  graph: &AcyclicGraph, durations: &HashMap<Uuid, u64>, workers: NonZeroUsize,
+) -> Result<Schedule, Error> {
+  let order: Vec<Uuid> = graph.topological_generations().flatten().collect();
+  let duration = |uuid: &Uuid| durations.get(uuid).copied().unwrap_or_default();
+
+  // longest chain starting from each node, itself included
+  let mut priorities: HashMap<Uuid, u64> = HashMap::with_capacity(order.len());
+  for uuid in order.iter().rev() {
+    let below = graph.nodes()[uuid]
+      .childs()
+      .iter()
+      .map(|child| priorities[child])
+      .max()
+      .unwrap_or_default();
+    let priority = duration(uuid)
+      .checked_add(below)
+      .context(DurationOverflowSnafu)?;
+    priorities.insert(*uuid, priority);
+  }
+
+  let mut critical_path = Vec::new();
+  let mut next = priorities
+    .iter()
+    .max_by_key(|(uuid, priority)| (**priority, Reverse(**uuid)))
+    .map(|(uuid, _)| *uuid);
+  while let Some(uuid) = next {
+    critical_path.push(uuid);
+    next = graph.nodes()[&uuid]
+      .childs()
+      .iter()
+      .max_by_key(|child| (priorities[*child], Reverse(**child)))
+      .copied();
+  }
+  let critical_path_length = critical_path.first().map_or(0, |uuid| priorities[uuid]);
+
+  let mut in_degrees: HashMap<Uuid, usize> = order.iter().map(|&uuid| (uuid, 0)).collect();
+  for node in graph.nodes().values() {
+    for child in node.childs() {
+      *in_degrees.get_mut(child).unwrap() += 1;
+    }
+  }
+  let mut ready: BinaryHeap<(u64, Reverse<Uuid>)> = in_degrees
+    .iter()
+    .filter(|(_, in_degree)| **in_degree == 0)
+    .map(|(&uuid, _)| (priorities[&uuid], Reverse(uuid)))
+    .collect();
+  let mut free: BinaryHeap<Reverse<usize>> = (0..workers.get()).map(Reverse).collect();
+  let mut running: BinaryHeap<Reverse<(u64, Uuid, usize)>> = BinaryHeap::new();
+  let mut starts = HashMap::with_capacity(order.len());
+  let mut time = 0u64;
+
+  loop {
+    while let Some(Reverse(worker)) = free.pop() {
+      let Some((_, Reverse(uuid))) = ready.pop() else {
+        free.push(Reverse(worker));
+        break;
+      };
+      starts.insert(uuid, (time, worker));
+      // a chain ending here, bounded by the critical path
+      let finish = time
+        .checked_add(duration(&uuid))
+        .context(DurationOverflowSnafu)?;
+      running.push(Reverse((finish, uuid, worker)));
+    }
+
+    let Some(&Reverse((finish, ..))) = running.peek() else {
+      break;
+    };
+    time = finish;
+    // every node finishing now release its childs before the next choice
+    while running.peek().is_some_and(|Reverse(next)| next.0 == time) {
+      let Some(Reverse((_, uuid, worker))) = running.pop() else {
+        break;
+      };
+      free.push(Reverse(worker));
+      for child in graph.nodes()[&uuid].childs() {
+        let in_degree = in_degrees.get_mut(child).unwrap();
+        *in_degree -= 1;
+        if *in_degree == 0 {
+          ready.push((priorities[child], Reverse(*child)));
+        }
+      }
+    }
+  }
+
+  let work = order
+    .iter()
+    .try_fold(0u64, |work, uuid| work.checked_add(duration(uuid)))
+    .context(DurationOverflowSnafu)?;
+  Ok(Schedule {
+    workers: workers.get(),
+    starts,
+    makespan: time,
+    work,
+    critical_path,
+    critical_path_length,
+  })
+}
+
+impl Schedule {
+  // time the workers could have spent, wider than u64 with many workers
+  fn capacity(&self) -> u128 {
+    self.makespan as u128 * self.workers as u128
+  }
+
+  /// Time the workers spent waiting before the makespan
+  pub fn idle(&self) -> u128 {
+    self.capacity() - self.work as u128
+  }
+
+  pub fn table(&self, lang: Lang) -> Table {
+    let mut table = Table::metrics(lang);
+    table.metric(lang.text(Text::Workers), self.workers);
+    table.metric(lang.text(Text::TotalWork), self.work);
+    table.metric(lang.text(Text::Makespan), self.makespan);
+    let capacity = self.capacity().max(1);
+    table.metric(
+      lang.text(Text::IdleTime),
+      format!(
+        "{} ({:.1}%)",
+        self.idle(),
+        self.idle() as f64 * 100.0 / capacity as f64
+      ),
+    );
+    table.metric(
+      lang.text(Text::Speedup),
+      format!("{:.2}", self.work as f64 / self.makespan.max(1) as f64),
+    );
+    table.metric(
+      lang.text(Text::CriticalPathLength),
+      self.critical_path_length,
+    );
+    table.metric(
+      lang.text(Text::CriticalPath),
+      self
+        .critical_path
+        .iter()
+        .map(ShortUuid::from_uuid)
+        .join(" -> "),
+    );
+    table
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_simulate() {
+    // root -> a (5) -> c (1), root -> b (2) -> c
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (root, _) = graph.add_node("root");
+    let (a, _) = graph.add_node("a");
+    let (b, _) = graph.add_node("b");
+    let (c, _) = graph.add_node("c");
+    graph.add_child(root, a).unwrap();
+    graph.add_child(root, b).unwrap();
+    graph.add_child(a, c).unwrap();
+    graph.add_child(b, c).unwrap();
+    let durations = HashMap::from([(root, 1), (a, 5), (b, 2), (c, 1)]);
+
+    let schedule = simulate(&graph, &durations, NonZeroUsize::new(2).unwrap()).unwrap();
+    assert_eq!(schedule.makespan, 7);
+    assert_eq!(schedule.work, 9);
+    assert_eq!(schedule.idle(), 5);
+    assert_eq!(schedule.critical_path, vec![root, a, c]);
+    assert_eq!(schedule.critical_path_length, 7);
+    assert_eq!(schedule.starts[&a].0, 1);
+    assert_eq!(schedule.starts[&b].0, 1);
+    assert_eq!(schedule.starts[&c].0, 6);
+
+    let schedule = simulate(&graph, &durations, NonZeroUsize::new(1).unwrap()).unwrap();
+    assert_eq!(schedule.makespan, 9);
+    assert_eq!(schedule.idle(), 0);
+    // a has the longest chain so it's started before b
+    assert_eq!(schedule.starts[&a].0, 1);
+    assert_eq!(schedule.starts[&b].0, 6);
+  }
+
+  #[test]
+  fn test_duration_field() {
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let (a, _) = graph.add_node(serde_json::json!({ "name": "a", "duration": 42 }));
+    let (b, _) = graph.add_node("b");
+    let mut rng = rand::rng();
+    let durations = durations(&graph, 10.0, 0.0, &mut rng).unwrap();
+    assert_eq!(durations[&a], 42);
+    assert_eq!(durations[&b], 10);
+
+    assert!(matches!(
+      super::durations(&graph, f64::INFINITY, 0.0, &mut rng),
+      Err(Error::DurationMean { .. })
+    ));
+    assert!(matches!(
+      super::durations(&graph, 1e19, 0.0, &mut rng),
+      Err(Error::DurationMean { .. })
+    ));
+    assert!(matches!(
+      super::durations(&graph, 10.0, f64::NAN, &mut rng),
+      Err(Error::DurationStdDev { .. })
+    ));
+
+    // a chain of durations from the data beyond u64::MAX
+    graph.add_child(a, b).unwrap();
+    let durations = HashMap::from([(a, u64::MAX), (b, 1)]);
+    assert!(matches!(
+      simulate(&graph, &durations, NonZeroUsize::new(1).unwrap()),
+      Err(Error::DurationOverflow)
+    ));
+  }
+}
@@ -0,0 +1,177 @@
+use itertools::Itertools;
+
+use crate::{
+  graph::AcyclicGraph,
+  i18n::{
+    Lang,
+    Text,
+  },
+  report::Table,
+};
+
+/// Shape of a graph, to compare the generation parameters to a target shape.
+/// The distributions are numbers of nodes indexed by degree, depth or level.
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct Stats {
+  pub nodes: usize,
+  pub edges: usize,
+  pub roots: usize,
+  pub leaves: usize,
+  /// Number of edges of the longest path
+  pub longest_path: usize,
+  /// Average childs of the nodes with at least one child, like `child_mean`
+  pub average_childs: f64,
+  /// Average width of the levels without the root level, like `width_mean`
+  pub average_width: f64,
+  /// Nodes per number of parents
+  pub in_degrees: Vec<usize>,
+  /// Nodes per number of childs
+  pub out_degrees: Vec<usize>,
+  /// Nodes per length of the longest path from a root
+  pub depths: Vec<usize>,
+  /// Nodes per level, the level recorded by the generator if every node have
+  /// one else the depth, see [`AcyclicGraph::depths`]
+  pub widths: Vec<usize>,
+}
+
+impl Stats {
+  pub fn new(graph: &AcyclicGraph) -> Self {
+    let parents = graph.parents();
+    let in_degrees = histogram(
+      graph
+        .nodes()
+        .keys()
+        .map(|uuid| parents.get(uuid).map_or(0, |parents| parents.len())),
+    );
+    let out_degrees = histogram(graph.nodes().values().map(|node| node.childs().len()));
+    let depths = histogram(graph.iter_topological().map(|(_, _, depth)| depth));
+    let widths = histogram(graph.depths().into_values());
+
+    let edges: usize = out_degrees
+      .iter()
+      .enumerate()
+      .map(|(degree, count)| degree * count)
+      .sum();
+    let leaves = out_degrees.first().copied().unwrap_or(0);
+    let parents = graph.nodes().len() - leaves;
+    let below_root = widths.iter().skip(1);
+    Self {
+      nodes: graph.nodes().len(),
+      edges,
+      roots: in_degrees.first().copied().unwrap_or(0),
+      leaves,
+      longest_path: depths.len().saturating_sub(1),
+      average_childs: edges as f64 / parents.max(1) as f64,
+      average_width: below_root.clone().sum::<usize>() as f64 / below_root.len().max(1) as f64,
+      in_degrees,
+      out_degrees,
+      depths,
+      widths,
+    }
+  }
+
+  /// One line of JSON
+  pub fn json(&self) -> String {
+    // only numbers, can't fail
+    serde_json::to_string(self).unwrap()
+  }
+
+  pub fn table(&self, lang: Lang) -> Table {
+    let mut table = Table::metrics(lang);
+    table.metric(lang.text(Text::Nodes), self.nodes);
+    table.metric(lang.text(Text::Edges), self.edges);
+    table.metric(lang.text(Text::Roots), self.roots);
+    table.metric(lang.text(Text::Leaves), self.leaves);
+    table.metric(lang.text(Text::LongestPath), self.longest_path);
+    table.metric(
+      lang.text(Text::AverageChilds),
+      format!("{:.2}", self.average_childs),
+    );
+    table.metric(
+      lang.text(Text::AverageWidth),
+      format!("{:.2}", self.average_width),
+    );
+    table.metric(lang.text(Text::InDegrees), distribution(&self.in_degrees));
+    table.metric(lang.text(Text::OutDegrees), distribution(&self.out_degrees));
+    table.metric(
+      lang.text(Text::DepthHistogram),
+      self.depths.iter().join(", "),
+    );
+    table.metric(lang.text(Text::LevelWidths), self.widths.iter().join(", "));
+    table
+  }
+}
+
+// the number of values equal to each index
+fn histogram(values: impl IntoIterator<Item = usize>) -> Vec<usize> {
+  let mut counts = Vec::new();
+  for value in values {
+    if counts.len() <= value {
+      counts.resize(value + 1, 0);
+    }
+    counts[value] += 1;
+  }
+  counts
+}
+
+// `degree: nodes` of the degrees some node have, a few degrees are used out
+// of a long range
+fn distribution(counts: &[usize]) -> String {
+  let text = counts
+    .iter()
+    .enumerate()
+    .filter(|(_, count)| **count > 0)
+    .map(|(degree, count)| format!("{degree}: {count}"))
+    .join(", ");
+  match text.is_empty() {
+    true => "-".to_string(),
+    false => text,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_stats() {
+    // root -> a -> c, root -> b -> c, root -> c, c -> d
+    let mut graph = AcyclicGraph::new("Test Graph");
+    let [root, a, b, c, d] = ["Root", "A", "B", "C", "D"].map(|name| graph.add_node(name).0);
+    for (parent, child) in [(root, a), (root, b), (a, c), (b, c), (root, c), (c, d)] {
+      graph.add_child(parent, child).unwrap();
+    }
+
+    let stats = Stats::new(&graph);
+    assert_eq!(
+      stats,
+      Stats {
+        nodes: 5,
+        edges: 6,
+        roots: 1,
+        leaves: 1,
+        longest_path: 3,
+        average_childs: 1.5,
+        average_width: 4.0 / 3.0,
+        in_degrees: vec![1, 3, 0, 1],
+        out_degrees: vec![1, 3, 0, 1],
+        depths: vec![1, 2, 1, 1],
+        widths: vec![1, 2, 1, 1],
+      }
+    );
+    assert_eq!(
+      stats.json(),
+      r#"{"nodes":5,"edges":6,"roots":1,"leaves":1,"longest_path":3,"average_childs":1.5,"average_width":1.3333333333333333,"in_degrees":[1,3,0,1],"out_degrees":[1,3,0,1],"depths":[1,2,1,1],"widths":[1,2,1,1]}"#
+    );
+
+    let mut output = Vec::new();
+    stats.table(Lang::En).write(&mut output, false).unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("  Nodes per in-degree                0: 1, 1: 3, 3: 1\n"));
+    assert!(output.contains("  Nodes per depth                    1, 2, 1, 1\n"));
+
+    let empty = Stats::new(&AcyclicGraph::new("Empty"));
+    assert_eq!((empty.longest_path, empty.average_width), (0, 0.0));
+    assert_eq!(distribution(&empty.in_degrees), "-");
+  }
+}
@@ -0,0 +1,417 @@
+use std::{
+  collections::HashMap,
+  num::NonZeroUsize,
+  ops::RangeInclusive,
+  str::FromStr,
+};
+
+use crate::{
+  generator::{
+    self,
+    Config,
+  },
+  graph::AcyclicGraph,
+  i18n::{
+    Lang,
+    Text,
+  },
+  report::Table,
+};
+
+/// Structural metrics of one generated graph
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Metrics {
+  pub nodes: f64,
+  pub edges: f64,
+  /// Number of levels, root level included
+  pub depth: f64,
+  pub max_width: f64,
+  pub leaves: f64,
+  pub max_out_degree: f64,
+  /// Average childs of the nodes with at least one child
+  pub mean_out_degree: f64,
+}
+
+impl Metrics {
+  const NAMES: [Text; 7] = [
+    Text::Nodes,
+    Text::Edges,
+    Text::Depth,
+    Text::MaxWidth,
+    Text::Leaves,
+    Text::MaxOutDegree,
+    Text::MeanOutDegree,
+  ];
+
+  pub fn new(graph: &AcyclicGraph) -> Self {
+    let mut widths: HashMap<usize, usize> = HashMap::new();
+    for depth in graph.depths().into_values() {
+      *widths.entry(depth).or_default() += 1;
+    }
+    let degrees: Vec<usize> = graph
+      .nodes()
+      .values()
+      .map(|node| node.childs().len())
+      .collect();
+    let edges: usize = degrees.iter().sum();
+    let parents = degrees.iter().filter(|&&degree| degree > 0).count();
+
+    Self {
+      nodes: graph.nodes().len() as f64,
+      edges: edges as f64,
+      depth: widths.len() as f64,
+      max_width: widths.values().copied().max().unwrap_or(0) as f64,
+      leaves: (degrees.len() - parents) as f64,
+      max_out_degree: degrees.iter().copied().max().unwrap_or(0) as f64,
+      mean_out_degree: edges as f64 / parents.max(1) as f64,
+    }
+  }
+
+  fn values(&self) -> [f64; 7] {
+    [
+      self.nodes,
+      self.edges,
+      self.depth,
+      self.max_width,
+      self.leaves,
+      self.max_out_degree,
+      self.mean_out_degree,
+    ]
+  }
+}
+
+/// Summary of the values of a metric over all the runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Statistics {
+  pub mean: f64,
+  /// Population standard deviation
+  pub std_dev: f64,
+  pub min: f64,
+  pub p50: f64,
+  pub p90: f64,
+  pub p99: f64,
+  pub max: f64,
+}
+
+impl Statistics {
+  /// `values` must not be empty
+  pub fn new(mut values: Vec<f64>) -> Self {
+    values.sort_by(f64::total_cmp);
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let variance = values
+      .iter()
+      .map(|value| (value - mean).powi(2))
+      .sum::<f64>()
+      / n;
+    // nearest rank
+    let percentile = |p: f64| values[((p * n).ceil() as usize).clamp(1, values.len()) - 1];
+
+    Self {
+      mean,
+      std_dev: variance.sqrt(),
+      min: values[0],
+      p50: percentile(0.5),
+      p90: percentile(0.9),
+      p99: percentile(0.99),
+      max: values[values.len() - 1],
+    }
+  }
+}
+
+/// Statistics of the metrics of graphs generated with the seeds following the
+/// seed of the config
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sweep {
+  pub runs: usize,
+  pub statistics: Vec<(Text, Statistics)>,
+}
+
+pub fn sweep(cfg: &Config, runs: NonZeroUsize) -> Result<Sweep, generator::Error> {
+  let mut values: Vec<Vec<f64>> = vec![Vec::with_capacity(runs.get()); Metrics::NAMES.len()];
+  let mut cfg = cfg.clone();
+  for _ in 0..runs.get() {
+    let metrics = Metrics::new(&generator::generate(&cfg)?);
+    for (values, value) in values.iter_mut().zip(metrics.values()) {
+      values.push(value);
+    }
+    cfg.seed = cfg.seed.wrapping_add(1);
+  }
+
+  Ok(Sweep {
+    runs: runs.get(),
+    statistics: Metrics::NAMES
+      .into_iter()
+      .zip(values.into_iter().map(Statistics::new))
+      .collect(),
+  })
+}
+
+impl Sweep {
+  pub fn table(&self, lang: Lang) -> Table {
+    let mut table = Table::statistics(lang);
+    for (metric, statistics) in &self.statistics {
+      let mut cells = vec![lang.text(*metric).to_string()];
+      cells.extend(
+        [
+          statistics.mean,
+          statistics.std_dev,
+          statistics.min,
+          statistics.p50,
+          statistics.p90,
+          statistics.p99,
+          statistics.max,
+        ]
+        .map(|value| format!("{value:.2}")),
+      );
+      table.cells(cells);
+    }
+    table
+  }
+}
+
+/// Sweeps of two configs with the same number of runs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+  pub a: Sweep,
+  pub b: Sweep,
+}
+
+/// Sweep both configs to compare the shapes they generate, each from its own
+/// seed
+pub fn compare(a: &Config, b: &Config, runs: NonZeroUsize) -> Result<Comparison, generator::Error> {
+  Ok(Comparison {
+    a: sweep(a, runs)?,
+    b: sweep(b, runs)?,
+  })
+}
+
+impl Comparison {
+  /// Mean and standard deviation of each metric and the difference of the
+  /// means, relative to A
+  pub fn table(&self, lang: Lang) -> Table {
+    let mut table = Table::comparison(lang);
+    for ((metric, a), (_, b)) in self.a.statistics.iter().zip(&self.b.statistics) {
+      let delta = b.mean - a.mean;
+      let relative = if a.mean == 0.0 {
+        "-".to_string()
+      } else {
+        format!("{:+.1}%", delta * 100.0 / a.mean)
+      };
+      table.cells([
+        lang.text(*metric).to_string(),
+        format!("{:.2} ± {:.2}", a.mean, a.std_dev),
+        format!("{:.2} ± {:.2}", b.mean, b.std_dev),
+        format!("{delta:+.2}"),
+        relative,
+      ]);
+    }
+    table
+  }
+}
+
+/// Inclusive range of accepted values, parsed from `n`, `a..b`, `a..=b`,
+/// `a..` or `..b`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interval(RangeInclusive<usize>);
+
+impl FromStr for Interval {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let bound = |bound: &str, default: usize| {
+      if bound.is_empty() {
+        Ok(default)
+      } else {
+        bound
+          .parse::<usize>()
+          .map_err(|e| format!("invalid bound {bound:?}: {e}"))
+      }
+    };
+    let range = if let Some((start, end)) = s.split_once("..=") {
+      bound(start, 0)?..=bound(end, usize::MAX)?
+    } else if let Some((start, end)) = s.split_once("..") {
+      let end = bound(end, usize::MAX)?;
+      bound(start, 0)?..=if end == usize::MAX {
+        end
+      } else {
+        end.checked_sub(1).ok_or("empty range")?
+      }
+    } else {
+      let n = bound(s, 0)?;
+      n..=n
+    };
+    if range.is_empty() {
+      return Err(format!("empty range {s:?}"));
+    }
+    Ok(Self(range))
+  }
+}
+
+impl Interval {
+  fn contains(&self, value: f64) -> bool {
+    self.0.contains(&(value as usize))
+  }
+}
+
+/// Accepted values of the metrics of a graph, `None` accept anything
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Constraints {
+  pub nodes: Option<Interval>,
+  /// Number of levels, root level included
+  pub depth: Option<Interval>,
+  pub leaves: Option<Interval>,
+  pub max_width: Option<Interval>,
+}
+
+impl Constraints {
+  pub fn accept(&self, metrics: &Metrics) -> bool {
+    [
+      (&self.nodes, metrics.nodes),
+      (&self.depth, metrics.depth),
+      (&self.leaves, metrics.leaves),
+      (&self.max_width, metrics.max_width),
+    ]
+    .into_iter()
+    .all(|(interval, value)| {
+      interval
+        .as_ref()
+        .is_none_or(|interval| interval.contains(value))
+    })
+  }
+}
+
+/// First seed from the seed of the config that generate a graph matching the
+/// constraints, `None` if none is found in `tries` seeds
+pub fn find_seed(
+  cfg: &Config, constraints: &Constraints, tries: NonZeroUsize,
+) -> Result<Option<u64>, generator::Error> {
+  let mut cfg = cfg.clone();
+  for _ in 0..tries.get() {
+    if constraints.accept(&Metrics::new(&generator::generate(&cfg)?)) {
+      return Ok(Some(cfg.seed));
+    }
+    cfg.seed = cfg.seed.wrapping_add(1);
+  }
+  Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_statistics() {
+    let statistics = Statistics::new((1..=100).rev().map(f64::from).collect());
+    assert_eq!(statistics.mean, 50.5);
+    assert!((statistics.std_dev - 28.866).abs() < 1e-3);
+    assert_eq!(statistics.min, 1.0);
+    assert_eq!(statistics.p50, 50.0);
+    assert_eq!(statistics.p90, 90.0);
+    assert_eq!(statistics.p99, 99.0);
+    assert_eq!(statistics.max, 100.0);
+
+    let statistics = Statistics::new(vec![3.0]);
+    assert_eq!((statistics.std_dev, statistics.p99), (0.0, 3.0));
+  }
+
+  #[test]
+  fn test_sweep() {
+    let cfg = Config {
+      depth: 3,
+      width_mean: 4.0,
+      width_std_dev: 1.0,
+      child_mean: 2.0,
+      seed: 42,
+      ..Default::default()
+    };
+    let sweep = sweep(&cfg, NonZeroUsize::new(20).unwrap()).unwrap();
+    assert_eq!(sweep.runs, 20);
+    let (metric, depth) = sweep.statistics[2];
+    assert_eq!(metric, Text::Depth);
+    assert!(depth.max <= 3.0);
+    // a generated graph is a tree
+    let (nodes, edges) = (sweep.statistics[0].1, sweep.statistics[1].1);
+    assert_eq!(edges.mean, nodes.mean - 1.0);
+  }
+
+  #[test]
+  fn test_compare() {
+    let a = Config {
+      depth: 3,
+      width_mean: 4.0,
+      width_std_dev: 1.0,
+      child_mean: 2.0,
+      seed: 42,
+      ..Default::default()
+    };
+    let b = Config {
+      depth: 4,
+      ..a.clone()
+    };
+    let comparison = compare(&a, &b, NonZeroUsize::new(10).unwrap()).unwrap();
+    assert_eq!(
+      comparison.a,
+      sweep(&a, NonZeroUsize::new(10).unwrap()).unwrap()
+    );
+    let (_, depth_a) = comparison.a.statistics[2];
+    let (_, depth_b) = comparison.b.statistics[2];
+    assert!(depth_b.mean > depth_a.mean);
+
+    let mut output = Vec::new();
+    comparison
+      .table(Lang::En)
+      .write(&mut output, false)
+      .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.starts_with("  Metric"));
+    assert!(output.contains(&format!("{:+.2}", depth_b.mean - depth_a.mean)));
+  }
+
+  #[test]
+  fn test_interval() {
+    let interval = |s: &str| s.parse::<Interval>().map(|interval| interval.0);
+    assert_eq!(interval("5"), Ok(5..=5));
+    assert_eq!(interval("100..120"), Ok(100..=119));
+    assert_eq!(interval("100..=120"), Ok(100..=120));
+    assert_eq!(interval("30.."), Ok(30..=usize::MAX));
+    assert_eq!(interval("..40"), Ok(0..=39));
+    assert!(interval("5..5").is_err());
+    assert!(interval("..0").is_err());
+    assert!(interval("a..b").is_err());
+  }
+
+  #[test]
+  fn test_find_seed() {
+    let cfg = Config {
+      depth: 4,
+      width_mean: 6.0,
+      width_std_dev: 2.0,
+      child_mean: 2.0,
+      ..Default::default()
+    };
+    let constraints = Constraints {
+      nodes: Some("15..=18".parse().unwrap()),
+      depth: Some("4".parse().unwrap()),
+      ..Constraints::default()
+    };
+    let tries = NonZeroUsize::new(1000).unwrap();
+    let seed = find_seed(&cfg, &constraints, tries).unwrap().unwrap();
+
+    let metrics = Metrics::new(
+      &generator::generate(&Config {
+        seed,
+        ..cfg.clone()
+      })
+      .unwrap(),
+    );
+    assert!((15.0..=18.0).contains(&metrics.nodes));
+    assert_eq!(metrics.depth, 4.0);
+
+    let impossible = Constraints {
+      depth: Some("5..".parse().unwrap()),
+      ..Constraints::default()
+    };
+    assert_eq!(find_seed(&cfg, &impossible, tries).unwrap(), None);
+  }
+}
@@ -0,0 +1,199 @@
+use std::{
+  fmt::{
+    self,
+    Write,
+  },
+  str::FromStr,
+};
+
+use short_uuid::ShortUuid;
+use snafu::Snafu;
+use uuid::Uuid;
+
+use crate::graph::Node;
+
+#[derive(Debug, Snafu, PartialEq)]
+pub enum Error {
+  #[snafu(display("Unknown placeholder {{{name}}} in template"))]
+  UnknownPlaceholder { name: String },
+  #[snafu(display("Unclosed placeholder at byte {position} in template"))]
+  UnclosedPlaceholder { position: usize },
+  #[snafu(display("Unmatched '}}' at byte {position} in template"))]
+  UnmatchedBrace { position: usize },
+}
+
+/// What a placeholder is replaced with when rendering a node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placeholder {
+  /// The node name, empty if the node data is not text
+  Name,
+  /// The node data whatever its kind
+  Data,
+  /// Depth of the node, root is 0
+  Depth,
+  /// The full UUID
+  Uuid,
+  /// The short UUID used as identifier in the exports
+  Id,
+  /// Creation time of the node, empty without timestamps
+  Timestamp,
+}
+
+impl FromStr for Placeholder {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s {
+      "name" => Ok(Self::Name),
+      "data" => Ok(Self::Data),
+      "depth" => Ok(Self::Depth),
+      "uuid" => Ok(Self::Uuid),
+      "id" => Ok(Self::Id),
+      "timestamp" => Ok(Self::Timestamp),
+      _ => UnknownPlaceholderSnafu { name: s }.fail(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+  Literal(String),
+  Placeholder(Placeholder),
+}
+
+/// A tiny template like `"{name} ({data}) d={depth}"`, `{{` and `}}` are
+/// literal braces
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+  segments: Vec<Segment>,
+}
+
+impl FromStr for Template {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = s.char_indices().peekable();
+
+    while let Some((position, c)) = chars.next() {
+      match c {
+        '{' if chars.next_if(|&(_, c)| c == '{').is_some() => literal.push('{'),
+        '}' if chars.next_if(|&(_, c)| c == '}').is_some() => literal.push('}'),
+        '{' => {
+          let start = position + 1;
+          let end = loop {
+            match chars.next() {
+              Some((end, '}')) => break end,
+              Some(_) => {}
+              None => return UnclosedPlaceholderSnafu { position }.fail(),
+            }
+          };
+          if !literal.is_empty() {
+            segments.push(Segment::Literal(std::mem::take(&mut literal)));
+          }
+          segments.push(Segment::Placeholder(s[start..end].trim().parse()?));
+        }
+        '}' => return UnmatchedBraceSnafu { position }.fail(),
+        c => literal.push(c),
+      }
+    }
+    if !literal.is_empty() {
+      segments.push(Segment::Literal(literal));
+    }
+
+    Ok(Self { segments })
+  }
+}
+
+impl Template {
+  pub fn uses(&self, placeholder: Placeholder) -> bool {
+    self.segments.contains(&Segment::Placeholder(placeholder))
+  }
+
+  pub fn write(
+    &self, w: &mut impl Write, uuid: Uuid, node: &Node, depth: Option<usize>,
+  ) -> fmt::Result {
+    for segment in &self.segments {
+      match segment {
+        Segment::Literal(literal) => w.write_str(literal)?,
+        Segment::Placeholder(Placeholder::Name) => w.write_str(node.name().unwrap_or_default())?,
+        Segment::Placeholder(Placeholder::Data) => write!(w, "{}", node.data())?,
+        Segment::Placeholder(Placeholder::Depth) => {
+          if let Some(depth) = depth {
+            write!(w, "{depth}")?
+          }
+        }
+        Segment::Placeholder(Placeholder::Uuid) => write!(w, "{uuid}")?,
+        Segment::Placeholder(Placeholder::Id) => write!(w, "{}", ShortUuid::from_uuid(&uuid))?,
+        Segment::Placeholder(Placeholder::Timestamp) => {
+          if let Some(timestamp) = node.timestamp() {
+            write!(w, "{timestamp}")?
+          }
+        }
+      }
+    }
+    Ok(())
+  }
+
+  pub fn render(&self, uuid: Uuid, node: &Node, depth: Option<usize>) -> String {
+    let mut s = String::new();
+    // writing to a String can't fail
+    let _ = self.write(&mut s, uuid, node, depth);
+    s
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_render() {
+    let template: Template = "{name} ({data}) d={depth} {{{uuid}}}".parse().unwrap();
+    let node = Node::new("fox");
+    let uuid = Uuid::nil();
+    assert_eq!(
+      template.render(uuid, &node, Some(2)),
+      "fox (fox) d=2 {00000000-0000-0000-0000-000000000000}"
+    );
+    assert!(template.uses(Placeholder::Depth));
+    assert!(!template.uses(Placeholder::Id));
+  }
+
+  #[test]
+  fn test_number_data() {
+    let template: Template = "[{name}] {data}".parse().unwrap();
+    assert_eq!(template.render(Uuid::nil(), &Node::new(42), None), "[] 42");
+  }
+
+  #[test]
+  fn test_timestamp() {
+    let template: Template = "{name} at {timestamp}".parse().unwrap();
+    let mut node = Node::new("fox");
+    assert_eq!(template.render(Uuid::nil(), &node, None), "fox at ");
+    node.set_timestamp(crate::schema::Timestamp(86400));
+    assert_eq!(
+      template.render(Uuid::nil(), &node, None),
+      "fox at 1970-01-02T00:00:00Z"
+    );
+  }
+
+  #[test]
+  fn test_errors() {
+    assert_eq!(
+      "{nope}".parse::<Template>(),
+      Err(Error::UnknownPlaceholder {
+        name: "nope".into()
+      })
+    );
+    assert_eq!(
+      "a {name".parse::<Template>(),
+      Err(Error::UnclosedPlaceholder { position: 2 })
+    );
+    assert_eq!(
+      "a } b".parse::<Template>(),
+      Err(Error::UnmatchedBrace { position: 2 })
+    );
+  }
+}
@@ -0,0 +1,438 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use petname::Generator;
+use rand::{
+  Rng,
+  RngCore,
+  seq::index,
+};
+use snafu::{
+  OptionExt,
+  ResultExt,
+  Snafu,
+  ensure,
+};
+use uuid::Uuid;
+
+use crate::{
+  graph::{
+    self,
+    AcyclicGraph,
+    NodeData,
+  },
+  schema::Timestamp,
+};
+
+/// Largest average step of [`timestamps`], a hundred years in seconds
+pub const MAX_TIMESTAMP_STEP: u64 = 100 * 365 * 24 * 3600;
+
+#[derive(Snafu, Debug, PartialEq)]
+pub enum Error {
+  #[snafu(display("The timestamp step {step} is above {MAX_TIMESTAMP_STEP} seconds"))]
+  TimestampStep { step: u64 },
+  #[snafu(display("A timestamp after {} is out of range", last.0))]
+  TimestampOverflow { last: Timestamp },
+  #[snafu(display("Can't rename the node {uuid}"))]
+  Rename { uuid: Uuid, source: graph::Error },
+}
+
+/// Random changes to apply to a graph, counts are capped by what the graph
+/// allows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Mutation {
+  pub remove_nodes: usize,
+  pub remove_edges: usize,
+  pub add_edges: usize,
+}
+
+/// Apply random mutations that keep the graph acyclic, nodes are removed
+/// first, then edges and finally new edges are added. Return the mutations
+/// actually applied.
+pub fn mutate(graph: &mut AcyclicGraph, mutation: Mutation, rng: &mut impl Rng) -> Mutation {
+  let nodes: Vec<Uuid> = graph.nodes().keys().copied().collect();
+  let remove_nodes = mutation.remove_nodes.min(nodes.len());
+  for i in index::sample(rng, nodes.len(), remove_nodes) {
+    // sampled from the graph without repetition
+    graph.remove_node(nodes[i]).unwrap();
+  }
+
+  let edges: Vec<(Uuid, Uuid)> = graph
+    .nodes()
+    .iter()
+    .flat_map(|(&parent, node)| node.childs().iter().map(move |&child| (parent, child)))
+    .sorted()
+    .collect();
+  let remove_edges = mutation.remove_edges.min(edges.len());
+  for i in index::sample(rng, edges.len(), remove_edges) {
+    let (parent, child) = edges[i];
+    graph.remove_child(parent, child).unwrap();
+  }
+
+  // an edge from a node to a later one in a topological order can't create a
+  // cycle, and the order stays valid after adding it
+  let order: Vec<Uuid> = graph.topological_generations().flatten().collect();
+  let edges = edges.len() - remove_edges;
+  let free = order.len() * order.len().saturating_sub(1) / 2 - edges;
+  let add_edges = mutation.add_edges.min(free);
+  let mut added = 0;
+  while added < add_edges {
+    let a = rng.random_range(0..order.len());
+    let b = rng.random_range(0..order.len());
+    if a != b && graph.add_child(order[a.min(b)], order[a.max(b)]).is_ok() {
+      added += 1;
+    }
+  }
+
+  Mutation {
+    remove_nodes,
+    remove_edges,
+    add_edges,
+  }
+}
+
+/// Subtrees to remove from a graph
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Prune {
+  /// Fraction of the non root nodes removed with all their descendants
+  pub fraction: f64,
+  /// Remove every node deeper than this depth
+  pub max_depth: Option<usize>,
+}
+
+/// Remove random subtrees, a subtree is a node with everything reachable from
+/// it even when a descendant have other parents. Return the number of removed
+/// nodes.
+pub fn prune(graph: &mut AcyclicGraph, prune: Prune, rng: &mut impl Rng) -> usize {
+  let nodes = graph.nodes().len();
+
+  if let Some(max_depth) = prune.max_depth {
+    for (uuid, depth) in graph.depths() {
+      if depth > max_depth {
+        graph.remove_node(uuid).unwrap();
+      }
+    }
+  }
+
+  let parents = graph.parents();
+  // by UUID order, the parents are a hash map
+  let candidates: Vec<Uuid> = graph
+    .nodes()
+    .keys()
+    .filter(|uuid| parents.contains_key(uuid))
+    .copied()
+    .collect();
+  let amount = ((prune.fraction * candidates.len() as f64).round() as usize).min(candidates.len());
+  for i in index::sample(rng, candidates.len(), amount) {
+    // may already be removed with the subtree of another candidate
+    if let Ok(levels) = graph.levels(candidates[i]) {
+      for uuid in levels.into_iter().flatten() {
+        graph.remove_node(uuid).unwrap();
+      }
+    }
+  }
+
+  nodes - graph.nodes().len()
+}
+
+/// Give a fresh petname to a fraction of the nodes, UUIDs and edges are kept.
+/// Return the number of renamed nodes. With unique names, see
+/// [`AcyclicGraph::enable_unique_names`], a name already taken is an error.
+pub fn rename(graph: &mut AcyclicGraph, fraction: f64, rng: &mut impl Rng) -> Result<usize, Error> {
+  let petnames = petname::Petnames::default();
+
+  let nodes: Vec<Uuid> = graph.nodes().keys().copied().collect();
+  let amount = ((fraction * nodes.len() as f64).round() as usize).min(nodes.len());
+  for i in index::sample(rng, nodes.len(), amount) {
+    let name = petnames
+      .generate(rng, 1, "_")
+      .unwrap_or_else(|| "renamed".to_string());
+    graph
+      .set_node_name(nodes[i], name)
+      .context(RenameSnafu { uuid: nodes[i] })?;
+  }
+
+  Ok(amount)
+}
+
+/// A copy of the graph with the same edges and levels where the graph name,
+/// the UUIDs and the node data are replaced by fresh petnames and random
+/// UUIDs and the timestamps are dropped, to share a real graph as a benchmark.
+/// Nodes without data stay without data.
+pub fn anonymize(graph: &AcyclicGraph, rng: &mut impl Rng) -> AcyclicGraph {
+  let petnames = petname::Petnames::default();
+  let name = |rng: &mut dyn RngCore, words| {
+    petnames
+      .generate(rng, words, "_")
+      .unwrap_or_else(|| "anonymous".to_string())
+  };
+
+  let mut anonymized = AcyclicGraph::new(name(rng, 2));
+  // by UUID order so the same seed gives the same graph
+  let uuids: Vec<Uuid> = graph.nodes().keys().copied().collect();
+  let mut fresh = HashMap::with_capacity(uuids.len());
+  for uuid in &uuids {
+    let node = &graph.nodes()[uuid];
+    let data = match node.data() {
+      NodeData::None => NodeData::None,
+      _ => NodeData::from(name(rng, 1)),
+    };
+    let (new, _) = anonymized.add_node_with_rng(data, rng);
+    if let Some(level) = node.level() {
+      // just added
+      anonymized.get_node_mut(new).unwrap().set_level(level);
+    }
+    fresh.insert(*uuid, new);
+  }
+  for uuid in &uuids {
+    for child in graph.nodes()[uuid].childs() {
+      // edges of an acyclic graph
+      anonymized.add_child(fresh[uuid], fresh[child]).unwrap();
+    }
+  }
+  anonymized
+}
+
+/// Give each node a creation time, roots are created at `from` and a node is
+/// created on average `step` seconds after its last parent. The step can't be
+/// above [`MAX_TIMESTAMP_STEP`] and the timestamps must stay in range.
+pub fn timestamps(
+  graph: &mut AcyclicGraph, from: Timestamp, step: u64, rng: &mut impl Rng,
+) -> Result<(), Error> {
+  ensure!(step <= MAX_TIMESTAMP_STEP, TimestampStepSnafu { step });
+  let parents = graph.parents();
+  let max_gap = (step.max(1) as i64)
+    .checked_mul(2)
+    .context(TimestampStepSnafu { step })?;
+  let gaps = 1..=max_gap - 1;
+
+  let generations: Vec<Vec<Uuid>> = graph.topological_generations().collect();
+  for uuid in generations.into_iter().flatten() {
+    let timestamp = match parents.get(&uuid) {
+      Some(parents) => {
+        let last = parents
+          .iter()
+          .filter_map(|parent| graph.nodes()[parent].timestamp())
+          .max()
+          .unwrap_or(from);
+        let timestamp = last.0.checked_add(rng.random_range(gaps.clone()));
+        Timestamp(timestamp.context(TimestampOverflowSnafu { last })?)
+      }
+      None => from,
+    };
+    // generations come from the graph
+    graph.get_node_mut(uuid).unwrap().set_timestamp(timestamp);
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{
+    SeedableRng,
+    rngs::StdRng,
+  };
+
+  use super::*;
+  use crate::generator::{
+    self,
+    Config,
+  };
+
+  fn config() -> Config {
+    Config {
+      depth: 4,
+      width_mean: 6.0,
+      width_std_dev: 1.0,
+      child_mean: 2.0,
+      seed: 42,
+      ..Default::default()
+    }
+  }
+
+  fn edges(graph: &AcyclicGraph) -> usize {
+    graph.nodes().values().map(|node| node.childs().len()).sum()
+  }
+
+  #[test]
+  fn test_mutate() {
+    let graph = generator::generate(&config()).unwrap();
+    let mutation = Mutation {
+      remove_nodes: 2,
+      remove_edges: 3,
+      add_edges: 5,
+    };
+
+    let mut mutated = graph.clone();
+    let applied = mutate(&mut mutated, mutation, &mut StdRng::seed_from_u64(7));
+    assert_eq!(applied, mutation);
+    assert_eq!(mutated.nodes().len(), graph.nodes().len() - 2);
+    assert_eq!(mutated.check_invariants(), Ok(()));
+
+    let mut again = graph.clone();
+    mutate(&mut again, mutation, &mut StdRng::seed_from_u64(7));
+    assert_eq!(mutated.nodes(), again.nodes());
+  }
+
+  #[test]
+  fn test_mutate_capped() {
+    let mut graph = generator::generate(&config()).unwrap();
+    let nodes = graph.nodes().len();
+    let applied = mutate(
+      &mut graph,
+      Mutation {
+        add_edges: usize::MAX,
+        ..Mutation::default()
+      },
+      &mut StdRng::seed_from_u64(7),
+    );
+    assert_eq!(applied.add_edges, nodes * (nodes - 1) / 2 - (nodes - 1));
+    assert_eq!(edges(&graph), nodes * (nodes - 1) / 2);
+    assert_eq!(graph.check_invariants(), Ok(()));
+  }
+
+  #[test]
+  fn test_prune_depth() {
+    let mut graph = generator::generate(&config()).unwrap();
+    let nodes = graph.nodes().len();
+    let deep = graph.depths().values().filter(|&&depth| depth > 1).count();
+
+    let removed = prune(
+      &mut graph,
+      Prune {
+        max_depth: Some(1),
+        ..Prune::default()
+      },
+      &mut StdRng::seed_from_u64(7),
+    );
+    assert_eq!(removed, deep);
+    assert_eq!(graph.nodes().len(), nodes - deep);
+    assert!(graph.depths().values().all(|&depth| depth <= 1));
+  }
+
+  #[test]
+  fn test_prune_fraction() {
+    let mut graph = generator::generate(&config()).unwrap();
+    let nodes = graph.nodes().len();
+
+    let removed = prune(
+      &mut graph,
+      Prune {
+        fraction: 0.2,
+        ..Prune::default()
+      },
+      &mut StdRng::seed_from_u64(7),
+    );
+    // at least the sampled nodes are removed
+    assert!(removed >= ((nodes - 1) as f64 * 0.2).round() as usize);
+    assert_eq!(graph.nodes().len(), nodes - removed);
+    assert_eq!(graph.check_invariants(), Ok(()));
+    // removing subtrees keep a single root
+    assert_eq!(graph.nodes().len() - graph.parents().len(), 1);
+
+    let nodes = graph.nodes().len();
+    let removed = prune(
+      &mut graph,
+      Prune {
+        fraction: 1.0,
+        ..Prune::default()
+      },
+      &mut StdRng::seed_from_u64(7),
+    );
+    assert_eq!(removed, nodes - 1);
+    assert_eq!(graph.nodes().len(), 1);
+  }
+
+  #[test]
+  fn test_rename() {
+    let graph = generator::generate(&config()).unwrap();
+    let mut renamed = graph.clone();
+    let amount = rename(&mut renamed, 0.5, &mut StdRng::seed_from_u64(7)).unwrap();
+    assert_eq!(amount, (graph.nodes().len() as f64 * 0.5).round() as usize);
+
+    assert_eq!(renamed.nodes().len(), graph.nodes().len());
+    for (uuid, node) in graph.nodes() {
+      let other = renamed.get_node(*uuid).unwrap();
+      assert_eq!(other.childs(), node.childs());
+      assert_eq!(other.level(), node.level());
+      assert!(other.name().is_some());
+    }
+
+    // the index of the unique names follows the new names
+    let mut unique = AcyclicGraph::new("Unique");
+    let (a, _) = unique.add_node("A");
+    unique.add_node("B");
+    unique.enable_unique_names().unwrap();
+    assert_eq!(
+      rename(&mut unique, 1.0, &mut StdRng::seed_from_u64(8)),
+      Ok(2)
+    );
+    assert_eq!(unique.find_by_name("A"), None);
+    for (uuid, node) in unique.nodes() {
+      assert_eq!(unique.find_by_name(node.name().unwrap()), Some(*uuid));
+    }
+    let name = unique.get_node(a).unwrap().name().unwrap().to_string();
+    unique.add_node("C");
+    assert!(unique.set_node_name(a, "C").is_err());
+    assert_eq!(unique.find_by_name(&name), Some(a));
+  }
+
+  #[test]
+  fn test_anonymize() {
+    let mut graph = generator::generate(&config()).unwrap();
+    let expected = graph.clone();
+    timestamps(&mut graph, Timestamp(0), 60, &mut StdRng::seed_from_u64(7)).unwrap();
+
+    let mut anonymized = anonymize(&graph, &mut StdRng::seed_from_u64(7));
+    assert_eq!(anonymized, anonymize(&graph, &mut StdRng::seed_from_u64(7)));
+    assert!(anonymized.nodes().iter().all(|(uuid, node)| {
+      !graph.nodes().contains_key(uuid) && node.timestamp().is_none() && node.name().is_some()
+    }));
+
+    // the same graph once the names are the same
+    let mut expected = expected;
+    for graph in [&mut anonymized, &mut expected] {
+      let uuids: Vec<Uuid> = graph.nodes().keys().copied().collect();
+      for uuid in uuids {
+        graph.get_node_mut(uuid).unwrap().set_name("node");
+      }
+    }
+    assert!(anonymized.structurally_eq(&expected));
+  }
+
+  #[test]
+  fn test_timestamps() {
+    let mut graph = generator::generate(&config()).unwrap();
+    mutate(
+      &mut graph,
+      Mutation {
+        add_edges: 10,
+        ..Mutation::default()
+      },
+      &mut StdRng::seed_from_u64(7),
+    );
+    timestamps(&mut graph, Timestamp(0), 60, &mut StdRng::seed_from_u64(7)).unwrap();
+
+    for node in graph.nodes().values() {
+      let timestamp = node.timestamp().unwrap();
+      for child in node.childs() {
+        assert!(graph.nodes()[child].timestamp().unwrap() > timestamp);
+      }
+    }
+
+    let mut rng = StdRng::seed_from_u64(7);
+    for step in [u64::MAX, i64::MAX as u64, MAX_TIMESTAMP_STEP + 1] {
+      assert_eq!(
+        timestamps(&mut graph, Timestamp(0), step, &mut rng),
+        Err(Error::TimestampStep { step })
+      );
+    }
+    assert_eq!(
+      timestamps(&mut graph, Timestamp(i64::MAX - 1), 60, &mut rng),
+      Err(Error::TimestampOverflow {
+        last: Timestamp(i64::MAX - 1)
+      })
+    );
+    timestamps(&mut graph, Timestamp(0), MAX_TIMESTAMP_STEP, &mut rng).unwrap();
+  }
+}
@@ -1,18 +1,124 @@
-use std::collections::{
-  HashMap,
-  HashSet,
-  VecDeque,
-};
+use std::collections::HashSet;
 
+use serde::Serialize;
+use serde_json::Value;
 use short_uuid::ShortUuid;
+use snafu::{
+  ResultExt,
+  Snafu,
+};
 use uuid::Uuid;
 
 use crate::{
-  generator::Config,
-  graph::AcyclicGraph,
+  generator::{
+    Config,
+    Shape,
+  },
+  graph::{
+    self,
+    AcyclicGraph,
+  },
+  i18n::{
+    Lang,
+    Text,
+  },
+  report::{
+    Status,
+    Table,
+  },
 };
 
-pub fn validator(graph: &AcyclicGraph, cfg: &Config) -> Result<(), ()> {
+#[derive(Snafu, Debug)]
+pub enum Error {
+  #[snafu(display("The graph has {count} roots instead of 1"))]
+  Roots { count: usize },
+  #[snafu(display("Can't compute the levels of the graph"))]
+  Levels { source: graph::Error },
+  #[snafu(display("A node is reachable by several paths from the root"))]
+  SeveralPaths,
+  #[snafu(display("Failed checks: {}", checks.join(", ")))]
+  Checks { checks: Vec<String> },
+}
+
+/// A check of a [`ValidationReport`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckKind {
+  Roots,
+  WrongRecordedLevel,
+  ChildrenBeforeParent,
+  OnlyOnePath,
+  ExtraEdgeDensity,
+  AverageChilds,
+  MaxDepth,
+  AverageDepth,
+  AverageWidth,
+}
+
+impl CheckKind {
+  pub fn text(self) -> Text {
+    match self {
+      CheckKind::Roots => Text::Roots,
+      CheckKind::WrongRecordedLevel => Text::WrongRecordedLevel,
+      CheckKind::ChildrenBeforeParent => Text::ChildrenBeforeParent,
+      CheckKind::OnlyOnePath => Text::OnlyOnePath,
+      CheckKind::ExtraEdgeDensity => Text::ExtraEdgeDensity,
+      CheckKind::AverageChilds => Text::AverageChilds,
+      CheckKind::MaxDepth => Text::MaxDepth,
+      CheckKind::AverageDepth => Text::AverageDepth,
+      CheckKind::AverageWidth => Text::AverageWidth,
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Check {
+  pub check: CheckKind,
+  pub value: Value,
+  /// None for an informative check, the maximum for [`CheckKind::MaxDepth`]
+  pub expected: Option<Value>,
+  /// Relative gap allowed between a mean and the requested one
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub tolerance: Option<f64>,
+  pub status: Status,
+}
+
+/// The statistics of a graph next to the parameters it was generated with.
+/// The checks stop at the roots when there isn't a single one since the
+/// levels start from it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValidationReport {
+  pub roots: usize,
+  /// Number of levels, root level included
+  pub max_depth: Option<usize>,
+  pub average_depth: Option<f64>,
+  /// Average number of nodes of the levels below the root
+  pub average_width: Option<f64>,
+  /// Average number of childs of the nodes with a child
+  pub average_childs: f64,
+  /// Whether each node is reachable by a single path from the root, only
+  /// checked for a tree
+  pub only_one_path: Option<bool>,
+  /// [`Status::Fail`] if a check failed
+  pub status: Status,
+  pub checks: Vec<Check>,
+  #[serde(skip)]
+  root: Option<Uuid>,
+}
+
+/// Check that a graph follows the parameters it was generated with, without
+/// printing anything. The failed checks are in the error.
+pub fn validate(graph: &AcyclicGraph, cfg: &Config) -> Result<(), Error> {
+  report(graph, cfg, None)?.result()
+}
+
+/// Compute every check of the graph against the parameters it was generated
+/// with. Without `tolerance` the average childs and width are informative,
+/// with it they fail when they are further than that fraction from the
+/// requested means, 0.15 for 15%.
+pub fn report(
+  graph: &AcyclicGraph, cfg: &Config, tolerance: Option<f64>,
+) -> Result<ValidationReport, Error> {
   let childs_count: usize = graph.nodes().values().map(|node| node.childs().len()).sum();
 
   let nodes_with_child_count = graph
@@ -23,74 +129,278 @@ pub fn validator(graph: &AcyclicGraph, cfg: &Config) -> Result<(), ()> {
 
   let average_childs = childs_count as f64 / nodes_with_child_count as f64;
 
-  let parents = graph.parents();
-
-  let roots = roots(graph, &parents);
-  eprintln!("Validation results:");
-  let root = if let [root] = &roots[..] {
-    eprintln!(" - Found root: {}", ShortUuid::from_uuid(root));
-    *root
-  } else {
-    eprintln!("Validation failed: expected 1 root, found {}", roots.len());
-    return Err(());
+  let roots = roots(graph);
+  let mut report = ValidationReport {
+    roots: roots.len(),
+    max_depth: None,
+    average_depth: None,
+    average_width: None,
+    average_childs,
+    only_one_path: None,
+    status: Status::Ok,
+    checks: Vec::new(),
+    root: None,
   };
+  let status = match roots.as_slice() {
+    [root] => {
+      report.root = Some(*root);
+      Status::Ok
+    }
+    _ => Status::Fail,
+  };
+  report.check(CheckKind::Roots, roots.len(), Some(1.into()), status);
+  if let Some(root) = report.root {
+    check(graph, cfg, root, tolerance, &mut report)?;
+  }
+  if report.failed().next().is_some() {
+    report.status = Status::Fail;
+  }
+  Ok(report)
+}
 
-  let levels = levels(graph, root);
-  let depths = depths(&levels);
-  let max_depth = depths.values().copied().max().unwrap_or(0);
-  let average_depth = depths.values().copied().sum::<usize>() as f64 / depths.len() as f64;
+fn check(
+  graph: &AcyclicGraph, cfg: &Config, root: Uuid, tolerance: Option<f64>,
+  report: &mut ValidationReport,
+) -> Result<(), Error> {
+  // every node is reached from the single root, the depth of a node is the
+  // index of its level
+  let levels = graph.levels(root).context(LevelsSnafu)?;
+  let max_depth = levels.len().saturating_sub(1);
+  let (count, total) = levels
+    .iter()
+    .enumerate()
+    .fold((0, 0), |(count, total), (depth, level)| {
+      (count + level.len(), total + depth * level.len())
+    });
+  let average_depth = total as f64 / count as f64;
 
   let average_width = average_width_without_root(&levels);
-  if have_only_one_path(graph, root) {
-    eprintln!(" - Graph have only one path to each node");
-  } else {
-    eprintln!("Validation failed: graph contains multiple paths to some nodes");
-    return Err(());
+  // a DOT import doesn't record the levels
+  if graph.nodes().values().any(|node| node.level().is_some()) {
+    let recorded = levels
+      .iter()
+      .enumerate()
+      .flat_map(|(depth, level)| level.iter().map(move |uuid| (depth, uuid)))
+      .filter(|(depth, uuid)| graph.nodes()[*uuid].level() != Some(*depth))
+      .count();
+    report.check(
+      CheckKind::WrongRecordedLevel,
+      recorded,
+      Some(0.into()),
+      zero(recorded),
+    );
+  }
+  if graph
+    .nodes()
+    .values()
+    .any(|node| node.timestamp().is_some())
+  {
+    let early = graph
+      .nodes()
+      .values()
+      .flat_map(|node| {
+        node
+          .childs()
+          .iter()
+          .map(move |child| (node.timestamp(), graph.nodes()[child].timestamp()))
+      })
+      .filter(|(parent, child)| child <= parent)
+      .count();
+    report.check(
+      CheckKind::ChildrenBeforeParent,
+      early,
+      Some(0.into()),
+      zero(early),
+    );
   }
-  eprintln!(
-    " - Average childs per node with child: {:.2} (expected average {:.2})",
-    average_childs, cfg.child_mean
+  match cfg.shape {
+    Shape::Tree => {
+      let only_one_path = have_only_one_path(graph, root);
+      let status = match only_one_path {
+        true => Status::Ok,
+        false => Status::Fail,
+      };
+      report.check(
+        CheckKind::OnlyOnePath,
+        only_one_path,
+        Some(true.into()),
+        status,
+      );
+      report.only_one_path = Some(only_one_path);
+      // the other checks assume a tree
+      if !only_one_path {
+        return Ok(());
+      }
+    }
+    Shape::Dag => extra_edges(graph, cfg, &levels, report),
+  }
+  report.mean(
+    CheckKind::AverageChilds,
+    report.average_childs,
+    cfg.child_mean,
+    tolerance,
   );
-  eprintln!(" - Max depth expect {} + 1 <= {}", max_depth, cfg.depth);
-  eprintln!(" - Average depth {average_depth:.2}");
-  eprintln!(
-    " - Average width without root level: {:.2} (expected average {:.2})",
-    average_width, cfg.width_mean
+  let status = if max_depth < cfg.depth {
+    Status::Ok
+  } else {
+    Status::Fail
+  };
+  report.check(
+    CheckKind::MaxDepth,
+    max_depth + 1,
+    Some(cfg.depth.into()),
+    status,
   );
-
+  report.check(CheckKind::AverageDepth, average_depth, None, Status::Info);
+  report.mean(
+    CheckKind::AverageWidth,
+    average_width,
+    cfg.width_mean,
+    tolerance,
+  );
+  report.max_depth = Some(max_depth + 1);
+  report.average_depth = Some(average_depth);
+  report.average_width = Some(average_width);
   Ok(())
 }
 
-// could be more simple if we assume root, but this way is more general
-fn depths(levels: &[Vec<Uuid>]) -> HashMap<Uuid, usize> {
-  levels
-    .iter()
-    .enumerate()
-    .flat_map(|(i, level)| level.iter().map(move |&uuid| (uuid, i)))
-    .collect()
+fn zero(count: usize) -> Status {
+  match count {
+    0 => Status::Ok,
+    _ => Status::Fail,
+  }
 }
 
-// level existance mean root exist
-fn levels(graph: &AcyclicGraph, root: Uuid) -> Vec<Vec<Uuid>> {
-  let mut levels = Vec::new();
-  let mut current_level = vec![root];
+impl ValidationReport {
+  pub fn failed(&self) -> impl Iterator<Item = &Check> {
+    self
+      .checks
+      .iter()
+      .filter(|check| check.status == Status::Fail)
+  }
 
-  while !current_level.is_empty() {
-    levels.push(current_level.clone());
-    let mut next_level = Vec::new();
+  /// The first failed check as an error, the checks other than the roots
+  /// and the single path are listed by their English name
+  pub fn result(&self) -> Result<(), Error> {
+    if self.root.is_none() {
+      return RootsSnafu { count: self.roots }.fail();
+    }
+    if self.only_one_path == Some(false) {
+      return SeveralPathsSnafu.fail();
+    }
+    let checks: Vec<String> = self
+      .failed()
+      .map(|check| Lang::En.text(check.check.text()).to_string())
+      .collect();
+    match checks.is_empty() {
+      true => Ok(()),
+      false => ChecksSnafu { checks }.fail(),
+    }
+  }
 
-    for uuid in &current_level {
-      if let Some(node) = graph.nodes().get(uuid) {
-        for child in node.childs() {
-          next_level.push(*child);
-        }
+  pub fn table(&self, lang: Lang) -> Table {
+    let mut table = Table::checks(lang);
+    for check in &self.checks {
+      let mut value = cell(&check.value, lang);
+      if let (CheckKind::Roots, Some(root)) = (check.check, self.root) {
+        value = format!("{value} ({})", ShortUuid::from_uuid(&root));
+      }
+      let mut expected = check
+        .expected
+        .as_ref()
+        .map_or_else(String::new, |expected| cell(expected, lang));
+      if check.check == CheckKind::MaxDepth {
+        expected = format!("<= {expected}");
+      }
+      if let Some(tolerance) = check.tolerance {
+        // 0.15 * 100 isn't exactly 15
+        let percent = (tolerance * 10_000.0).round() / 100.0;
+        expected = format!("{expected} ± {percent}%");
       }
+      table.row(lang.text(check.check.text()), value, expected, check.status);
     }
+    table
+  }
+
+  /// On one line
+  pub fn json(&self) -> String {
+    // only numbers and known strings, can't fail
+    serde_json::to_string(self).unwrap()
+  }
 
-    std::mem::swap(&mut current_level, &mut next_level);
+  fn check(
+    &mut self, check: CheckKind, value: impl Into<Value>, expected: Option<Value>, status: Status,
+  ) {
+    self.checks.push(Check {
+      check,
+      value: value.into(),
+      expected,
+      tolerance: None,
+      status,
+    });
   }
 
-  levels
+  // informative without tolerance
+  fn mean(&mut self, check: CheckKind, value: f64, expected: f64, tolerance: Option<f64>) {
+    let status = match tolerance {
+      Some(tolerance) if (value - expected).abs() <= tolerance * expected => Status::Ok,
+      Some(_) => Status::Fail,
+      None => Status::Info,
+    };
+    self.checks.push(Check {
+      check,
+      value: value.into(),
+      expected: Some(expected.into()),
+      tolerance,
+      status,
+    });
+  }
+}
+
+// the floats with 2 decimals, NaN of an empty mean is serialized as null
+fn cell(value: &Value, lang: Lang) -> String {
+  match value {
+    Value::Bool(true) => lang.text(Text::Yes).to_string(),
+    Value::Bool(false) => lang.text(Text::No).to_string(),
+    Value::Number(number) if number.is_f64() => {
+      format!("{:.2}", number.as_f64().unwrap_or_default())
+    }
+    Value::Null => "-".to_string(),
+    value => value.to_string(),
+  }
+}
+
+// the edges beyond the tree, as many as the density asks unless every pair
+// of nodes on different levels is already linked
+fn extra_edges(
+  graph: &AcyclicGraph, cfg: &Config, levels: &[Vec<Uuid>], report: &mut ValidationReport,
+) {
+  let tree = graph.nodes().len() - 1;
+  let edges: usize = graph.nodes().values().map(|node| node.childs().len()).sum();
+  let mut above = 0;
+  let mut room = 0;
+  for level in levels {
+    room += above * level.len();
+    above += level.len();
+  }
+  let extra = edges.saturating_sub(tree);
+  let expected =
+    ((cfg.extra_edge_density * tree as f64).round() as usize).min(room.saturating_sub(tree));
+  let density = match tree {
+    0 => 0.0,
+    tree => extra as f64 / tree as f64,
+  };
+  let status = if extra == expected {
+    Status::Ok
+  } else {
+    Status::Fail
+  };
+  report.check(
+    CheckKind::ExtraEdgeDensity,
+    density,
+    Some(cfg.extra_edge_density.into()),
+    status,
+  );
 }
 
 fn average_width_without_root(levels: &[Vec<Uuid>]) -> f64 {
@@ -98,32 +408,119 @@ fn average_width_without_root(levels: &[Vec<Uuid>]) -> f64 {
   total_width as f64 / (levels.len() - 1) as f64
 }
 
-fn roots(graph: &AcyclicGraph, parents: &HashMap<Uuid, HashSet<Uuid>>) -> Vec<Uuid> {
+// only the nodes with a parent are kept, the parents themselves are not
+// needed
+fn roots(graph: &AcyclicGraph) -> Vec<Uuid> {
+  let childs: HashSet<&Uuid> = graph
+    .nodes()
+    .values()
+    .flat_map(|node| node.childs())
+    .collect();
   graph
     .nodes()
     .keys()
-    .filter(|uuid| !parents.contains_key(uuid))
+    .filter(|uuid| !childs.contains(uuid))
     .copied()
     .collect()
 }
 
-// BFS to check if each node is reachable by only one path
+// each node is reachable by only one path when the reachable nodes have one
+// edge less than nodes
 fn have_only_one_path(graph: &AcyclicGraph, root: Uuid) -> bool {
-  let mut visited = HashSet::new();
-  let mut queue = VecDeque::new();
-  queue.push_back(root);
-  visited.insert(root);
-  while let Some(current) = queue.pop_front() {
-    if let Ok(node) = graph.get_node(current) {
-      for &child in node.childs() {
-        if visited.insert(child) {
-          queue.push_back(child);
-        } else {
-          return false;
-        }
-      }
-    }
+  let Ok(bfs) = graph.bfs(root) else {
+    return true;
+  };
+  let (nodes, edges) = bfs.fold((0, 0), |(nodes, edges), (_, node, _)| {
+    (nodes + 1, edges + node.childs().len())
+  });
+  edges + 1 == nodes
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::generator::generate;
+
+  #[test]
+  fn test_validate() {
+    let cfg = Config {
+      depth: 3,
+      width_mean: 4.0,
+      width_std_dev: 1.0,
+      child_mean: 2.0,
+      seed: 42,
+      ..Default::default()
+    };
+    let mut graph = generate(&cfg).unwrap();
+    assert!(validate(&graph, &cfg).is_ok());
+
+    let (extra, _) = graph.add_node("Extra");
+    assert!(matches!(
+      validate(&graph, &cfg),
+      Err(Error::Roots { count: 2 })
+    ));
+    graph.remove_node(extra).unwrap();
+    let deeper = Config { depth: 2, ..cfg };
+    let Err(Error::Checks { checks }) = validate(&graph, &deeper) else {
+      panic!("the depth check should fail");
+    };
+    assert_eq!(checks, [Lang::En.text(Text::MaxDepth)]);
   }
 
-  true
+  #[test]
+  fn test_report() {
+    let cfg = Config {
+      depth: 4,
+      width_mean: 4.0,
+      width_std_dev: 0.0,
+      child_mean: 2.0,
+      child_std_dev: 0.0,
+      seed: 42,
+      ..Default::default()
+    };
+    let graph = generate(&cfg).unwrap();
+    let report = report(&graph, &cfg, None).unwrap();
+    assert_eq!(report.status, Status::Ok);
+    assert_eq!(report.roots, 1);
+    assert_eq!(report.only_one_path, Some(true));
+    let average_width = report.average_width.unwrap();
+    let status = |report: &ValidationReport, kind| {
+      report
+        .checks
+        .iter()
+        .find(|check| check.check == kind)
+        .map(|check| check.status)
+    };
+    assert_eq!(status(&report, CheckKind::AverageWidth), Some(Status::Info));
+
+    // the means are checked with a tolerance
+    let report_with = |width_mean| {
+      let cfg = Config {
+        width_mean,
+        ..cfg.clone()
+      };
+      super::report(&graph, &cfg, Some(0.15)).unwrap()
+    };
+    let close = report_with(average_width * 1.1);
+    assert_eq!(status(&close, CheckKind::AverageWidth), Some(Status::Ok));
+    let far = report_with(average_width * 2.0);
+    assert_eq!(status(&far, CheckKind::AverageWidth), Some(Status::Fail));
+    assert_eq!(far.status, Status::Fail);
+    let Err(Error::Checks { checks }) = far.result() else {
+      panic!("the width check should fail");
+    };
+    assert_eq!(checks, [Lang::En.text(Text::AverageWidth)]);
+
+    let json: Value = serde_json::from_str(&far.json()).unwrap();
+    assert_eq!(json["status"], "fail");
+    assert_eq!(json["roots"], 1);
+    let width = json["checks"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .find(|check| check["check"] == "average_width")
+      .unwrap();
+    assert_eq!(width["tolerance"], 0.15);
+    assert_eq!(width["status"], "fail");
+  }
 }